@@ -1,3 +1,4 @@
 pub mod occurs;
 pub mod private_recursion;
+pub mod sorts;
 pub mod tightness;
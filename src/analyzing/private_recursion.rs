@@ -1,12 +1,24 @@
 use {
-    crate::syntax_tree::asp::{Head, Predicate, Program},
+    crate::syntax_tree::asp::{AtomicFormula, ConditionalHead, Head, Predicate, Program, Sign},
     indexmap::IndexSet,
-    petgraph::{algo::is_cyclic_directed, graph::DiGraph},
+    petgraph::{
+        algo::{is_cyclic_directed, tarjan_scc},
+        graph::DiGraph,
+        visit::EdgeRef as _,
+    },
     std::collections::HashMap,
 };
 
 pub trait PrivateRecursion {
     fn has_private_recursion(&self, private_predicates: &IndexSet<Predicate>) -> bool;
+
+    /// The private predicates that are not determined uniquely by the remaining predicates,
+    /// i.e. those in the head of a choice rule and those depending on themselves through a cycle of
+    /// private predicates that is not purely positive (e.g. through negation or conditional literals)
+    fn unprojectable_private_predicates(
+        &self,
+        private_predicates: &IndexSet<Predicate>,
+    ) -> IndexSet<Predicate>;
 }
 
 impl PrivateRecursion for Program {
@@ -50,6 +62,86 @@ impl PrivateRecursion for Program {
 
         is_cyclic_directed(&dependency_graph)
     }
+
+    fn unprojectable_private_predicates(
+        &self,
+        private_predicates: &IndexSet<Predicate>,
+    ) -> IndexSet<Predicate> {
+        let mut result = IndexSet::new();
+
+        for rule in &self.rules {
+            if let Head::Choice(ref a) = rule.head {
+                if private_predicates.contains(&a.predicate()) {
+                    result.insert(a.predicate());
+                }
+            }
+        }
+
+        // The edges are labeled by whether the dependency is positive
+        let mut dependency_graph = DiGraph::<Predicate, bool>::new();
+        let mut mapping = HashMap::new();
+
+        for predicate in self.predicates() {
+            if private_predicates.contains(&predicate) {
+                let node = dependency_graph.add_node(predicate.clone());
+                mapping.insert(predicate, node);
+            }
+        }
+
+        for rule in &self.rules {
+            if let Some(head_predicate) = rule.head.predicate() {
+                if !private_predicates.contains(&head_predicate) {
+                    continue;
+                }
+
+                for literal in &rule.body.formulas {
+                    let positive_predicate = match literal.head {
+                        ConditionalHead::AtomicFormula(AtomicFormula::Literal(ref l))
+                            if literal.basic() && l.sign == Sign::NoSign =>
+                        {
+                            Some(l.atom.predicate())
+                        }
+                        _ => None,
+                    };
+
+                    for body_predicate in literal.predicates() {
+                        if private_predicates.contains(&body_predicate) {
+                            let positive = positive_predicate.as_ref() == Some(&body_predicate);
+                            dependency_graph.add_edge(
+                                mapping[&head_predicate],
+                                mapping[&body_predicate],
+                                positive,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut components = HashMap::new();
+        for (i, component) in tarjan_scc(&dependency_graph).into_iter().enumerate() {
+            for node in component {
+                components.insert(node, i);
+            }
+        }
+
+        let unprojectable_components: IndexSet<_> = dependency_graph
+            .edge_references()
+            .filter(|edge| {
+                !edge.weight() && components[&edge.source()] == components[&edge.target()]
+            })
+            .map(|edge| components[&edge.source()])
+            .collect();
+
+        result.extend(
+            dependency_graph
+                .node_indices()
+                .filter(|node| unprojectable_components.contains(&components[node]))
+                .map(|node| dependency_graph[node].clone()),
+        );
+
+        result
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +181,33 @@ mod tests {
                 .has_private_recursion(&private_predicates))
         }
     }
+
+    #[test]
+    fn test_unprojectable_private_predicates() {
+        let private_predicates: IndexSet<Predicate> = IndexSet::from_iter(
+            ["a/0", "b/0", "p/1"]
+                .into_iter()
+                .map(|p| p.parse().unwrap()),
+        );
+
+        for (program, unprojectable) in [
+            ("a :- not c. c :- not a.", vec![]),
+            ("a :- b. b :- a, not c.", vec![]),
+            ("p(X) :- q(X). p(X) :- p(Y), r(X, Y).", vec![]),
+            ("{a}.", vec!["a/0"]),
+            ("a :- not a.", vec!["a/0"]),
+            ("a :- b. b :- not a. p(X) :- p(X).", vec!["a/0", "b/0"]),
+            ("a :- b : a. b :- a.", vec!["a/0", "b/0"]),
+        ] {
+            let mut result: Vec<_> = Program::from_str(program)
+                .unwrap()
+                .unprojectable_private_predicates(&private_predicates)
+                .into_iter()
+                .map(|p| p.to_string())
+                .collect();
+            result.sort();
+
+            assert_eq!(result, unprojectable, "{program}");
+        }
+    }
 }
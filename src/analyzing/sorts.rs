@@ -0,0 +1,202 @@
+use {
+    crate::syntax_tree::asp::{
+        AtomicFormula, ConditionalHead, Head, Literal, PrecomputedTerm, Predicate, Program,
+        Relation, Rule, Sign, Term, Variable,
+    },
+    indexmap::IndexSet,
+};
+
+pub trait Sorts {
+    /// The arguments of the predicates defined by the program that can only be integers,
+    /// as pairs of a predicate and the position of the argument (starting from 0)
+    ///
+    /// An argument is an integer if in every rule defining the predicate it is a numeral, an arithmetic term, an interval,
+    /// or a variable bound to an integer by the body of the rule.
+    /// The arguments are inferred from the rules without assuming anything about recursive predicates,
+    /// so that every typing lemma follows from the completion of the program.
+    fn integer_arguments(&self) -> IndexSet<(Predicate, usize)>;
+}
+
+impl Sorts for Program {
+    fn integer_arguments(&self) -> IndexSet<(Predicate, usize)> {
+        let mut result = IndexSet::new();
+
+        loop {
+            let mut changed = false;
+
+            for predicate in self.head_predicates() {
+                let rules: Vec<_> = self
+                    .rules
+                    .iter()
+                    .filter(|rule| rule.head.predicate().as_ref() == Some(&predicate))
+                    .collect();
+
+                let integer_variables: Vec<_> = rules
+                    .iter()
+                    .map(|rule| integer_variables(rule, &result))
+                    .collect();
+
+                for position in 0..predicate.arity {
+                    if result.contains(&(predicate.clone(), position)) {
+                        continue;
+                    }
+
+                    let integer = rules
+                        .iter()
+                        .zip(&integer_variables)
+                        .all(|(rule, variables)| {
+                            rule.head
+                                .terms()
+                                .is_some_and(|terms| integer_valued(&terms[position], variables))
+                        });
+
+                    if integer {
+                        result.insert((predicate.clone(), position));
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                return result;
+            }
+        }
+    }
+}
+
+/// Whether every value of the term is an integer, given the variables known to be integers
+fn integer_valued(term: &Term, integer_variables: &IndexSet<Variable>) -> bool {
+    match term {
+        Term::PrecomputedTerm(PrecomputedTerm::Numeral(_)) => true,
+        Term::PrecomputedTerm(_) => false,
+        Term::Variable(variable) => integer_variables.contains(variable),
+        // Arithmetic terms and intervals have no values unless all their arguments are integers
+        Term::UnaryOperation { .. } | Term::BinaryOperation { .. } => true,
+    }
+}
+
+/// The variables occurring within arithmetic terms or intervals
+fn arithmetic_variables(term: &Term) -> IndexSet<Variable> {
+    match term {
+        Term::PrecomputedTerm(_) | Term::Variable(_) => IndexSet::new(),
+        Term::UnaryOperation { .. } | Term::BinaryOperation { .. } => term.variables(),
+    }
+}
+
+/// The variables of the rule that can only be instantiated by integers for the body to be satisfied
+fn integer_variables(
+    rule: &Rule,
+    integer_arguments: &IndexSet<(Predicate, usize)>,
+) -> IndexSet<Variable> {
+    let mut result = IndexSet::new();
+
+    // Conditional literals with conditions are satisfied by instances failing the conditions, so only basic ones bind variables
+    let formulas: Vec<_> = rule
+        .body
+        .formulas
+        .iter()
+        .filter(|formula| formula.basic())
+        .filter_map(|formula| match &formula.head {
+            ConditionalHead::AtomicFormula(formula) => Some(formula),
+            ConditionalHead::Falsity => None,
+        })
+        .collect();
+
+    if let Head::Basic(atom) | Head::Choice(atom) = &rule.head {
+        for term in &atom.terms {
+            result.extend(arithmetic_variables(term));
+        }
+    }
+
+    for formula in &formulas {
+        match formula {
+            AtomicFormula::Literal(literal) => {
+                for term in &literal.atom.terms {
+                    result.extend(arithmetic_variables(term));
+                }
+            }
+            AtomicFormula::Comparison(comparison) => {
+                result.extend(arithmetic_variables(&comparison.lhs));
+                result.extend(arithmetic_variables(&comparison.rhs));
+            }
+        }
+    }
+
+    loop {
+        let size = result.len();
+
+        for formula in &formulas {
+            match formula {
+                AtomicFormula::Literal(Literal {
+                    sign: Sign::NoSign,
+                    atom,
+                }) => {
+                    for (position, term) in atom.terms.iter().enumerate() {
+                        if let Term::Variable(variable) = term {
+                            if integer_arguments.contains(&(atom.predicate(), position)) {
+                                result.insert(variable.clone());
+                            }
+                        }
+                    }
+                }
+                AtomicFormula::Comparison(comparison) if comparison.relation == Relation::Equal => {
+                    for (lhs, rhs) in [
+                        (&comparison.lhs, &comparison.rhs),
+                        (&comparison.rhs, &comparison.lhs),
+                    ] {
+                        if let Term::Variable(variable) = lhs {
+                            if integer_valued(rhs, &result) {
+                                result.insert(variable.clone());
+                            }
+                        }
+                    }
+                }
+                AtomicFormula::Literal(_) | AtomicFormula::Comparison(_) => (),
+            }
+        }
+
+        if result.len() == size {
+            return result;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::Sorts, crate::syntax_tree::asp::Program, std::str::FromStr};
+
+    #[test]
+    fn test_integer_arguments() {
+        for (program, arguments) in [
+            ("p(1). p(a).", vec![]),
+            ("p(1). p(2 + 3).", vec!["p/1 0"]),
+            ("p(X) :- X = 1..5.", vec!["p/1 0"]),
+            ("p(X, Y) :- q(X), Y = 2.", vec!["p/2 1"]),
+            ("p(X, Y) :- q(X), Y = X * 2.", vec!["p/2 0", "p/2 1"]),
+            ("p(X) :- q(X), X > 1.", vec![]),
+            ("p(X) :- q(X), not r(X + 1).", vec!["p/1 0"]),
+            ("p(X) :- q(X) : r(X + 1).", vec![]),
+            ("q(X) :- X = 1..n. p(X) :- q(X).", vec!["q/1 0", "p/1 0"]),
+            ("q(X) :- X = 1..n. p(X) :- not q(X), r(X).", vec!["q/1 0"]),
+            ("p(X) :- p(X). p(1).", vec![]),
+            ("p(X + 1) :- p(X). p(1).", vec!["p/1 0"]),
+            (
+                "{p(X, Y)} :- X = 1..3, Y = X. p(X, a) :- X = 1.",
+                vec!["p/2 0"],
+            ),
+            (
+                "composite(I * J) :- I > 1, J > 1. prime(I) :- I = 2..n, not composite(I).",
+                vec!["composite/1 0", "prime/1 0"],
+            ),
+        ] {
+            let result: Vec<_> = Program::from_str(program)
+                .unwrap()
+                .integer_arguments()
+                .into_iter()
+                .map(|(predicate, position)| format!("{predicate} {position}"))
+                .collect();
+
+            assert_eq!(result, arguments, "{program}");
+        }
+    }
+}
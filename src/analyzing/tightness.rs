@@ -1,37 +1,67 @@
 use {
-    crate::syntax_tree::asp::Program,
-    petgraph::{algo::is_cyclic_directed, graph::DiGraph},
+    crate::syntax_tree::asp::{Predicate, Program},
+    indexmap::IndexSet,
+    petgraph::{
+        algo::{is_cyclic_directed, tarjan_scc},
+        graph::DiGraph,
+    },
     std::collections::HashMap,
 };
 
 pub trait Tightness {
     fn is_tight(&self) -> bool;
+
+    /// The sets of predicates that depend positively on each other,
+    /// i.e. the strongly connected components of the positive dependency graph that contain a cycle
+    fn positive_loops(&self) -> Vec<IndexSet<Predicate>>;
 }
 
 impl Tightness for Program {
     fn is_tight(&self) -> bool {
-        let mut dependency_graph = DiGraph::<(), ()>::new();
-        let mut mapping = HashMap::new();
+        let dependency_graph = positive_dependency_graph(self);
+        !is_cyclic_directed(&dependency_graph)
+    }
 
-        for predicate in self.predicates() {
-            let node = dependency_graph.add_node(());
-            mapping.insert(predicate, node);
-        }
+    fn positive_loops(&self) -> Vec<IndexSet<Predicate>> {
+        let dependency_graph = positive_dependency_graph(self);
+
+        tarjan_scc(&dependency_graph)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || dependency_graph.contains_edge(component[0], component[0])
+            })
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|node| dependency_graph[node].clone())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn positive_dependency_graph(program: &Program) -> DiGraph<Predicate, ()> {
+    let mut dependency_graph = DiGraph::<Predicate, ()>::new();
+    let mut mapping = HashMap::new();
+
+    for predicate in program.predicates() {
+        let node = dependency_graph.add_node(predicate.clone());
+        mapping.insert(predicate, node);
+    }
 
-        for rule in &self.rules {
-            if let Some(head_predicate) = rule.head.predicate() {
-                for positive_body_predicate in rule.body.positive_predicates() {
-                    dependency_graph.update_edge(
-                        mapping[&head_predicate],
-                        mapping[&positive_body_predicate],
-                        (),
-                    );
-                }
+    for rule in &program.rules {
+        if let Some(head_predicate) = rule.head.predicate() {
+            for positive_body_predicate in rule.body.positive_predicates() {
+                dependency_graph.update_edge(
+                    mapping[&head_predicate],
+                    mapping[&positive_body_predicate],
+                    (),
+                );
             }
         }
-
-        !is_cyclic_directed(&dependency_graph)
     }
+
+    dependency_graph
 }
 
 #[cfg(test)]
@@ -58,4 +88,38 @@ mod tests {
             assert!(!Program::from_str(program).unwrap().is_tight())
         }
     }
+
+    #[test]
+    fn test_positive_loops() {
+        for (program, loops) in [
+            ("a :- not b. b :- not a.", vec![]),
+            ("p(X) :- not q(X). q(X) :- p(X).", vec![]),
+            ("a :- a.", vec![vec!["a/0"]]),
+            ("a :- b. b :- a. c :- a, not c.", vec![vec!["a/0", "b/0"]]),
+            (
+                "p :- q, not r. p :- r. r :- p. s :- s.",
+                vec![vec!["s/0"], vec!["p/0", "r/0"]],
+            ),
+        ] {
+            let mut result: Vec<Vec<String>> = Program::from_str(program)
+                .unwrap()
+                .positive_loops()
+                .into_iter()
+                .map(|component| {
+                    let mut component: Vec<_> = component.iter().map(ToString::to_string).collect();
+                    component.sort();
+                    component
+                })
+                .collect();
+            result.sort();
+
+            let mut loops: Vec<Vec<String>> = loops
+                .into_iter()
+                .map(|component| component.into_iter().map(String::from).collect())
+                .collect();
+            loops.sort();
+
+            assert_eq!(result, loops, "{program}");
+        }
+    }
 }
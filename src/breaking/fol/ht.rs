@@ -16,6 +16,7 @@ pub fn break_equivalences_annotated_formula(annotated_formula: AnnotatedFormula)
                 role: annotated_formula.role,
                 direction: annotated_formula.direction,
                 name: format!("{}_{}", annotated_formula.name, i),
+                using: annotated_formula.using.clone(),
                 formula,
             })
             .collect(),
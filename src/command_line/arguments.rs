@@ -1,8 +1,21 @@
 use {
-    clap::{Parser, Subcommand, ValueEnum},
+    crate::{solving::grounding::Constant, syntax_tree::fol::Predicate},
+    clap::{ArgAction, CommandFactory as _, FromArgMatches as _, Parser, Subcommand, ValueEnum},
     std::path::PathBuf,
 };
 
+/// The exit codes of commands invoking a prover
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  every problem was proven
+  1  an unexpected error occurred (e.g., a file could not be read or parsed)
+  2  the command line arguments are invalid
+  3  some problem could not be proven (e.g., the prover gave up)
+  4  some problem could not be proven within the time limit
+  5  the prover could not be run or its output could not be understood
+  6  the input files do not constitute a valid task (e.g., the program is not tight or the axioms are contradictory)
+  7  a pre-check found a counterexample to the claim";
+
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Arguments {
@@ -10,6 +23,39 @@ pub struct Arguments {
     pub command: Command,
 }
 
+impl Arguments {
+    /// The command line interface, where the flags of the configurable commands `verify` and `derive`
+    /// also accept an explicit value (e.g. `--no-simplify=false`) to override a project configuration
+    pub fn configurable_command() -> clap::Command {
+        let explicit_flags = |command: clap::Command| {
+            command.mut_args(|arg| match arg.get_action() {
+                ArgAction::SetTrue => arg
+                    .action(ArgAction::Set)
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("true")
+                    .default_value("false"),
+                _ => arg,
+            })
+        };
+
+        Arguments::command()
+            .mut_subcommand("verify", explicit_flags)
+            .mut_subcommand("derive", explicit_flags)
+    }
+
+    /// Parse the given arguments with the configurable command line interface, exiting on errors
+    pub fn parse_configurable<I, T>(args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let mut command = Arguments::configurable_command();
+        let matches = command.clone().get_matches_from(args);
+        Arguments::from_arg_matches(&matches).unwrap_or_else(|e| e.format(&mut command).exit())
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Analyze a property of a given answer set program or first-order theory
@@ -23,17 +69,16 @@ pub enum Command {
     },
 
     /// Sequentially derive a series of lemmas from a set of assumptions
+    #[command(after_help = EXIT_CODES, args_override_self = true)]
     Derive {
-        /// The proof outline file (lemmas and definitions)
-        outline: PathBuf,
-
-        /// The user guide defining placeholders and assumptions
-        user_guide: PathBuf,
-
         /// The decomposition strategy to use
         #[arg(long, value_enum, default_value_t)]
         task_decomposition: TaskDecomposition,
 
+        /// The direction of the derivation
+        #[arg(long, value_enum, default_value_t)]
+        direction: Direction,
+
         /// Omit simplifications
         #[arg(long, action)]
         no_simplify: bool,
@@ -50,6 +95,10 @@ pub enum Command {
         #[arg(long, action)]
         no_proof_search: bool,
 
+        /// Check that the axioms of every problem are consistent before the proof search
+        #[arg(long, action)]
+        check_consistency: bool,
+
         /// The number of prover instances to spawn
         #[arg(long, short = 'n', default_value_t = 1)]
         prover_instances: usize,
@@ -62,9 +111,120 @@ pub enum Command {
         #[arg(long, action)]
         no_timing: bool,
 
+        /// The format of the output
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
+
         /// The destination directory for the problem files
         #[arg(long)]
         out_dir: Option<PathBuf>,
+
+        /// The project configuration file providing default arguments
+        /// [default: the anthem.toml within a given directory]
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// The profile of the project configuration to apply
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Replay the prover outcomes recorded in the given directory instead of running a prover
+        #[arg(long, conflicts_with = "record")]
+        replay: Option<PathBuf>,
+
+        /// Record the prover outcomes in the given directory for later replay
+        #[arg(long)]
+        record: Option<PathBuf>,
+
+        /// Reuse the proven problems recorded in the given cache file and record the new outcomes in it
+        #[arg(long)]
+        cache: Option<PathBuf>,
+
+        /// The user guide defining placeholders and assumptions (may be given multiple times)
+        #[arg(long)]
+        user_guide: Vec<PathBuf>,
+
+        /// The proof outline containing lemmas and definitions (may be given multiple times)
+        #[arg(long)]
+        proof_outline: Vec<PathBuf>,
+
+        /// A set of files from which to construct the derivation, including
+        ///
+        ///   - a proof outline (lemmas and definitions) and
+        ///   - a user guide defining placeholders and assumptions.
+        ///
+        /// Two files without the extensions .po and .ug are taken as the proof outline and the user guide, in this order.
+        #[arg(verbatim_doc_comment)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Evaluate the formulas of a specification on a finite structure
+    Evaluate {
+        /// Assign a value to a placeholder, e.g. `-c n=10` (may be given multiple times)
+        #[arg(long = "const", short = 'c', value_name = "NAME=VALUE")]
+        placeholders: Vec<Constant>,
+
+        /// The specification to evaluate
+        specification: PathBuf,
+
+        /// The files containing the facts that define the extensions of the predicates
+        structure: Vec<PathBuf>,
+    },
+
+    /// Develop a proof outline step by step in a proof session reading commands from stdin
+    #[command(after_help = EXIT_CODES)]
+    Prove {
+        /// Show prompts and keep the session open after a command fails
+        #[arg(long, action)]
+        interactive: bool,
+
+        /// The direction of the proof
+        #[arg(long, value_enum, default_value_t = Direction::Forward)]
+        direction: Direction,
+
+        /// The time limit in seconds to prove each problem passed to a prover
+        #[arg(long, short, default_value_t = 30)]
+        time_limit: usize,
+
+        /// The number of prover instances to spawn
+        #[arg(long, short = 'n', default_value_t = 1)]
+        prover_instances: usize,
+
+        /// The number of threads each prover may use
+        #[arg(long, short = 'm', default_value_t = 1)]
+        prover_cores: usize,
+
+        /// Omit timing information
+        #[arg(long, action)]
+        no_timing: bool,
+
+        /// Replay the prover outcomes recorded in the given directory instead of running a prover
+        #[arg(long)]
+        replay: Option<PathBuf>,
+
+        /// The specification of an external equivalence claim (either a program or a first-order specification)
+        #[arg(long)]
+        spec: Option<PathBuf>,
+
+        /// The program of an external equivalence claim
+        #[arg(long)]
+        program: Option<PathBuf>,
+
+        /// The user guide defining placeholders and assumptions (may be given multiple times)
+        #[arg(long)]
+        user_guide: Vec<PathBuf>,
+
+        /// The proof outline whose steps are accepted at the start of the session (may be given multiple times)
+        #[arg(long)]
+        proof_outline: Vec<PathBuf>,
+
+        /// A set of files from which to construct the session, including
+        ///
+        ///   - a user guide defining placeholders and assumptions,
+        ///   - optionally, a specification and a program of an external equivalence claim, and
+        ///   - optionally, a proof outline.
+        #[arg(verbatim_doc_comment)]
+        files: Vec<PathBuf>,
     },
 
     /// Simplify a first-order theory
@@ -77,6 +237,20 @@ pub enum Command {
         input: Option<PathBuf>,
     },
 
+    /// Compute the stable models of a small answer set program with the built-in grounder and solver
+    Solve {
+        /// Replace a symbolic constant by a value, e.g. `-c n=10` (may be given multiple times)
+        #[arg(long = "const", short = 'c', value_name = "NAME=VALUE")]
+        constants: Vec<Constant>,
+
+        /// The maximal number of stable models to compute, 0 computes all of them
+        #[arg(long, short = 'n', default_value_t = 1)]
+        models: usize,
+
+        /// The files containing the program and its facts [default: stdin]
+        files: Vec<PathBuf>,
+    },
+
     /// Translate a given answer set program or first-order theory
     Translate {
         /// The translation to use
@@ -88,6 +262,7 @@ pub enum Command {
     },
 
     /// Create and optionally verify a set of problem files from a claim about answer set programs or first-order theories
+    #[command(after_help = EXIT_CODES, args_override_self = true)]
     Verify {
         /// The equivalence theory used to proof the claim
         #[arg(long, value_enum)]
@@ -105,10 +280,23 @@ pub enum Command {
         #[arg(long, value_enum, default_value_t)]
         direction: Direction,
 
-        /// Bypass the tightness checks during verification of external equivalence
+        /// The predicates (e.g., `p/1,q/0`) that may occur in the context of a strong equivalence claim
+        /// [default: the public predicates of a given user guide, or all predicates otherwise]
+        #[arg(long, value_delimiter = ',')]
+        context: Option<Vec<Predicate>>,
+
+        /// Search for a counterexample to an external equivalence claim before the proof search
+        #[arg(long, value_enum)]
+        pre_check: Option<PreCheck>,
+
+        /// Use the plain completion for non-tight programs (sound only for locally tight programs)
         #[arg(long, action)]
         bypass_tightness: bool,
 
+        /// Prove the typing lemmas inferred for the integer arguments of program predicates first and use them as axioms
+        #[arg(long, action)]
+        typing_lemmas: bool,
+
         /// Omit simplifications
         #[arg(long, action)]
         no_simplify: bool,
@@ -121,10 +309,18 @@ pub enum Command {
         #[arg(long, action)]
         no_proof_search: bool,
 
+        /// Check that the axioms of every problem are consistent before the proof search
+        #[arg(long, action)]
+        check_consistency: bool,
+
         /// Omit display of system runtimes
         #[arg(long, action)]
         no_timing: bool,
 
+        /// The format of the output
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
+
         /// The time limit in seconds to prove each problem passed to a prover
         #[arg(long, short, default_value_t = 60)]
         time_limit: usize,
@@ -141,6 +337,59 @@ pub enum Command {
         #[arg(long)]
         save_problems: Option<PathBuf>,
 
+        /// The project configuration file providing default arguments
+        /// [default: the anthem.toml within a given directory]
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// The profile of the project configuration to apply
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Replay the prover outcomes recorded in the given directory instead of running a prover
+        #[arg(long, conflicts_with = "record")]
+        replay: Option<PathBuf>,
+
+        /// Record the prover outcomes in the given directory for later replay
+        #[arg(long)]
+        record: Option<PathBuf>,
+
+        /// Reuse the proven problems recorded in the given cache file and record the new outcomes in it
+        #[arg(long)]
+        cache: Option<PathBuf>,
+
+        /// Try candidate lemmas on the problems that could not be proven and write the helpful ones to the given proof outline
+        #[arg(long, value_name = "OUTLINE")]
+        suggest_lemmas: Option<PathBuf>,
+
+        /// The time limit in seconds to prove each candidate lemma
+        #[arg(long, default_value_t = 5)]
+        suggestion_time_limit: usize,
+
+        /// The left program of a strong or intuitionistic equivalence claim
+        #[arg(long)]
+        left: Option<PathBuf>,
+
+        /// The right program of a strong or intuitionistic equivalence claim
+        #[arg(long)]
+        right: Option<PathBuf>,
+
+        /// The specification of an external equivalence claim (either a program or a first-order specification)
+        #[arg(long)]
+        spec: Option<PathBuf>,
+
+        /// The program of an external equivalence claim
+        #[arg(long)]
+        program: Option<PathBuf>,
+
+        /// The user guide of an external equivalence claim (may be given multiple times)
+        #[arg(long)]
+        user_guide: Vec<PathBuf>,
+
+        /// The proof outline of an external equivalence claim (may be given multiple times)
+        #[arg(long)]
+        proof_outline: Vec<PathBuf>,
+
         /// A set of files from which to construct the claim, including
         ///
         ///   - a specification of intended behavior,
@@ -187,6 +436,13 @@ pub enum Equivalence {
     Strong,
     External,
     Intuitionistic,
+    Uniform,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum PreCheck {
+    /// Compare the outputs on all small input instances satisfying the assumptions
+    Bounded,
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -196,15 +452,44 @@ pub enum TaskDecomposition {
     Sequential,
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// One JSON record per line (JSON Lines)
+    Json,
+}
+
 pub use crate::syntax_tree::fol::Direction;
 
 #[cfg(test)]
 mod tests {
-    use super::Arguments;
+    use super::{Arguments, Command};
 
     #[test]
     fn verify() {
         use clap::CommandFactory as _;
         Arguments::command().debug_assert()
     }
+
+    #[test]
+    fn explicit_flags() {
+        Arguments::configurable_command().debug_assert();
+
+        for (args, expected) in [
+            (&["--no-simplify"][..], true),
+            (&["--no-simplify", "--no-simplify=false"], false),
+            (&["--no-simplify=false", "--no-simplify"], true),
+            (&[], false),
+        ] {
+            let args = ["anthem", "verify", "--equivalence", "strong"]
+                .iter()
+                .chain(args);
+            match Arguments::parse_configurable(args).command {
+                Command::Verify { no_simplify, .. } => assert_eq!(no_simplify, expected),
+                _ => unreachable!(),
+            }
+        }
+    }
 }
@@ -0,0 +1,287 @@
+use {
+    crate::command_line::files::FileRoles,
+    anyhow::{anyhow, bail, Context, Result},
+    serde::Deserialize,
+    std::{
+        ffi::OsString,
+        fs,
+        path::{Path, PathBuf},
+    },
+    toml::{Table, Value},
+};
+
+/// The name of the project configuration file
+pub const CONFIG_FILE_NAME: &str = "anthem.toml";
+
+/// Options whose values are paths, these are resolved relative to the configuration file
+const PATH_OPTIONS: [&str; 12] = [
+    "out-dir",
+    "cache",
+    "suggest-lemmas",
+    "record",
+    "replay",
+    "save-problems",
+    "left",
+    "right",
+    "spec",
+    "program",
+    "user-guide",
+    "proof-outline",
+];
+
+/// The command line options of a single command, together with named profiles overriding them
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Options {
+    #[serde(flatten)]
+    pub defaults: Table,
+    pub profiles: Table,
+}
+
+/// The contents of an `anthem.toml` project configuration file
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub files: FileRoles,
+    pub verify: Options,
+    pub derive: Options,
+}
+
+impl Config {
+    /// Read a configuration file, relative paths within it are resolved relative to its directory
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("could not read file `{}`", path.display()))?;
+        let config: Config = toml::from_str(&content)
+            .with_context(|| format!("could not parse file `{}`", path.display()))?;
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        Ok(config.resolve(directory))
+    }
+
+    fn resolve(mut self, directory: &Path) -> Self {
+        self.files = self.files.resolve(directory);
+
+        let mut tables = vec![&mut self.verify.defaults, &mut self.derive.defaults];
+        for options in [&mut self.verify.profiles, &mut self.derive.profiles] {
+            tables.extend(
+                options
+                    .iter_mut()
+                    .filter_map(|(_, profile)| profile.as_table_mut()),
+            );
+        }
+
+        for table in tables {
+            for option in PATH_OPTIONS {
+                let paths = match table.get_mut(option) {
+                    Some(Value::Array(values)) => values.iter_mut().collect(),
+                    Some(value) => vec![value],
+                    None => vec![],
+                };
+
+                for path in paths {
+                    if let Value::String(path) = path {
+                        *path = directory.join(&path).to_string_lossy().into_owned();
+                    }
+                }
+            }
+        }
+
+        self
+    }
+
+    /// The command line arguments corresponding to the options of the given command and profile
+    ///
+    /// The options of the profile replace the defaults, and the options given on the command line replace both,
+    /// so that e.g. a list of values given on the command line is not extended by the configured one.
+    pub fn arguments(
+        &self,
+        command: &str,
+        profile: Option<&str>,
+        given: &[String],
+    ) -> Result<Vec<OsString>> {
+        let options = match command {
+            "verify" => &self.verify,
+            "derive" => &self.derive,
+            _ => bail!("the command `{command}` cannot be configured"),
+        };
+
+        let mut table = options.defaults.clone();
+
+        if let Some(profile) = profile {
+            let overrides = options
+                .profiles
+                .get(profile)
+                .ok_or_else(|| anyhow!("the profile `{profile}` is not defined for `{command}`"))?
+                .as_table()
+                .ok_or_else(|| anyhow!("the profile `{profile}` is not a table"))?;
+            table.extend(overrides.clone());
+        }
+
+        table.retain(|key, _| !given.iter().any(|option| option == key));
+
+        table_to_arguments(&table)
+    }
+}
+
+/// Translate a table of options into command line arguments
+fn table_to_arguments(table: &Table) -> Result<Vec<OsString>> {
+    let mut arguments = vec![];
+
+    for (key, value) in table {
+        let values = match value {
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+
+        for value in values {
+            match value {
+                Value::Boolean(value) => arguments.push(format!("--{key}={value}").into()),
+                Value::String(value) => {
+                    arguments.push(format!("--{key}").into());
+                    arguments.push(value.into());
+                }
+                Value::Integer(value) => {
+                    arguments.push(format!("--{key}").into());
+                    arguments.push(value.to_string().into());
+                }
+                value => bail!(
+                    "the option `{key}` has an unsupported value: `{value}` (expected a boolean, string or integer)"
+                ),
+            }
+        }
+    }
+
+    Ok(arguments)
+}
+
+/// Find the configuration file within the given paths (either the file itself or its directory)
+pub fn find<'a>(paths: impl IntoIterator<Item = &'a PathBuf>) -> Option<PathBuf> {
+    paths.into_iter().find_map(|path| {
+        if path.is_dir() {
+            let candidate = path.join(CONFIG_FILE_NAME);
+            candidate.is_file().then_some(candidate)
+        } else if path
+            .file_name()
+            .is_some_and(|name| name == CONFIG_FILE_NAME)
+        {
+            Some(path.clone())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::Config,
+        crate::command_line::files::FileRoles,
+        std::{ffi::OsString, path::PathBuf},
+    };
+
+    #[test]
+    fn parse_config() {
+        let config: Config = toml::from_str::<Config>(
+            r#"
+            [files]
+            specification = "primes.1.lp"
+            program = "primes.2.lp"
+            user-guide = "primes.ug"
+
+            [verify]
+            equivalence = "external"
+            time-limit = 120
+            bypass-tightness = true
+            no-simplify = false
+            save-problems = "problems"
+
+            [verify.profiles.quick]
+            time-limit = 10
+            direction = "forward"
+            "#,
+        )
+        .unwrap()
+        .resolve(&PathBuf::from("project"));
+
+        assert_eq!(
+            config.files,
+            FileRoles {
+                specification: Some("project/primes.1.lp".into()),
+                program: Some("project/primes.2.lp".into()),
+                user_guide: vec!["project/primes.ug".into()],
+                ..FileRoles::default()
+            }
+        );
+
+        assert_eq!(
+            config.arguments("verify", None, &[]).unwrap(),
+            [
+                "--equivalence",
+                "external",
+                "--time-limit",
+                "120",
+                "--bypass-tightness=true",
+                "--no-simplify=false",
+                "--save-problems",
+                "project/problems",
+            ]
+            .map(OsString::from)
+        );
+
+        assert_eq!(
+            config.arguments("verify", Some("quick"), &[]).unwrap(),
+            [
+                "--equivalence",
+                "external",
+                "--time-limit",
+                "10",
+                "--bypass-tightness=true",
+                "--no-simplify=false",
+                "--save-problems",
+                "project/problems",
+                "--direction",
+                "forward",
+            ]
+            .map(OsString::from)
+        );
+
+        assert_eq!(
+            config
+                .arguments(
+                    "verify",
+                    Some("quick"),
+                    &["time-limit".to_string(), "bypass-tightness".to_string()]
+                )
+                .unwrap(),
+            [
+                "--equivalence",
+                "external",
+                "--no-simplify=false",
+                "--save-problems",
+                "project/problems",
+                "--direction",
+                "forward",
+            ]
+            .map(OsString::from)
+        );
+
+        assert!(config.arguments("verify", Some("slow"), &[]).is_err());
+        assert!(config.arguments("derive", None, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reject_invalid_config() {
+        for config in [
+            "[files]\nspec = \"a.spec\"",
+            "[verify]\ntime-limit = 1.5",
+            "[unknown]",
+        ] {
+            let result = toml::from_str::<Config>(config)
+                .map_err(anyhow::Error::from)
+                .and_then(|config| config.arguments("verify", None, &[]));
+            assert!(result.is_err(), "{config}");
+        }
+    }
+}
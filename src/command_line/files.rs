@@ -1,9 +1,122 @@
 use {
+    crate::{command_line::config::CONFIG_FILE_NAME, convenience::with_warnings::WithWarnings},
     either::Either,
-    std::{ffi::OsStr, path::PathBuf},
+    serde::{Deserialize, Deserializer},
+    std::{
+        ffi::OsStr,
+        fs,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
     walkdir::WalkDir,
 };
 
+#[derive(Error, Debug)]
+pub enum FilesWarning {
+    #[error("the file `{}` is not used since its function could not be determined", .0.display())]
+    UnusedFile(PathBuf),
+}
+
+/// Explicitly assigned roles of the files involved in a claim
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FileRoles {
+    pub left: Option<PathBuf>,
+    pub right: Option<PathBuf>,
+    pub specification: Option<PathBuf>,
+    pub program: Option<PathBuf>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub user_guide: Vec<PathBuf>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub proof_outline: Vec<PathBuf>,
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<PathBuf>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(PathBuf),
+        Many(Vec<PathBuf>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(path) => vec![path],
+        OneOrMany::Many(paths) => paths,
+    })
+}
+
+impl FileRoles {
+    /// Resolve all relative paths relative to the given directory
+    pub fn resolve(self, directory: &Path) -> Self {
+        let resolve = |path: Option<PathBuf>| path.map(|path| directory.join(path));
+        let resolve_all =
+            |paths: Vec<PathBuf>| paths.into_iter().map(|path| directory.join(path)).collect();
+
+        FileRoles {
+            left: resolve(self.left),
+            right: resolve(self.right),
+            specification: resolve(self.specification),
+            program: resolve(self.program),
+            user_guide: resolve_all(self.user_guide),
+            proof_outline: resolve_all(self.proof_outline),
+        }
+    }
+
+    /// Override the roles with those assigned by `other`
+    pub fn merge(self, other: FileRoles) -> Self {
+        let merge_all = |paths: Vec<PathBuf>, overrides: Vec<PathBuf>| {
+            if overrides.is_empty() {
+                paths
+            } else {
+                overrides
+            }
+        };
+
+        FileRoles {
+            left: other.left.or(self.left),
+            right: other.right.or(self.right),
+            specification: other.specification.or(self.specification),
+            program: other.program.or(self.program),
+            user_guide: merge_all(self.user_guide, other.user_guide),
+            proof_outline: merge_all(self.proof_outline, other.proof_outline),
+        }
+    }
+
+    /// The roles of the original form `derive <OUTLINE> <USER_GUIDE>`, which assigns them by position
+    ///
+    /// The form only applies to exactly two files whose extensions do not indicate other roles.
+    pub fn derivation_by_position(paths: &[PathBuf]) -> Self {
+        let extension =
+            |path: &PathBuf| path.extension().and_then(OsStr::to_str).map(str::to_owned);
+
+        match paths {
+            [outline, user_guide]
+                if outline.is_file()
+                    && user_guide.is_file()
+                    && !matches!(extension(outline).as_deref(), Some("lp" | "spec" | "ug"))
+                    && !matches!(extension(user_guide).as_deref(), Some("lp" | "spec" | "po")) =>
+            {
+                FileRoles {
+                    user_guide: vec![user_guide.clone()],
+                    proof_outline: vec![outline.clone()],
+                    ..FileRoles::default()
+                }
+            }
+            _ => FileRoles::default(),
+        }
+    }
+
+    fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.left
+            .iter()
+            .chain(self.right.iter())
+            .chain(self.specification.iter())
+            .chain(self.program.iter())
+            .chain(self.user_guide.iter())
+            .chain(self.proof_outline.iter())
+    }
+}
+
 #[derive(Debug)]
 pub struct Files {
     pub specifications: Vec<PathBuf>,
@@ -11,6 +124,8 @@ pub struct Files {
     pub user_guides: Vec<PathBuf>,
     pub proof_outlines: Vec<PathBuf>,
     pub other: Vec<PathBuf>,
+    /// Explicitly assigned roles, these take precedence over the heuristics based on extensions and order
+    pub roles: FileRoles,
 }
 
 impl Files {
@@ -21,6 +136,7 @@ impl Files {
             user_guides: vec![],
             proof_outlines: vec![],
             other: vec![],
+            roles: FileRoles::default(),
         }
     }
 
@@ -49,34 +165,192 @@ impl Files {
         Ok(result)
     }
 
+    /// Assign roles explicitly, the assigned files are no longer subject to the heuristics
+    ///
+    /// Warns about every remaining file whose function could not be determined,
+    /// except for hidden files and the project configuration.
+    pub fn assign(mut self, roles: FileRoles) -> WithWarnings<Self, FilesWarning> {
+        let assigned: Vec<_> = roles.paths().map(identify).collect();
+
+        for paths in [
+            &mut self.specifications,
+            &mut self.programs,
+            &mut self.user_guides,
+            &mut self.proof_outlines,
+            &mut self.other,
+        ] {
+            paths.retain(|path| !assigned.contains(&identify(path)));
+        }
+
+        self.roles = roles;
+
+        let warnings = self
+            .other
+            .iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(OsStr::to_str)
+                    .is_some_and(|name| name != CONFIG_FILE_NAME && !name.starts_with('.'))
+            })
+            .cloned()
+            .map(FilesWarning::UnusedFile)
+            .collect();
+
+        WithWarnings {
+            data: self,
+            warnings,
+        }
+    }
+
     pub fn left(&self) -> Option<&PathBuf> {
-        self.programs.first()
+        self.roles.left.as_ref().or_else(|| self.programs.first())
     }
 
     pub fn right(&self) -> Option<&PathBuf> {
-        self.programs.get(1)
+        self.roles.right.as_ref().or_else(|| {
+            if self.roles.left.is_some() {
+                self.programs.first()
+            } else {
+                self.programs.get(1)
+            }
+        })
     }
 
     pub fn specification(&self) -> Option<Either<&PathBuf, &PathBuf>> {
-        self.specifications
-            .first()
-            .map(Either::Right)
-            .or_else(|| self.programs.first().map(Either::Left))
+        match self.roles.specification {
+            Some(ref path) => Some(match path.extension().and_then(OsStr::to_str) {
+                Some("lp") => Either::Left(path),
+                _ => Either::Right(path),
+            }),
+            None => self
+                .specifications
+                .first()
+                .map(Either::Right)
+                .or_else(|| self.programs.first().map(Either::Left)),
+        }
     }
 
     pub fn program(&self) -> Option<&PathBuf> {
-        if self.specifications.is_empty() {
-            self.programs.get(1)
+        self.roles.program.as_ref().or_else(|| {
+            if self.specifications.is_empty() && self.roles.specification.is_none() {
+                self.programs.get(1)
+            } else {
+                self.programs.first()
+            }
+        })
+    }
+
+    pub fn user_guides(&self) -> &[PathBuf] {
+        if self.roles.user_guide.is_empty() {
+            &self.user_guides
         } else {
-            self.programs.first()
+            &self.roles.user_guide
+        }
+    }
+
+    pub fn proof_outlines(&self) -> &[PathBuf] {
+        if self.roles.proof_outline.is_empty() {
+            &self.proof_outlines
+        } else {
+            &self.roles.proof_outline
+        }
+    }
+}
+
+/// Identify a path by its canonical form if it exists
+fn identify(path: &PathBuf) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{FileRoles, Files, FilesWarning},
+        either::Either,
+        std::path::PathBuf,
+    };
+
+    fn sorted(programs: &[&str], specifications: &[&str]) -> Files {
+        Files {
+            programs: programs.iter().map(PathBuf::from).collect(),
+            specifications: specifications.iter().map(PathBuf::from).collect(),
+            ..Files::empty()
         }
     }
 
-    pub fn user_guide(&self) -> Option<&PathBuf> {
-        self.user_guides.first()
+    #[test]
+    fn heuristic_roles() {
+        let files = sorted(&["a.lp", "b.lp"], &[]);
+        assert_eq!(files.left(), Some(&"a.lp".into()));
+        assert_eq!(files.right(), Some(&"b.lp".into()));
+        assert_eq!(files.specification(), Some(Either::Left(&"a.lp".into())));
+        assert_eq!(files.program(), Some(&"b.lp".into()));
+    }
+
+    #[test]
+    fn explicit_roles() {
+        let files = sorted(&["a.lp", "b.lp"], &[])
+            .assign(FileRoles {
+                specification: Some("b.lp".into()),
+                ..FileRoles::default()
+            })
+            .data;
+        assert_eq!(files.specification(), Some(Either::Left(&"b.lp".into())));
+        assert_eq!(files.program(), Some(&"a.lp".into()));
+
+        let files = sorted(&["a.lp", "b.lp"], &["c.spec"])
+            .assign(FileRoles {
+                right: Some("a.lp".into()),
+                program: Some("a.lp".into()),
+                ..FileRoles::default()
+            })
+            .data;
+        assert_eq!(files.left(), Some(&"b.lp".into()));
+        assert_eq!(files.right(), Some(&"a.lp".into()));
+        assert_eq!(files.specification(), Some(Either::Right(&"c.spec".into())));
+        assert_eq!(files.program(), Some(&"a.lp".into()));
     }
 
-    pub fn proof_outline(&self) -> Option<&PathBuf> {
-        self.proof_outlines.first()
+    #[test]
+    fn unused_files() {
+        let files = Files {
+            other: vec![
+                "project/anthem.toml".into(),
+                "project/.gitignore".into(),
+                "project/notes.txt".into(),
+            ],
+            ..Files::empty()
+        }
+        .assign(FileRoles::default());
+
+        assert_eq!(files.warnings.len(), 1);
+        assert!(matches!(
+            &files.warnings[0],
+            FilesWarning::UnusedFile(path) if path == &PathBuf::from("project/notes.txt")
+        ));
+    }
+
+    #[test]
+    fn merge_roles() {
+        let roles = FileRoles {
+            left: Some("a.lp".into()),
+            user_guide: vec!["a.ug".into(), "b.ug".into()],
+            ..FileRoles::default()
+        }
+        .merge(FileRoles {
+            left: Some("b.lp".into()),
+            proof_outline: vec!["a.po".into()],
+            ..FileRoles::default()
+        });
+
+        assert_eq!(
+            roles,
+            FileRoles {
+                left: Some("b.lp".into()),
+                user_guide: vec!["a.ug".into(), "b.ug".into()],
+                proof_outline: vec!["a.po".into()],
+                ..FileRoles::default()
+            }
+        );
     }
 }
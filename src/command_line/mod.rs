@@ -1,3 +1,4 @@
 pub mod arguments;
+pub mod config;
 pub mod files;
 pub mod procedures;
@@ -2,10 +2,17 @@ use {
     crate::{
         analyzing::tightness::Tightness,
         command_line::{
-            arguments::{Arguments, Command, Equivalence, Property, Simplification, Translation},
-            files::Files,
+            arguments::{
+                Arguments, Command, Equivalence, FormulaRepresentation, OutputFormat, PreCheck,
+                Property, Simplification, TaskDecomposition, Translation,
+            },
+            config::{self, Config},
+            files::{FileRoles, Files, FilesWarning},
         },
+        convenience::with_warnings::WithWarnings,
+        evaluating::fol::Structure,
         simplifying::fol::ht::{simplify, simplify_shallow},
+        solving::{grounding::ground, stable_models::stable_models},
         syntax_tree::{asp, fol, Node as _},
         translating::{
             asp_to_ht::{tau_star, Version},
@@ -14,23 +21,85 @@ use {
             shorthand::shorthand,
         },
         verifying::{
-            prover::{vampire::Vampire, Prover, Report, Status, Success},
+            bounded::{BoundedCheck, BoundedCheckReport},
+            ht::StrongEquivalenceCheck,
+            problem::{self, Problem},
+            prover::{
+                cache::Cache,
+                replay::{Record, Replay},
+                vampire::Vampire,
+                Failure, Prover, Report, Status, Success,
+            },
+            session::{Instruction, Session, HELP},
+            suggestion,
             task::{
                 derivation::DerivationTask, external_equivalence::ExternalEquivalenceTask,
                 intuit_equivalence::IntuitEquivalenceTask,
-                strong_equivalence::StrongEquivalenceTask, Task,
+                strong_equivalence::StrongEquivalenceTask,
+                uniform_equivalence::UniformEquivalenceTask, Task,
             },
         },
     },
-    anyhow::{anyhow, Context, Result},
-    clap::Parser as _,
+    anyhow::{anyhow, bail, Context, Result},
+    clap::parser::ValueSource,
     either::Either,
-    std::collections::HashSet,
-    std::time::Instant,
+    indexmap::{IndexMap, IndexSet},
+    itertools::Itertools as _,
+    serde_json::json,
+    std::{
+        collections::HashSet, ffi::OsString, fmt::Display, io::Write as _, ops::ControlFlow,
+        path::PathBuf, process::ExitCode, time::Instant,
+    },
+    thiserror::Error,
 };
 
-pub fn main() -> Result<()> {
-    match Arguments::parse().command {
+/// The outcome of running anthem, determines the exit code
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Success = 0,
+    Error = 1,
+    ProofFailure = 3,
+    Timeout = 4,
+    ToolError = 5,
+    InvalidInput = 6,
+    Counterexample = 7,
+}
+
+impl From<Outcome> for ExitCode {
+    fn from(outcome: Outcome) -> Self {
+        ExitCode::from(outcome as u8)
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("the given input does not constitute a valid task")]
+struct InvalidInput;
+
+fn invalid_input<E>(error: E) -> anyhow::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    anyhow::Error::new(error).context(InvalidInput)
+}
+
+pub fn main() -> ExitCode {
+    match run() {
+        Ok(outcome) => outcome.into(),
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+            if error.is::<InvalidInput>() {
+                Outcome::InvalidInput.into()
+            } else {
+                Outcome::Error.into()
+            }
+        }
+    }
+}
+
+fn run() -> Result<Outcome> {
+    let (arguments, roles) = parse_arguments()?;
+
+    match arguments.command {
         Command::Analyze { property, input } => {
             match property {
                 Property::Tightness => {
@@ -48,21 +117,29 @@ pub fn main() -> Result<()> {
                 }
             }
 
-            Ok(())
+            Ok(Outcome::Success)
         }
 
         Command::Derive {
-            outline,
-            user_guide,
             task_decomposition,
+            direction,
             no_simplify,
             no_eq_break,
             time_limit,
             no_proof_search,
+            check_consistency,
             no_timing,
+            output,
             out_dir,
             prover_instances,
             prover_cores,
+            replay,
+            record,
+            cache,
+            user_guide,
+            proof_outline,
+            files,
+            ..
         } => {
             let start_time = if !no_timing {
                 Some(Instant::now())
@@ -70,22 +147,33 @@ pub fn main() -> Result<()> {
                 None
             };
 
-            println!(
-                "WARNING: Do not specify directions (forward, backward) in any annotated formulas!"
-            );
+            let roles = roles
+                .merge(FileRoles::derivation_by_position(&files))
+                .merge(FileRoles {
+                    user_guide,
+                    proof_outline,
+                    ..FileRoles::default()
+                });
+            let files = report_warnings(sort_files(files, roles)?, output);
 
-            let proof_outline = fol::Specification::from_file(outline)?;
-            let user_guide = fol::UserGuide::from_file(user_guide)?;
+            let proof_outline = read_proof_outlines(files.proof_outlines())?
+                .ok_or(anyhow!("no proof outline was provided"))?;
+            let user_guide = read_user_guides(files.user_guides())?
+                .ok_or(anyhow!("no user guide was provided"))?;
 
-            let problems = DerivationTask {
-                proof_outline,
-                user_guide,
-                task_decomposition,
-                simplify: !no_simplify,
-                break_equivalences: !no_eq_break,
-            }
-            .decompose()?
-            .report_warnings();
+            let problems = report_warnings(
+                DerivationTask {
+                    proof_outline,
+                    user_guide,
+                    task_decomposition,
+                    direction,
+                    simplify: !no_simplify,
+                    break_equivalences: !no_eq_break,
+                }
+                .decompose()
+                .map_err(invalid_input)?,
+                output,
+            );
 
             if let Some(out_dir) = out_dir {
                 for problem in &problems {
@@ -103,85 +191,226 @@ pub fn main() -> Result<()> {
                     cores: prover_cores,
                 };
 
-                let problems = problems.into_iter().inspect(|problem| {
-                    println!("> Proving {}...", problem.name);
-                    println!("Axioms:");
-                    for axiom in problem.axioms() {
-                        println!("    {}", axiom.formula);
-                    }
-                    println!();
-                    println!("Conjectures:");
-                    for conjecture in problem.conjectures() {
-                        println!("    {}", conjecture.formula);
+                if check_consistency {
+                    check_consistency_with(
+                        prover.clone(),
+                        replay.clone(),
+                        record.clone(),
+                        &problems,
+                        output,
+                    )?;
+                }
+
+                let summary = prove_with(prover, replay, record, cache, problems, output)?;
+
+                match output {
+                    OutputFormat::Text => {
+                        if summary.success() {
+                            print!("> Success! Anthem proved every lemma.")
+                        } else {
+                            print!("> Failure! Anthem was unable to prove every lemma.")
+                        }
+                        print_elapsed(start_time);
                     }
-                    println!();
-                });
+                    OutputFormat::Json => summary.print_record(start_time, None),
+                }
 
-                let mut success = true;
-                for result in prover.prove_all(problems) {
-                    match result {
-                        Ok(report) => match report.status() {
-                            Ok(status) => {
-                                println!(
-                                    "> Proving {} ended with a SZS status",
-                                    report.problem.name
-                                );
-
-                                match report.start_time {
-                                    Some(start) => println!(
-                                        "Status: {status} ({} ms)",
-                                        start.elapsed().as_millis()
-                                    ),
-                                    None => println!("Status: {status}"),
-                                }
+                return Ok(summary.outcome());
+            }
+
+            if output == OutputFormat::Text {
+                print_elapsed(start_time);
+            }
+
+            Ok(Outcome::Success)
+        }
+
+        Command::Evaluate {
+            placeholders,
+            specification,
+            structure,
+        } => {
+            let mut rules = vec![];
+            for file in structure {
+                rules.extend(asp::Program::from_file(file)?);
+            }
+
+            let program = ground(&asp::Program { rules }, &placeholders)
+                .context("unable to ground the given structure")?;
+            let enumeration = stable_models(&program, Some(2));
+            let [model] = enumeration.models.as_slice() else {
+                bail!("the given structure must have exactly one stable model")
+            };
+
+            let mapping = placeholders
+                .iter()
+                .map(|constant| {
+                    let sort = match constant.value {
+                        asp::PrecomputedTerm::Numeral(_) => fol::Sort::Integer,
+                        asp::PrecomputedTerm::Symbol(_) => fol::Sort::Symbol,
+                        asp::PrecomputedTerm::Infimum | asp::PrecomputedTerm::Supremum => {
+                            fol::Sort::General
+                        }
+                    };
+                    let placeholder = fol::FunctionConstant {
+                        name: constant.name.clone(),
+                        sort,
+                    };
+                    (constant.name.clone(), placeholder)
+                })
+                .collect();
+
+            let specification =
+                fol::Specification::from_file(specification)?.replace_placeholders(&mapping);
+
+            let mut structure = Structure::from_atoms(
+                model,
+                placeholders
+                    .into_iter()
+                    .map(|constant| (constant.name, constant.value))
+                    .collect(),
+            );
+            structure.fill_integer_gaps();
+
+            let violations = structure
+                .violations(&specification)
+                .context("unable to evaluate the given specification")?;
+
+            if violations.is_empty() {
+                println!("> The structure satisfies the specification.");
+                Ok(Outcome::Success)
+            } else {
+                println!("> The structure violates the following formulas:");
+                for formula in violations {
+                    println!("{formula}");
+                }
+                Ok(Outcome::Counterexample)
+            }
+        }
 
-                                if !matches!(status, Status::Success(Success::Theorem)) {
-                                    success = false;
+        Command::Prove {
+            interactive,
+            direction,
+            time_limit,
+            prover_instances,
+            prover_cores,
+            no_timing,
+            replay,
+            spec,
+            program,
+            user_guide,
+            proof_outline,
+            files,
+        } => {
+            let roles = roles.merge(FileRoles {
+                specification: spec,
+                program,
+                user_guide,
+                proof_outline,
+                ..FileRoles::default()
+            });
+            let files = report_warnings(sort_files(files, roles)?, OutputFormat::Text);
+
+            let user_guide = read_user_guides(files.user_guides())?
+                .ok_or(anyhow!("no user guide was provided"))?;
+            let placeholders: IndexMap<_, _> = user_guide
+                .placeholders()
+                .into_iter()
+                .map(|p| (p.name.clone(), p))
+                .collect();
+
+            let mut session = match (files.specification(), files.program()) {
+                (Some(specification), Some(program)) => {
+                    let task = report_warnings(
+                        ExternalEquivalenceTask {
+                            specification: match specification {
+                                Either::Left(program) => {
+                                    Either::Left(asp::Program::from_file(program)?)
                                 }
-                            }
-                            Err(error) => {
-                                match report.start_time {
-                                    Some(start) => println!(
-                                        "> Proving {} ended without a SZS status ({} ms)",
-                                        report.problem.name,
-                                        start.elapsed().as_millis()
-                                    ),
-                                    None => println!(
-                                        "> Proving {} ended without a SZS status",
-                                        report.problem.name
-                                    ),
+                                Either::Right(specification) => {
+                                    Either::Right(fol::Specification::from_file(specification)?)
                                 }
+                            },
+                            program: asp::Program::from_file(program)?,
+                            user_guide: user_guide.clone(),
+                            proof_outline: fol::Specification::empty(),
+                            formula_representation: FormulaRepresentation::default(),
+                            task_decomposition: TaskDecomposition::default(),
+                            direction,
+                            bypass_tightness: false,
+                            typing_lemmas: false,
+                            simplify: true,
+                            break_equivalences: true,
+                        }
+                        .assemble()
+                        .map_err(invalid_input)?,
+                        OutputFormat::Text,
+                    );
 
-                                println!("Output/stdout:");
-                                println!("{}", report.output.stdout);
-                                println!("Output/stderr:");
-                                println!("{}", report.output.stderr);
-                                println!("Error: {error}");
-                                success = false;
-                            }
-                        },
-                        Err(error) => {
-                            println!("> Proving <a problem> ended with an error"); // TODO: Get the name of the problem
-                            println!("Error: {error}");
-                            success = false;
+                    let mut premises = task.stable_premises;
+                    let conclusions = match direction {
+                        fol::Direction::Backward => {
+                            premises.extend(task.backward_premises);
+                            task.backward_conclusions
+                        }
+                        fol::Direction::Universal | fol::Direction::Forward => {
+                            premises.extend(task.forward_premises);
+                            task.forward_conclusions
                         }
+                    };
+
+                    let mut taken_predicates = user_guide.input_predicates();
+                    for formula in premises.iter().chain(&conclusions) {
+                        taken_predicates.extend(formula.predicates());
                     }
-                    println!();
+
+                    Session::new(
+                        premises,
+                        conclusions,
+                        direction,
+                        taken_predicates,
+                        placeholders,
+                    )
                 }
 
-                if success {
-                    print!("> Success! Anthem proved every lemma.")
-                } else {
-                    print!("> Failure! Anthem was unable to prove every lemma.")
+                // Without a specification and a program, the session derives lemmas from the assumptions of the user guide
+                (None, None) => {
+                    let premises = user_guide
+                        .formulas()
+                        .into_iter()
+                        .filter(|formula| formula.role == fol::Role::Assumption)
+                        .map(|formula| {
+                            formula.replace_placeholders(&placeholders).into_problem_formula(
+                                problem::Role::Axiom,
+                                problem::FormulaType::Tff,
+                            )
+                        })
+                        .collect();
+
+                    Session::new(
+                        premises,
+                        vec![],
+                        direction,
+                        user_guide.public_predicates(),
+                        placeholders,
+                    )
                 }
-            }
 
-            match start_time {
-                Some(start) => println!(" ({} ms)", start.elapsed().as_millis()),
-                None => println!(),
+                _ => bail!("a proof session requires either a specification and a program or neither of them"),
+            };
+
+            if let Some(proof_outline) = read_proof_outlines(files.proof_outlines())? {
+                session.load(proof_outline).map_err(invalid_input)?;
             }
 
-            Ok(())
+            let prover = Vampire {
+                time_limit,
+                time_execution: !no_timing,
+                instances: prover_instances,
+                cores: prover_cores,
+            };
+
+            run_session(session, prover, replay, interactive)
         }
 
         Command::Simplify { with, input } => {
@@ -197,7 +426,47 @@ pub fn main() -> Result<()> {
                 }
             }
 
-            Ok(())
+            Ok(Outcome::Success)
+        }
+
+        Command::Solve {
+            constants,
+            models,
+            files,
+        } => {
+            let program = if files.is_empty() {
+                asp::Program::from_stdin()?
+            } else {
+                let mut rules = vec![];
+                for file in files {
+                    rules.extend(asp::Program::from_file(file)?);
+                }
+                asp::Program { rules }
+            };
+
+            let program =
+                ground(&program, &constants).context("unable to ground the given program")?;
+            let enumeration = stable_models(&program, (models > 0).then_some(models));
+
+            for (i, model) in enumeration.models.iter().enumerate() {
+                println!("Answer: {}", i + 1);
+                println!("{}", model.iter().map(ToString::to_string).join(" "));
+            }
+
+            if enumeration.models.is_empty() {
+                println!("UNSATISFIABLE");
+            } else {
+                println!("SATISFIABLE");
+            }
+
+            println!();
+            println!(
+                "Models       : {}{}",
+                enumeration.models.len(),
+                if enumeration.exhausted { "" } else { "+" }
+            );
+
+            Ok(Outcome::Success)
         }
 
         Command::Translate { with, input } => {
@@ -238,7 +507,7 @@ pub fn main() -> Result<()> {
                 }
             }
 
-            Ok(())
+            Ok(Outcome::Success)
         }
 
         Command::Verify {
@@ -246,16 +515,33 @@ pub fn main() -> Result<()> {
             formula_representation,
             task_decomposition,
             direction,
+            pre_check,
             bypass_tightness,
+            typing_lemmas,
             no_simplify,
             no_eq_break,
             no_proof_search,
+            check_consistency,
             no_timing,
+            output,
             time_limit,
             prover_instances,
             prover_cores,
             save_problems: out_dir,
+            replay,
+            record,
+            cache,
+            suggest_lemmas,
+            suggestion_time_limit,
+            context,
+            left,
+            right,
+            spec,
+            program,
+            user_guide,
+            proof_outline,
             files,
+            ..
         } => {
             let start_time = if !no_timing {
                 Some(Instant::now())
@@ -263,83 +549,194 @@ pub fn main() -> Result<()> {
                 None
             };
 
-            let files =
-                Files::sort(files).context("unable to sort the given files by their function")?;
+            let roles = roles.merge(FileRoles {
+                left,
+                right,
+                specification: spec,
+                program,
+                user_guide,
+                proof_outline,
+            });
+            let files = report_warnings(sort_files(files, roles)?, output);
+
+            if pre_check.is_some()
+                && !matches!(equivalence, Equivalence::External | Equivalence::Strong)
+            {
+                bail!("pre-checks are only available for external and strong equivalence")
+            }
+
+            if suggest_lemmas.is_some() && equivalence != Equivalence::External {
+                bail!("lemma suggestions are only available for external equivalence")
+            }
+
+            let mut bounded_check = None;
+            let mut strong_equivalence_check = None;
+            // The predicates of the context a strong equivalence claim is relative to, and where they are taken from
+            let mut relative_context = None;
+            let mut public_predicates = IndexSet::new();
 
             let problems = match equivalence {
-                Equivalence::Strong => StrongEquivalenceTask {
-                    left: asp::Program::from_file(
-                        files
-                            .left()
-                            .ok_or(anyhow!("no left program was provided"))?,
-                    )?,
-                    right: asp::Program::from_file(
-                        files
-                            .right()
-                            .ok_or(anyhow!("no right program was provided"))?,
-                    )?,
-                    task_decomposition,
-                    direction,
-                    formula_representation,
-                    simplify: !no_simplify,
-                    break_equivalences: !no_eq_break,
+                Equivalence::Strong => {
+                    let given_context = context.is_some();
+                    let task = StrongEquivalenceTask {
+                        context: match context {
+                            Some(context) => Some(context.into_iter().collect()),
+                            None => read_user_guides(files.user_guides())?
+                                .map(|user_guide| user_guide.public_predicates()),
+                        },
+                        left: asp::Program::from_file(
+                            files
+                                .left()
+                                .ok_or(anyhow!("no left program was provided"))?,
+                        )?,
+                        right: asp::Program::from_file(
+                            files
+                                .right()
+                                .ok_or(anyhow!("no right program was provided"))?,
+                        )?,
+                        task_decomposition,
+                        direction,
+                        formula_representation,
+                        simplify: !no_simplify,
+                        break_equivalences: !no_eq_break,
+                    };
+
+                    if task.context.is_none() {
+                        strong_equivalence_check = Some(StrongEquivalenceCheck {
+                            left: task.left.clone(),
+                            right: task.right.clone(),
+                        });
+                    } else if pre_check.is_some() {
+                        bail!("pre-checks are not available for strong equivalence relative to a context")
+                    } else {
+                        relative_context = task.context.as_ref().map(|context| {
+                            (
+                                context.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                                if given_context {
+                                    "the given context"
+                                } else {
+                                    "the public predicates of the user guide"
+                                },
+                            )
+                        });
+                    }
+
+                    report_warnings(task.decompose().map_err(invalid_input)?, output)
                 }
-                .decompose()?
-                .report_warnings(),
-
-                Equivalence::External => ExternalEquivalenceTask {
-                    specification: match files
-                        .specification()
-                        .ok_or(anyhow!("no specification was provided"))?
-                    {
-                        Either::Left(program) => Either::Left(asp::Program::from_file(program)?),
-                        Either::Right(specification) => {
-                            Either::Right(fol::Specification::from_file(specification)?)
-                        }
-                    },
-                    program: asp::Program::from_file(
-                        files.program().ok_or(anyhow!("no program was provided"))?,
-                    )?,
-                    user_guide: fol::UserGuide::from_file(
-                        files
-                            .user_guide()
+
+                Equivalence::External => {
+                    let task = ExternalEquivalenceTask {
+                        specification: match files
+                            .specification()
+                            .ok_or(anyhow!("no specification was provided"))?
+                        {
+                            Either::Left(program) => {
+                                Either::Left(asp::Program::from_file(program)?)
+                            }
+                            Either::Right(specification) => {
+                                Either::Right(fol::Specification::from_file(specification)?)
+                            }
+                        },
+                        program: asp::Program::from_file(
+                            files.program().ok_or(anyhow!("no program was provided"))?,
+                        )?,
+                        user_guide: read_user_guides(files.user_guides())?
                             .ok_or(anyhow!("no user guide was provided"))?,
-                    )?,
-                    proof_outline: files
-                        .proof_outline()
-                        .map(fol::Specification::from_file)
-                        .unwrap_or_else(|| Ok(fol::Specification::empty()))?,
-                    formula_representation,
-                    task_decomposition,
-                    direction,
-                    bypass_tightness,
-                    simplify: !no_simplify,
-                    break_equivalences: !no_eq_break,
-                }
-                .decompose()?
-                .report_warnings(),
-
-                Equivalence::Intuitionistic => IntuitEquivalenceTask {
-                    left: asp::Program::from_file(
-                        files
-                            .left()
-                            .ok_or(anyhow!("no left program was provided"))?,
-                    )?,
-                    right: asp::Program::from_file(
-                        files
-                            .right()
-                            .ok_or(anyhow!("no right program was provided"))?,
-                    )?,
-                    task_decomposition,
-                    direction,
-                    simplify: !no_simplify,
-                    break_equivalences: !no_eq_break,
-                    translation: formula_representation,
+                        proof_outline: read_proof_outlines(files.proof_outlines())?
+                            .unwrap_or_else(fol::Specification::empty),
+                        formula_representation,
+                        task_decomposition,
+                        direction,
+                        bypass_tightness,
+                        typing_lemmas,
+                        simplify: !no_simplify,
+                        break_equivalences: !no_eq_break,
+                    };
+
+                    public_predicates = task.user_guide.public_predicates();
+
+                    if pre_check == Some(PreCheck::Bounded) {
+                        bounded_check = Some(BoundedCheck {
+                            specification: task.specification.clone(),
+                            program: task.program.clone(),
+                            user_guide: task.user_guide.clone(),
+                        });
+                    }
+
+                    report_warnings(task.decompose().map_err(invalid_input)?, output)
                 }
-                .decompose()?
-                .report_warnings(),
+
+                Equivalence::Uniform => report_warnings(
+                    UniformEquivalenceTask {
+                        left: asp::Program::from_file(
+                            files
+                                .left()
+                                .ok_or(anyhow!("no left program was provided"))?,
+                        )?,
+                        right: asp::Program::from_file(
+                            files
+                                .right()
+                                .ok_or(anyhow!("no right program was provided"))?,
+                        )?,
+                        formula_representation,
+                        task_decomposition,
+                        direction,
+                        simplify: !no_simplify,
+                        break_equivalences: !no_eq_break,
+                    }
+                    .decompose()
+                    .map_err(invalid_input)?,
+                    output,
+                ),
+
+                Equivalence::Intuitionistic => report_warnings(
+                    IntuitEquivalenceTask {
+                        left: asp::Program::from_file(
+                            files
+                                .left()
+                                .ok_or(anyhow!("no left program was provided"))?,
+                        )?,
+                        right: asp::Program::from_file(
+                            files
+                                .right()
+                                .ok_or(anyhow!("no right program was provided"))?,
+                        )?,
+                        task_decomposition,
+                        direction,
+                        simplify: !no_simplify,
+                        break_equivalences: !no_eq_break,
+                        translation: formula_representation,
+                    }
+                    .decompose()
+                    .map_err(invalid_input)?,
+                    output,
+                ),
             };
 
+            if let Some(bounded_check) = bounded_check {
+                let report = bounded_check
+                    .run()
+                    .context("the bounded pre-check could not be completed")?;
+                print_pre_check_report(&report, output);
+
+                if report.exact && report.counterexample.is_some() {
+                    return Ok(Outcome::Counterexample);
+                }
+            }
+
+            if pre_check == Some(PreCheck::Bounded) {
+                if let Some(strong_equivalence_check) = strong_equivalence_check.take() {
+                    let report = strong_equivalence_check
+                        .run()
+                        .context("the bounded pre-check could not be completed")?;
+                    print_pre_check_report(&report, output);
+
+                    if report.counterexample.is_some() {
+                        return Ok(Outcome::Counterexample);
+                    }
+                }
+            }
+
             if let Some(out_dir) = out_dir {
                 for problem in &problems {
                     let mut path = out_dir.clone();
@@ -356,93 +753,1151 @@ pub fn main() -> Result<()> {
                     cores: prover_cores,
                 };
 
-                let problems = problems.into_iter().inspect(|problem| {
-                    println!("> Proving {}...", problem.name);
-                    println!("Axioms:");
-                    for axiom in problem.axioms() {
-                        println!("    {}", axiom.formula);
-                    }
-                    println!();
-                    println!("Conjectures:");
-                    for conjecture in problem.conjectures() {
-                        println!("    {}", conjecture.formula);
+                if check_consistency {
+                    check_consistency_with(
+                        prover.clone(),
+                        replay.clone(),
+                        record.clone(),
+                        &problems,
+                        output,
+                    )?;
+                }
+
+                let summary = prove_with(
+                    prover.clone(),
+                    replay.clone(),
+                    record.clone(),
+                    cache,
+                    problems,
+                    output,
+                )?;
+
+                // The helpful lemmas among the candidates for the failed problems are saved as a proof outline
+                if let Some(path) = suggest_lemmas.filter(|_| !summary.success()) {
+                    let lemmas = suggest_lemmas_with(
+                        prover,
+                        suggestion_time_limit,
+                        replay,
+                        record,
+                        &summary.failed,
+                        &public_predicates,
+                        output,
+                    );
+
+                    if !lemmas.is_empty() {
+                        let outline = fol::Specification { formulas: lemmas };
+                        std::fs::write(&path, outline.to_string()).with_context(|| {
+                            format!("could not write file `{}`", path.display())
+                        })?;
+
+                        if output == OutputFormat::Text {
+                            println!(
+                                "> Saved {} suggested lemmas to {}",
+                                outline.formulas.len(),
+                                path.display()
+                            );
+                            println!();
+                        }
                     }
-                    println!();
-                });
+                }
 
-                let mut success = true;
-                for result in prover.prove_all(problems) {
-                    match result {
-                        Ok(report) => match report.status() {
-                            Ok(status) => {
-                                println!(
-                                    "> Proving {} ended with a SZS status",
-                                    report.problem.name
-                                );
-
-                                match report.start_time {
-                                    Some(start) => println!(
-                                        "Status: {status} ({} ms)",
-                                        start.elapsed().as_millis()
-                                    ),
-                                    None => println!("Status: {status}"),
-                                }
+                // A failed proof search for strong equivalence is followed by a search for a witness of non-equivalence,
+                // which is not available for strong equivalence relative to a context
+                let witness =
+                    strong_equivalence_check
+                        .filter(|_| !summary.success())
+                        .map(|check| {
+                            check.run().map(|report| report.counterexample).context(
+                            "the search for a witness of non-equivalence could not be completed",
+                        )
+                        });
+                let skipped_witness_context = relative_context
+                    .as_ref()
+                    .filter(|_| !summary.success())
+                    .map(|(_, source)| source);
 
-                                if !matches!(status, Status::Success(Success::Theorem)) {
-                                    success = false;
-                                }
-                            }
-                            Err(error) => {
-                                match report.start_time {
-                                    Some(start) => println!(
-                                        "> Proving {} ended without a SZS status ({} ms)",
-                                        report.problem.name,
-                                        start.elapsed().as_millis()
-                                    ),
-                                    None => println!(
-                                        "> Proving {} ended without a SZS status",
-                                        report.problem.name
-                                    ),
-                                }
+                match output {
+                    OutputFormat::Text => {
+                        if !summary.success() {
+                            print!("> Failure! Anthem was unable to find a proof of equivalence.")
+                        } else if let Some((predicates, source)) = &relative_context {
+                            print!(
+                                "> Success! Anthem found a proof of equivalence relative to {source} {{{}}}.",
+                                predicates.join(", ")
+                            )
+                        } else {
+                            print!("> Success! Anthem found a proof of equivalence.")
+                        }
+                        print_elapsed(start_time);
 
-                                println!("Output/stdout:");
-                                println!("{}", report.output.stdout);
-                                println!("Output/stderr:");
-                                println!("{}", report.output.stderr);
-                                println!("Error: {error}");
-                                success = false;
+                        match witness {
+                            Some(Ok(Some(witness))) => {
+                                println!();
+                                println!("> The programs are not strongly equivalent, as witnessed by the following:");
+                                println!("{witness}");
                             }
-                        },
-                        Err(error) => {
-                            println!("> Proving <a problem> ended with an error"); // TODO: Get the name of the problem
-                            println!("Error: {error}");
-                            success = false;
+                            Some(Err(error)) => {
+                                println!();
+                                println!("> Warning! {error:#}");
+                            }
+                            _ => (),
+                        }
+
+                        if let Some(context) = skipped_witness_context {
+                            println!();
+                            println!("> No witness of non-equivalence was searched for, since the claim is relative to {context}.");
                         }
                     }
-                    println!();
+                    OutputFormat::Json => {
+                        match witness {
+                            Some(Ok(Some(witness))) => println!(
+                                "{}",
+                                json!({
+                                    "type": "counterexample",
+                                    "counterexample": witness.to_string(),
+                                })
+                            ),
+                            Some(Err(error)) => println!(
+                                "{}",
+                                json!({ "type": "warning", "message": format!("{error:#}") })
+                            ),
+                            _ => (),
+                        }
+
+                        if let Some(context) = skipped_witness_context {
+                            println!(
+                                "{}",
+                                json!({
+                                    "type": "warning",
+                                    "message": format!("no witness of non-equivalence was searched for, since the claim is relative to {context}"),
+                                })
+                            );
+                        }
+                        summary.print_record(
+                            start_time,
+                            relative_context.map(|(predicates, _)| predicates),
+                        )
+                    }
+                }
+
+                return Ok(summary.outcome());
+            }
+
+            Ok(Outcome::Success)
+        }
+    }
+}
+
+/// Run a proof session on the commands read from stdin
+///
+/// Outside of interactive mode, the session ends at the first erroneous command.
+fn run_session(
+    mut session: Session,
+    mut prover: Vampire,
+    replay: Option<PathBuf>,
+    interactive: bool,
+) -> Result<Outcome> {
+    if interactive {
+        println!(
+            "> Started a proof session with {} premises and {} steps, enter `help` for a list of commands",
+            session.premises.len(),
+            session.steps.len()
+        );
+    }
+
+    let mut outcome = Outcome::Success;
+    let mut lines = std::io::stdin().lines();
+    loop {
+        if interactive {
+            print!("anthem> ");
+            std::io::stdout().flush()?;
+        }
+
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+
+        let result = line
+            .parse()
+            .map_err(invalid_input)
+            .and_then(|instruction| execute(&mut session, &mut prover, &replay, instruction));
+
+        match result {
+            Ok(ControlFlow::Break(())) => break,
+            Ok(ControlFlow::Continue(true)) => (),
+            Ok(ControlFlow::Continue(false)) => outcome = Outcome::ProofFailure,
+            Err(error) if interactive && error.is::<InvalidInput>() => {
+                println!("Error: {}", error.root_cause())
+            }
+            Err(error) if interactive => println!("Error: {error:#}"),
+            Err(error) => return Err(error),
+        }
+    }
+
+    if interactive {
+        Ok(Outcome::Success)
+    } else {
+        Ok(outcome)
+    }
+}
+
+/// Execute a command of a proof session, the result tells whether to continue and whether every proof succeeded
+fn execute(
+    session: &mut Session,
+    prover: &mut Vampire,
+    replay: &Option<PathBuf>,
+    instruction: Instruction,
+) -> Result<ControlFlow<(), bool>> {
+    let prove = |problems| {
+        prove_with(
+            prover.clone(),
+            replay.clone(),
+            None,
+            None,
+            problems,
+            OutputFormat::Text,
+        )
+    };
+
+    match instruction {
+        Instruction::Add(formula) => {
+            let problems = session.problems(&formula).map_err(invalid_input)?;
+            let success = problems.is_empty() || prove(problems)?.success();
+            if success {
+                println!("> Accepted {formula}");
+                session.accept(formula);
+            } else {
+                println!("> Rejected {formula}, since Anthem was unable to prove every conjecture");
+            }
+            Ok(ControlFlow::Continue(success))
+        }
+
+        Instruction::Try(formula) => {
+            let problem = session.conjecture(formula).map_err(invalid_input)?;
+            let success = prove(vec![problem])?.success();
+            if success {
+                println!("> Anthem proved the conjecture.");
+            } else {
+                println!("> Anthem was unable to prove the conjecture.");
+            }
+            Ok(ControlFlow::Continue(success))
+        }
+
+        Instruction::Remove(name) => {
+            let step = session.remove(&name).map_err(invalid_input)?;
+            println!("> Removed {}", step.formula);
+            if !session.unchecked().is_empty() {
+                println!("> The later steps are unchecked and will be proven again by `qed`");
+            }
+            Ok(ControlFlow::Continue(true))
+        }
+
+        Instruction::Steps => {
+            for (i, step) in session.steps.iter().enumerate() {
+                if step.proven {
+                    println!("{i}: {}", step.formula);
+                } else {
+                    println!("{i}: {} (unchecked)", step.formula);
+                }
+            }
+            Ok(ControlFlow::Continue(true))
+        }
+
+        Instruction::Axioms => {
+            println!("Axioms:");
+            for axiom in session.axioms().map_err(invalid_input)? {
+                println!("    {}", axiom.formula);
+            }
+            Ok(ControlFlow::Continue(true))
+        }
+
+        Instruction::TimeLimit(time_limit) => {
+            prover.time_limit = time_limit;
+            println!("> The time limit is now {time_limit} seconds");
+            Ok(ControlFlow::Continue(true))
+        }
+
+        Instruction::Qed => {
+            let problems = session.conclusions().map_err(invalid_input)?;
+
+            // The conclusions are only established once every step they rely on has been proven
+            for index in session.unchecked() {
+                let obligations = session.check(index).map_err(invalid_input)?;
+                if !obligations.is_empty() && !prove(obligations)?.success() {
+                    println!(
+                        "> Failure! Anthem was unable to prove the unchecked step {}",
+                        session.steps[index].formula
+                    );
+                    return Ok(ControlFlow::Continue(false));
+                }
+                session.confirm(index);
+            }
+
+            let success = prove(problems)?.success();
+            if success {
+                println!("> Success! Anthem proved every conclusion.");
+            } else {
+                println!("> Failure! Anthem was unable to prove every conclusion.");
+            }
+            Ok(ControlFlow::Continue(success))
+        }
+
+        Instruction::Save(path) => {
+            std::fs::write(&path, session.specification().to_string())
+                .with_context(|| format!("could not write file `{}`", path.display()))?;
+            println!("> Saved the proof outline to {}", path.display());
+            Ok(ControlFlow::Continue(true))
+        }
+
+        Instruction::Help => {
+            println!("{HELP}");
+            Ok(ControlFlow::Continue(true))
+        }
+
+        Instruction::Quit => Ok(ControlFlow::Break(())),
+    }
+}
+
+/// Parse the command line arguments, taking the defaults of a project configuration into account
+fn parse_arguments() -> Result<(Arguments, FileRoles)> {
+    let args: Vec<OsString> = std::env::args_os().collect();
+
+    // A first, lenient pass to find the configuration file (its options may supply required arguments)
+    let matches = Arguments::configurable_command()
+        .ignore_errors(true)
+        .get_matches_from(&args);
+
+    let (command, matches) = match matches.subcommand() {
+        Some((command @ ("verify" | "derive"), matches)) => (command, matches),
+        _ => return Ok((Arguments::parse_configurable(args), FileRoles::default())),
+    };
+
+    let profile = matches.get_one::<String>("profile");
+    let path = matches
+        .get_one::<PathBuf>("config")
+        .cloned()
+        .or_else(|| config::find(matches.get_many::<PathBuf>("files").into_iter().flatten()));
+
+    let path = match path {
+        Some(path) => path,
+        None if profile.is_some() => {
+            bail!("a profile was selected but no configuration file was found")
+        }
+        None => return Ok((Arguments::parse_configurable(args), FileRoles::default())),
+    };
+
+    let config = Config::from_file(&path)?;
+
+    // The options given on the command line replace the configured ones
+    let given: Vec<_> = Arguments::configurable_command()
+        .find_subcommand(command)
+        .into_iter()
+        .flat_map(|subcommand| subcommand.get_arguments())
+        .filter(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .filter_map(|arg| arg.get_long().map(str::to_owned))
+        .collect();
+
+    let configured_args = inject_arguments(
+        &args,
+        command,
+        config.arguments(command, profile.map(String::as_str), &given)?,
+    )?;
+
+    Ok((Arguments::parse_configurable(configured_args), config.files))
+}
+
+/// Insert the options of a configuration directly after the given subcommand
+///
+/// The options precede the actual arguments of the subcommand, so the latter take precedence.
+fn inject_arguments(
+    args: &[OsString],
+    command: &str,
+    options: impl IntoIterator<Item = OsString>,
+) -> Result<Vec<OsString>> {
+    // The first argument is the name of the executable, global options may precede the subcommand
+    let position = args
+        .iter()
+        .skip(1)
+        .position(|arg| arg == command)
+        .map(|i| i + 2)
+        .ok_or(anyhow!("unable to locate the `{command}` subcommand"))?;
+
+    let mut configured_args = args[..position].to_vec();
+    configured_args.extend(options);
+    configured_args.extend_from_slice(&args[position..]);
+    Ok(configured_args)
+}
+
+/// Sort the given files by their function, files with explicitly assigned roles are exempt
+fn sort_files(files: Vec<PathBuf>, roles: FileRoles) -> Result<WithWarnings<Files, FilesWarning>> {
+    Ok(Files::sort(files)
+        .context("unable to sort the given files by their function")?
+        .assign(roles))
+}
+
+/// Read and concatenate the given user guides, if any
+fn read_user_guides(paths: &[PathBuf]) -> Result<Option<fol::UserGuide>> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    let mut entries = vec![];
+    for path in paths {
+        entries.extend(fol::UserGuide::from_file(path)?);
+    }
+
+    Ok(Some(entries.into_iter().collect()))
+}
+
+/// Read and concatenate the given proof outlines, if any
+fn read_proof_outlines(paths: &[PathBuf]) -> Result<Option<fol::Specification>> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    let mut formulas = vec![];
+    for path in paths {
+        formulas.extend(fol::Specification::from_file(path)?);
+    }
+
+    Ok(Some(formulas.into_iter().collect()))
+}
+
+/// Print the warnings of a task according to the output format and return the data
+fn report_warnings<D, W: Display>(result: WithWarnings<D, W>, output: OutputFormat) -> D {
+    match output {
+        OutputFormat::Text => result.report_warnings(),
+        OutputFormat::Json => {
+            for warning in result.warnings {
+                println!(
+                    "{}",
+                    json!({ "type": "warning", "message": warning.to_string() })
+                );
+            }
+            result.data
+        }
+    }
+}
+
+fn print_pre_check_report<C: Display>(report: &BoundedCheckReport<C>, output: OutputFormat) {
+    match output {
+        OutputFormat::Text => {
+            match &report.counterexample {
+                Some(counterexample) if report.exact => {
+                    println!("> Counterexample! The bounded pre-check found an instance on which the claim fails:");
+                    println!("{counterexample}");
                 }
+                Some(counterexample) => {
+                    println!("> Possible counterexample! The bounded pre-check found an instance on which the claim fails when quantifiers range over a small universe:");
+                    println!("{counterexample}");
+                    println!(
+                        "> This may be an artifact of the bounds, so Anthem continues as usual."
+                    );
+                }
+                None => {
+                    print!(
+                        "> The bounded pre-check found no counterexample among {} instances",
+                        report.instances
+                    );
+                    if report.complete {
+                        println!(".");
+                    } else {
+                        println!(" (the bounds were not exhausted).");
+                    }
+                }
+            }
+            println!();
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            json!({
+                "type": "pre_check",
+                "instances": report.instances,
+                "complete": report.complete,
+                "exact": report.exact,
+                "counterexample": report.counterexample.as_ref().map(ToString::to_string),
+            })
+        ),
+    }
+}
 
-                if success {
-                    print!("> Success! Anthem found a proof of equivalence.")
+fn print_elapsed(start_time: Option<Instant>) {
+    match start_time {
+        Some(start) => println!(" ({} ms)", start.elapsed().as_millis()),
+        None => println!(),
+    }
+}
+
+/// The outcome of proving a series of problems
+struct Summary {
+    total: usize,
+    proven: usize,
+    timeouts: usize,
+    tool_errors: usize,
+    contradictions: usize,
+    blocked: usize,
+    /// The problems whose proof search ended without a proof
+    failed: Vec<Problem>,
+}
+
+impl Summary {
+    fn success(&self) -> bool {
+        self.proven == self.total
+    }
+
+    /// The number of problems whose proof search ended without a proof, except by a timeout
+    fn failures(&self) -> usize {
+        self.total
+            - self.proven
+            - self.timeouts
+            - self.tool_errors
+            - self.contradictions
+            - self.blocked
+    }
+
+    fn outcome(&self) -> Outcome {
+        if self.contradictions > 0 {
+            Outcome::InvalidInput
+        } else if self.tool_errors > 0 {
+            Outcome::ToolError
+        } else if self.proven + self.timeouts + self.blocked < self.total {
+            Outcome::ProofFailure
+        } else if self.timeouts > 0 {
+            Outcome::Timeout
+        } else {
+            Outcome::Success
+        }
+    }
+
+    /// Print the summary record, including the predicates of the context a claim is relative to, if any
+    fn print_record(&self, start_time: Option<Instant>, context: Option<Vec<String>>) {
+        let mut record = json!({
+            "type": "summary",
+            "success": self.success(),
+            "problems": self.total,
+            "proven": self.proven,
+            "failed": self.failures(),
+            "timeouts": self.timeouts,
+            "tool_errors": self.tool_errors,
+            "contradictions": self.contradictions,
+            "blocked": self.blocked,
+            "elapsed": start_time.map(|start| start.elapsed().as_millis() as u64),
+        });
+        if let Some(context) = context {
+            record["context"] = json!(context);
+        }
+        println!("{record}");
+    }
+}
+
+/// Prove the given problems with vampire, or replay or record the outcomes in the given directories
+///
+/// If a cache file is given, the problems proven in earlier runs are reused and the new outcomes are added to the cache.
+fn prove_with(
+    vampire: Vampire,
+    replay: Option<PathBuf>,
+    record: Option<PathBuf>,
+    cache: Option<PathBuf>,
+    problems: Vec<Problem>,
+    output: OutputFormat,
+) -> Result<Summary> {
+    let mut cache = cache.map(Cache::load).transpose()?;
+
+    let summary = match (replay, record) {
+        (Some(directory), _) => prove(
+            Replay {
+                directory,
+                time_execution: vampire.time_execution,
+            },
+            problems,
+            output,
+            cache.as_mut(),
+        ),
+        (None, Some(directory)) => prove(
+            Record {
+                prover: vampire,
+                directory,
+            },
+            problems,
+            output,
+            cache.as_mut(),
+        ),
+        (None, None) => prove(vampire, problems, output, cache.as_mut()),
+    };
+
+    if let Some(cache) = cache {
+        cache.save()?;
+    }
+
+    Ok(summary)
+}
+
+#[derive(Debug, Error)]
+#[error("the axioms of the following problems are contradictory: {}", .0.join(", "))]
+struct ContradictoryAxioms(Vec<String>);
+
+/// Check the consistency of the axioms of the given problems with vampire, or replay or record the outcomes in the given directories
+fn check_consistency_with(
+    vampire: Vampire,
+    replay: Option<PathBuf>,
+    record: Option<PathBuf>,
+    problems: &[Problem],
+    output: OutputFormat,
+) -> Result<()> {
+    match (replay, record) {
+        (Some(directory), _) => check_consistency(
+            Replay {
+                directory,
+                time_execution: vampire.time_execution,
+            },
+            problems,
+            output,
+        ),
+        (None, Some(directory)) => check_consistency(
+            Record {
+                prover: vampire,
+                directory,
+            },
+            problems,
+            output,
+        ),
+        (None, None) => check_consistency(vampire, problems, output),
+    }
+}
+
+/// Ask the prover whether the axioms of the given problems are contradictory, which would make every conjecture trivially provable
+///
+/// Every distinct set of axioms is checked once, by an attempt to derive a contradiction from it.
+fn check_consistency<P>(prover: P, problems: &[Problem], output: OutputFormat) -> Result<()>
+where
+    P: Prover,
+    P::Error: Display,
+{
+    let mut axioms = HashSet::new();
+    let problems: Vec<_> = problems
+        .iter()
+        .filter(|problem| axioms.insert(problem.axioms()))
+        .map(Problem::consistency_problem)
+        .collect();
+
+    let problems = problems.into_iter().inspect(move |problem| {
+        if output == OutputFormat::Text {
+            println!("> Checking {}...", problem.name);
+            println!();
+        }
+    });
+
+    let mut contradictory = vec![];
+    for result in prover.prove_all(problems) {
+        if let Ok(report) = &result {
+            if let Ok(Status::Success(Success::Theorem | Success::ContradictoryAxioms)) =
+                report.status()
+            {
+                let name = &report.problem().name;
+                contradictory.push(
+                    name.strip_suffix("_consistency")
+                        .unwrap_or(name)
+                        .to_string(),
+                );
+            }
+        }
+
+        match output {
+            OutputFormat::Text => print_result(result),
+            OutputFormat::Json => print_result_record(prover.name(), result),
+        }
+    }
+
+    if contradictory.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid_input(ContradictoryAxioms(contradictory)))
+    }
+}
+
+/// Search for lemmas helping with the given failed problems with vampire, or replay or record the outcomes in the given directories
+///
+/// The candidate lemmas are proven within the given time limit, the helpful ones are returned.
+fn suggest_lemmas_with(
+    vampire: Vampire,
+    time_limit: usize,
+    replay: Option<PathBuf>,
+    record: Option<PathBuf>,
+    failed: &[Problem],
+    public_predicates: &IndexSet<fol::Predicate>,
+    output: OutputFormat,
+) -> Vec<fol::AnnotatedFormula> {
+    let candidate_prover = Vampire {
+        time_limit,
+        ..vampire.clone()
+    };
+
+    match (replay, record) {
+        (Some(directory), _) => {
+            let replay = Replay {
+                directory,
+                time_execution: vampire.time_execution,
+            };
+            suggest_lemmas(replay.clone(), replay, failed, public_predicates, output)
+        }
+        (None, Some(directory)) => suggest_lemmas(
+            Record {
+                prover: candidate_prover,
+                directory: directory.clone(),
+            },
+            Record {
+                prover: vampire,
+                directory,
+            },
+            failed,
+            public_predicates,
+            output,
+        ),
+        (None, None) => {
+            suggest_lemmas(candidate_prover, vampire, failed, public_predicates, output)
+        }
+    }
+}
+
+/// Propose candidate lemmas for every failed problem and try them with the candidate prover
+///
+/// The proven candidates help with a problem if the prover proves the problem once they are added to its axioms.
+fn suggest_lemmas<P>(
+    candidate_prover: P,
+    prover: P,
+    failed: &[Problem],
+    public_predicates: &IndexSet<fol::Predicate>,
+    output: OutputFormat,
+) -> Vec<fol::AnnotatedFormula>
+where
+    P: Prover,
+{
+    let mut helpful = IndexSet::new();
+
+    for problem in failed {
+        if output == OutputFormat::Text {
+            println!("> Searching for lemmas helping with {}...", problem.name);
+        }
+
+        // Definitions are only restricted to the arguments proven to be integers
+        let typing = suggestion::typing_lemmas(problem);
+        let mut proven = proven_candidates(
+            &candidate_prover,
+            problem,
+            typing
+                .values()
+                .cloned()
+                .chain(suggestion::inductive_generalizations(problem))
+                .collect(),
+        );
+
+        let integer_arguments = typing
+            .iter()
+            .filter(|(_, typing_lemma)| proven.contains(typing_lemma))
+            .map(|(argument, _)| argument.clone())
+            .collect();
+        proven.extend(proven_candidates(
+            &candidate_prover,
+            problem,
+            suggestion::restricted_definitions(problem, public_predicates, &integer_arguments),
+        ));
+
+        let helps = !proven.is_empty()
+            && prover
+                .prove(suggestion::strengthen(problem, &proven))
+                .is_ok_and(|report| {
+                    matches!(report.status(), Ok(Status::Success(Success::Theorem)))
+                });
+
+        match output {
+            OutputFormat::Text => {
+                for candidate in &proven {
+                    println!("Proven: {}", candidate.formula);
+                }
+                if helps {
+                    println!("> The proven lemmas help to prove {}.", problem.name);
                 } else {
-                    print!("> Failure! Anthem was unable to find a proof of equivalence.")
+                    println!("> No helpful lemmas were found for {}.", problem.name);
                 }
+                println!();
+            }
+            OutputFormat::Json => println!(
+                "{}",
+                json!({
+                    "type": "suggestions",
+                    "name": problem.name,
+                    "lemmas": proven
+                        .iter()
+                        .map(|candidate| candidate.formula.to_string())
+                        .collect::<Vec<_>>(),
+                    "helpful": helps,
+                })
+            ),
+        }
+
+        if helps {
+            helpful.extend(proven.into_iter().map(|candidate| candidate.formula));
+        }
+    }
+
+    helpful.into_iter().collect()
+}
+
+/// The candidates whose conjectures the prover proves from the axioms of the given problem
+fn proven_candidates<P: Prover>(
+    prover: &P,
+    problem: &Problem,
+    candidates: Vec<suggestion::Candidate>,
+) -> Vec<suggestion::Candidate> {
+    let problems: Vec<_> = candidates
+        .iter()
+        .flat_map(|candidate| candidate.problems(problem))
+        .collect();
+
+    let proven: HashSet<_> = prover
+        .prove_all(problems)
+        .filter_map(Result::ok)
+        .filter(|report| matches!(report.status(), Ok(Status::Success(Success::Theorem))))
+        .map(|report| report.problem().name.clone())
+        .collect();
+
+    candidates
+        .into_iter()
+        .filter(|candidate| {
+            candidate
+                .problems(problem)
+                .iter()
+                .all(|problem| proven.contains(&problem.name))
+        })
+        .collect()
+}
+
+/// Prove the given problems while reporting the progress in the given output format
+///
+/// The problems are proven in waves: each wave consists of the problems whose dependencies have been proven,
+/// so independent problems are proven in parallel and a problem that could not be proven only blocks its dependents.
+/// Problems found proven in the cache are reused, unless one of their dependencies had to be proven again.
+fn prove<P>(
+    prover: P,
+    problems: Vec<Problem>,
+    output: OutputFormat,
+    mut cache: Option<&mut Cache>,
+) -> Summary
+where
+    P: Prover,
+    P::Error: Display,
+{
+    let mut summary = Summary {
+        total: problems.len(),
+        proven: 0,
+        timeouts: 0,
+        tool_errors: 0,
+        contradictions: 0,
+        blocked: 0,
+        failed: vec![],
+    };
+
+    // Dependencies on problems outside of the given ones are considered to be satisfied
+    let known: HashSet<String> = problems.iter().map(|p| p.name.clone()).collect();
+    let mut proven = HashSet::new();
+    let mut settled = HashSet::new();
+    let mut attempted = HashSet::new();
+    let mut pending = problems;
 
-                match start_time {
-                    Some(start) => println!(" ({} ms)", start.elapsed().as_millis()),
-                    None => println!(),
+    while !pending.is_empty() {
+        let (blocked, remaining): (Vec<_>, Vec<_>) = pending.into_iter().partition(|problem| {
+            problem
+                .dependencies
+                .iter()
+                .any(|d| settled.contains(d) && !proven.contains(d))
+        });
+
+        let skipped = !blocked.is_empty();
+        for problem in blocked {
+            let unproven: Vec<_> = problem
+                .dependencies
+                .iter()
+                .filter(|d| settled.contains(*d) && !proven.contains(*d))
+                .cloned()
+                .collect();
+
+            match output {
+                OutputFormat::Text => {
+                    println!(
+                        "> Skipping {}, which depends on unproven problems: {}",
+                        problem.name,
+                        unproven.join(", ")
+                    );
+                    println!();
                 }
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "type": "blocked",
+                        "name": problem.name,
+                        "dependencies": unproven,
+                    })
+                ),
             }
 
-            Ok(())
+            summary.blocked += 1;
+            settled.insert(problem.name);
+        }
+
+        let (mut ready, mut waiting): (Vec<_>, Vec<_>) =
+            remaining.into_iter().partition(|problem| {
+                problem
+                    .dependencies
+                    .iter()
+                    .all(|d| proven.contains(d) || !known.contains(d))
+            });
+
+        // Skipped problems may block further problems, otherwise only cyclic dependencies prevent progress
+        if ready.is_empty() {
+            if skipped {
+                pending = waiting;
+                continue;
+            }
+            ready = std::mem::take(&mut waiting);
+        }
+
+        settled.extend(ready.iter().map(|p| p.name.clone()));
+
+        let (cached, ready): (Vec<_>, Vec<_>) = ready.into_iter().partition(|problem| {
+            cache.as_ref().is_some_and(|cache| cache.proven(problem))
+                && !problem.dependencies.iter().any(|d| attempted.contains(d))
+        });
+
+        for problem in cached {
+            match output {
+                OutputFormat::Text => {
+                    println!("> Proving {} ended with a SZS status", problem.name);
+                    println!("Status: {} (cached)", Status::Success(Success::Theorem));
+                    println!();
+                }
+                OutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "type": "problem",
+                        "name": problem.name,
+                        "axioms": problem
+                            .axioms()
+                            .iter()
+                            .map(|axiom| axiom.formula.to_string())
+                            .collect::<Vec<_>>(),
+                        "conjectures": problem
+                            .conjectures()
+                            .iter()
+                            .map(|conjecture| conjecture.formula.to_string())
+                            .collect::<Vec<_>>(),
+                        "prover": prover.name(),
+                        "elapsed": null,
+                        "status": Status::Success(Success::Theorem).to_string(),
+                        "cached": true,
+                    })
+                ),
+            }
+
+            summary.proven += 1;
+            proven.insert(problem.name);
+        }
+
+        attempted.extend(ready.iter().map(|p| p.name.clone()));
+
+        let ready = ready.into_iter().inspect(move |problem| {
+            if output == OutputFormat::Text {
+                println!("> Proving {}...", problem.name);
+                println!("Axioms:");
+                for axiom in problem.axioms() {
+                    println!("    {}", axiom.formula);
+                }
+                println!();
+                println!("Conjectures:");
+                for conjecture in problem.conjectures() {
+                    println!("    {}", conjecture.formula);
+                }
+                println!();
+            }
+        });
+
+        for result in prover.prove_all(ready) {
+            match result.as_ref().map(Report::status) {
+                Ok(Ok(Status::Success(Success::Theorem))) => summary.proven += 1,
+                Ok(Ok(Status::Failure(Failure::TimeOut))) => summary.timeouts += 1,
+                Ok(Ok(Status::Success(Success::ContradictoryAxioms))) => {
+                    summary.contradictions += 1
+                }
+                Ok(Ok(_)) => (),
+                Ok(Err(_)) | Err(_) => summary.tool_errors += 1,
+            }
+
+            if let Ok(report) = &result {
+                if let Ok(status) = report.status() {
+                    match status {
+                        Status::Success(Success::Theorem) => {
+                            proven.insert(report.problem().name.clone());
+                        }
+                        Status::Success(Success::ContradictoryAxioms) => (),
+                        _ => summary.failed.push(report.problem().clone()),
+                    }
+                    if let Some(cache) = cache.as_mut() {
+                        cache.insert(report.problem(), status);
+                    }
+                }
+            }
+
+            match output {
+                OutputFormat::Text => print_result(result),
+                OutputFormat::Json => print_result_record(prover.name(), result),
+            }
+        }
+
+        pending = waiting;
+    }
+
+    summary
+}
+
+fn print_result<R: Report, E: Display>(result: Result<R, E>) {
+    match result {
+        Ok(report) => match report.status() {
+            Ok(status) => {
+                println!(
+                    "> Proving {} ended with a SZS status",
+                    report.problem().name
+                );
+
+                match report.start_time() {
+                    Some(start) => {
+                        println!("Status: {status} ({} ms)", start.elapsed().as_millis())
+                    }
+                    None => println!("Status: {status}"),
+                }
+
+                if status == Status::Success(Success::ContradictoryAxioms) {
+                    println!("Error: the axioms are contradictory, so every conjecture follows trivially");
+                }
+            }
+            Err(error) => {
+                match report.start_time() {
+                    Some(start) => println!(
+                        "> Proving {} ended without a SZS status ({} ms)",
+                        report.problem().name,
+                        start.elapsed().as_millis()
+                    ),
+                    None => println!(
+                        "> Proving {} ended without a SZS status",
+                        report.problem().name
+                    ),
+                }
+
+                println!("Output/stdout:");
+                println!("{}", report.stdout());
+                println!("Output/stderr:");
+                println!("{}", report.stderr());
+                println!("Error: {error}");
+            }
+        },
+        Err(error) => {
+            println!("> Proving <a problem> ended with an error"); // TODO: Get the name of the problem
+            println!("Error: {error}");
         }
     }
+    println!();
+}
+
+fn print_result_record<R: Report, E: Display>(prover: &str, result: Result<R, E>) {
+    let record = match result {
+        Ok(report) => {
+            let problem = report.problem();
+            let mut record = json!({
+                "type": "problem",
+                "name": problem.name,
+                "axioms": problem
+                    .axioms()
+                    .iter()
+                    .map(|axiom| axiom.formula.to_string())
+                    .collect::<Vec<_>>(),
+                "conjectures": problem
+                    .conjectures()
+                    .iter()
+                    .map(|conjecture| conjecture.formula.to_string())
+                    .collect::<Vec<_>>(),
+                "prover": prover,
+                "elapsed": report
+                    .start_time()
+                    .map(|start| start.elapsed().as_millis() as u64),
+            });
+
+            match report.status() {
+                Ok(status) => record["status"] = json!(status.to_string()),
+                Err(error) => {
+                    record["status"] = json!(null);
+                    record["error"] = json!(error.to_string());
+                    record["stdout"] = json!(report.stdout());
+                    record["stderr"] = json!(report.stderr());
+                }
+            }
+
+            record
+        }
+        // TODO: Get the name of the problem
+        Err(error) => json!({
+            "type": "problem",
+            "name": null,
+            "prover": prover,
+            "status": null,
+            "error": error.to_string(),
+        }),
+    };
+
+    println!("{record}");
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::syntax_tree::{asp, fol};
+    use {
+        super::inject_arguments,
+        crate::syntax_tree::{asp, fol},
+        std::ffi::OsString,
+    };
+
+    #[test]
+    fn inject_configured_arguments() {
+        let strings = |strings: &[&str]| strings.iter().map(OsString::from).collect::<Vec<_>>();
+
+        for (args, expected) in [
+            (
+                &["anthem", "verify", "a.lp"][..],
+                &["anthem", "verify", "--time-limit", "5", "a.lp"][..],
+            ),
+            (
+                &["anthem", "--global", "verify", "verify.lp"],
+                &[
+                    "anthem",
+                    "--global",
+                    "verify",
+                    "--time-limit",
+                    "5",
+                    "verify.lp",
+                ],
+            ),
+        ] {
+            assert_eq!(
+                inject_arguments(&strings(args), "verify", strings(&["--time-limit", "5"]))
+                    .unwrap(),
+                strings(expected)
+            );
+        }
+
+        assert!(inject_arguments(&strings(&["verify"]), "verify", strings(&[])).is_err());
+    }
 
     #[test]
     fn asp_program_parse_and_format() {
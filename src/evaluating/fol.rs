@@ -0,0 +1,464 @@
+use {
+    crate::{
+        solving::grounding::{compare, GroundAtom},
+        syntax_tree::{
+            asp::PrecomputedTerm,
+            fol::{
+                AnnotatedFormula, AtomicFormula, BinaryConnective, BinaryOperator, Comparison,
+                Formula, GeneralTerm, IntegerTerm, Predicate, Quantifier, Relation, Role, Sort,
+                Specification, SymbolicTerm, UnaryConnective, UnaryOperator, Variable,
+            },
+        },
+    },
+    indexmap::{IndexMap, IndexSet},
+    itertools::{Itertools as _, MinMaxResult},
+    std::collections::HashMap,
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum EvaluationError {
+    #[error("the placeholder `{0}` has no value")]
+    UnknownPlaceholder(String),
+    #[error("the value `{1}` of the placeholder `{0}` does not match its sort")]
+    InvalidPlaceholderValue(String, PrecomputedTerm),
+    #[error("the variable `{0}` is free")]
+    FreeVariable(Variable),
+    #[error("the value of the term `{0}` exceeds the range of integers")]
+    Overflow(IntegerTerm),
+}
+
+type Assignment = HashMap<Variable, PrecomputedTerm>;
+
+/// A finite structure interpreting the predicates and placeholders of first-order formulas
+///
+/// Comparisons and arithmetic are interpreted as in the standard interpretation.
+/// Variables range over the elements of the universe of the matching sort,
+/// which makes the truth of quantified formulas an approximation of the standard interpretation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Structure {
+    pub universe: IndexSet<PrecomputedTerm>,
+    pub extensions: IndexMap<Predicate, IndexSet<Vec<PrecomputedTerm>>>,
+    pub placeholders: IndexMap<String, PrecomputedTerm>,
+}
+
+impl Structure {
+    /// The structure in which exactly the given atoms are true
+    ///
+    /// The universe consists of the values occurring in the atoms and the values of the placeholders.
+    pub fn from_atoms<'a>(
+        atoms: impl IntoIterator<Item = &'a GroundAtom>,
+        placeholders: IndexMap<String, PrecomputedTerm>,
+    ) -> Self {
+        let mut structure = Structure {
+            universe: placeholders.values().cloned().collect(),
+            extensions: IndexMap::new(),
+            placeholders,
+        };
+
+        for atom in atoms {
+            structure.universe.extend(atom.terms.iter().cloned());
+            structure
+                .extensions
+                .entry(Predicate {
+                    symbol: atom.predicate_symbol.clone(),
+                    arity: atom.terms.len(),
+                })
+                .or_default()
+                .insert(atom.terms.clone());
+        }
+
+        structure
+    }
+
+    /// Add every integer between the least and the greatest integer of the universe to the universe
+    pub fn fill_integer_gaps(&mut self) {
+        let integers = self
+            .universe
+            .iter()
+            .filter_map(|value| match value {
+                PrecomputedTerm::Numeral(n) => Some(*n),
+                _ => None,
+            })
+            .minmax();
+
+        if let MinMaxResult::MinMax(min, max) = integers {
+            self.universe
+                .extend((min..=max).map(PrecomputedTerm::Numeral));
+        }
+    }
+
+    /// Decide whether the structure satisfies a closed formula
+    pub fn satisfies(&self, formula: &Formula) -> Result<bool, EvaluationError> {
+        self.evaluate(formula, &mut Assignment::new())
+    }
+
+    /// Find the `spec` formulas of a specification that the structure does not satisfy
+    ///
+    /// Free variables of the formulas are understood universally.
+    pub fn violations<'a>(
+        &self,
+        specification: &'a Specification,
+    ) -> Result<Vec<&'a AnnotatedFormula>, EvaluationError> {
+        let mut violations = vec![];
+
+        for formula in specification {
+            if formula.role == Role::Spec
+                && !self.satisfies(&formula.formula.clone().universal_closure())?
+            {
+                violations.push(formula);
+            }
+        }
+
+        Ok(violations)
+    }
+
+    fn domain(&self, sort: &Sort) -> Vec<PrecomputedTerm> {
+        self.universe
+            .iter()
+            .filter(|value| match sort {
+                Sort::General => true,
+                Sort::Integer => matches!(value, PrecomputedTerm::Numeral(_)),
+                Sort::Symbol => matches!(value, PrecomputedTerm::Symbol(_)),
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn placeholder(&self, name: &str) -> Result<&PrecomputedTerm, EvaluationError> {
+        self.placeholders
+            .get(name)
+            .ok_or_else(|| EvaluationError::UnknownPlaceholder(name.to_string()))
+    }
+
+    fn variable(
+        name: &str,
+        sort: Sort,
+        assignment: &Assignment,
+    ) -> Result<PrecomputedTerm, EvaluationError> {
+        let variable = Variable {
+            name: name.to_string(),
+            sort,
+        };
+
+        match assignment.get(&variable) {
+            Some(value) => Ok(value.clone()),
+            None => Err(EvaluationError::FreeVariable(variable)),
+        }
+    }
+
+    fn evaluate_integer_term(
+        &self,
+        term: &IntegerTerm,
+        assignment: &Assignment,
+    ) -> Result<isize, EvaluationError> {
+        let value = match term {
+            IntegerTerm::Numeral(n) => Some(*n),
+            IntegerTerm::FunctionConstant(name) => match self.placeholder(name)? {
+                PrecomputedTerm::Numeral(n) => Some(*n),
+                value => {
+                    return Err(EvaluationError::InvalidPlaceholderValue(
+                        name.clone(),
+                        value.clone(),
+                    ))
+                }
+            },
+            IntegerTerm::Variable(name) => match Self::variable(name, Sort::Integer, assignment)? {
+                PrecomputedTerm::Numeral(n) => Some(n),
+                _ => unreachable!("integer variables range over integers"),
+            },
+            IntegerTerm::UnaryOperation { op, arg } => {
+                let arg = self.evaluate_integer_term(arg, assignment)?;
+                match op {
+                    UnaryOperator::Negative => arg.checked_neg(),
+                    UnaryOperator::AbsoluteValue => arg.checked_abs(),
+                }
+            }
+            IntegerTerm::BinaryOperation { op, lhs, rhs } => {
+                let lhs = self.evaluate_integer_term(lhs, assignment)?;
+                let rhs = self.evaluate_integer_term(rhs, assignment)?;
+                match op {
+                    BinaryOperator::Add => lhs.checked_add(rhs),
+                    BinaryOperator::Subtract => lhs.checked_sub(rhs),
+                    BinaryOperator::Multiply => lhs.checked_mul(rhs),
+                }
+            }
+        };
+
+        value.ok_or_else(|| EvaluationError::Overflow(term.clone()))
+    }
+
+    fn evaluate_symbolic_term(
+        &self,
+        term: &SymbolicTerm,
+        assignment: &Assignment,
+    ) -> Result<PrecomputedTerm, EvaluationError> {
+        match term {
+            SymbolicTerm::Symbol(s) => Ok(PrecomputedTerm::Symbol(s.clone())),
+            SymbolicTerm::FunctionConstant(name) => match self.placeholder(name)? {
+                value @ PrecomputedTerm::Symbol(_) => Ok(value.clone()),
+                value => Err(EvaluationError::InvalidPlaceholderValue(
+                    name.clone(),
+                    value.clone(),
+                )),
+            },
+            SymbolicTerm::Variable(name) => Self::variable(name, Sort::Symbol, assignment),
+        }
+    }
+
+    fn evaluate_general_term(
+        &self,
+        term: &GeneralTerm,
+        assignment: &Assignment,
+    ) -> Result<PrecomputedTerm, EvaluationError> {
+        match term {
+            GeneralTerm::Infimum => Ok(PrecomputedTerm::Infimum),
+            GeneralTerm::Supremum => Ok(PrecomputedTerm::Supremum),
+            GeneralTerm::FunctionConstant(name) => self.placeholder(name).cloned(),
+            GeneralTerm::Variable(name) => Self::variable(name, Sort::General, assignment),
+            GeneralTerm::IntegerTerm(term) => self
+                .evaluate_integer_term(term, assignment)
+                .map(PrecomputedTerm::Numeral),
+            GeneralTerm::SymbolicTerm(term) => self.evaluate_symbolic_term(term, assignment),
+        }
+    }
+
+    fn evaluate_comparison(
+        &self,
+        comparison: &Comparison,
+        assignment: &Assignment,
+    ) -> Result<bool, EvaluationError> {
+        for (lhs, relation, rhs) in comparison.individuals() {
+            let ordering = compare(
+                &self.evaluate_general_term(lhs, assignment)?,
+                &self.evaluate_general_term(rhs, assignment)?,
+            );
+
+            let holds = match relation {
+                Relation::Equal => ordering.is_eq(),
+                Relation::NotEqual => ordering.is_ne(),
+                Relation::Less => ordering.is_lt(),
+                Relation::LessEqual => ordering.is_le(),
+                Relation::Greater => ordering.is_gt(),
+                Relation::GreaterEqual => ordering.is_ge(),
+            };
+
+            if !holds {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn evaluate(
+        &self,
+        formula: &Formula,
+        assignment: &mut Assignment,
+    ) -> Result<bool, EvaluationError> {
+        match formula {
+            Formula::AtomicFormula(AtomicFormula::Truth) => Ok(true),
+            Formula::AtomicFormula(AtomicFormula::Falsity) => Ok(false),
+            Formula::AtomicFormula(AtomicFormula::Atom(atom)) => {
+                let tuple = atom
+                    .terms
+                    .iter()
+                    .map(|term| self.evaluate_general_term(term, assignment))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(self
+                    .extensions
+                    .get(&atom.predicate())
+                    .is_some_and(|extension| extension.contains(&tuple)))
+            }
+            Formula::AtomicFormula(AtomicFormula::Comparison(comparison)) => {
+                self.evaluate_comparison(comparison, assignment)
+            }
+            Formula::UnaryFormula {
+                connective: UnaryConnective::Negation,
+                formula,
+            } => Ok(!self.evaluate(formula, assignment)?),
+            Formula::BinaryFormula {
+                connective,
+                lhs,
+                rhs,
+            } => {
+                let lhs = self.evaluate(lhs, assignment)?;
+                // The right-hand side is evaluated eagerly so that errors are reported independently of the left-hand side
+                let rhs = self.evaluate(rhs, assignment)?;
+                Ok(match connective {
+                    BinaryConnective::Conjunction => lhs && rhs,
+                    BinaryConnective::Disjunction => lhs || rhs,
+                    BinaryConnective::Implication => !lhs || rhs,
+                    BinaryConnective::ReverseImplication => lhs || !rhs,
+                    BinaryConnective::Equivalence => lhs == rhs,
+                })
+            }
+            Formula::QuantifiedFormula {
+                quantification,
+                formula,
+            } => self.evaluate_quantification(
+                &quantification.quantifier,
+                &quantification.variables,
+                formula,
+                assignment,
+            ),
+        }
+    }
+
+    fn evaluate_quantification(
+        &self,
+        quantifier: &Quantifier,
+        variables: &[Variable],
+        formula: &Formula,
+        assignment: &mut Assignment,
+    ) -> Result<bool, EvaluationError> {
+        let Some((variable, variables)) = variables.split_first() else {
+            return self.evaluate(formula, assignment);
+        };
+
+        let shadowed = assignment.remove(variable);
+
+        let mut result = matches!(quantifier, Quantifier::Forall);
+        for value in self.domain(&variable.sort) {
+            assignment.insert(variable.clone(), value);
+            let holds = self.evaluate_quantification(quantifier, variables, formula, assignment)?;
+
+            if holds != result {
+                result = holds;
+                break;
+            }
+        }
+
+        assignment.remove(variable);
+        if let Some(value) = shadowed {
+            assignment.insert(variable.clone(), value);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{EvaluationError, Structure},
+        crate::{
+            solving::grounding::GroundAtom,
+            syntax_tree::{asp::PrecomputedTerm, fol},
+        },
+        indexmap::{IndexMap, IndexSet},
+    };
+
+    fn structure() -> Structure {
+        Structure {
+            universe: IndexSet::from([
+                PrecomputedTerm::Numeral(1),
+                PrecomputedTerm::Numeral(2),
+                PrecomputedTerm::Numeral(3),
+                PrecomputedTerm::Symbol("a".into()),
+            ]),
+            extensions: IndexMap::from([(
+                "p/1".parse::<fol::Predicate>().unwrap(),
+                IndexSet::from([
+                    vec![PrecomputedTerm::Numeral(1)],
+                    vec![PrecomputedTerm::Symbol("a".into())],
+                ]),
+            )]),
+            placeholders: IndexMap::from([("n".into(), PrecomputedTerm::Numeral(2))]),
+        }
+    }
+
+    #[test]
+    fn test_satisfies() {
+        for (formula, target) in [
+            ("p(1) and not p(2)", true),
+            ("exists X$i (p(X$i) and X$i > 1)", false),
+            ("forall X (p(X) -> X = 1 or X = a)", true),
+            ("forall X$s p(X$s)", true),
+            ("forall X$i (X$i < n$i -> p(X$i))", true),
+            ("exists X$i Y$i (X$i = Y$i * 3)", true),
+            ("forall X (X < a)", false),
+            ("1 < 2 < a < #sup", true),
+            ("forall X$i (X$i <= 3 <-> #true)", true),
+        ] {
+            let formula: fol::Formula = formula.parse().unwrap();
+            assert_eq!(
+                structure().satisfies(&formula).unwrap(),
+                target,
+                "{formula}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_satisfies_errors() {
+        let structure = structure();
+
+        assert!(matches!(
+            structure.satisfies(&"p(X)".parse().unwrap()),
+            Err(EvaluationError::FreeVariable(_))
+        ));
+        assert!(matches!(
+            structure.satisfies(&"p(m$g)".parse().unwrap()),
+            Err(EvaluationError::UnknownPlaceholder(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_atoms() {
+        let atoms = [
+            GroundAtom {
+                predicate_symbol: "p".into(),
+                terms: vec![PrecomputedTerm::Numeral(5)],
+            },
+            GroundAtom {
+                predicate_symbol: "p".into(),
+                terms: vec![PrecomputedTerm::Symbol("a".into())],
+            },
+        ];
+        let mut structure = Structure::from_atoms(
+            &atoms,
+            IndexMap::from([("n".into(), PrecomputedTerm::Numeral(2))]),
+        );
+
+        assert_eq!(structure.universe.len(), 3);
+        assert!(structure
+            .satisfies(&"p(5) and p(a) and not p(n$i)".parse().unwrap())
+            .unwrap());
+
+        structure.fill_integer_gaps();
+        assert_eq!(
+            structure.universe,
+            IndexSet::from([
+                PrecomputedTerm::Numeral(2),
+                PrecomputedTerm::Numeral(5),
+                PrecomputedTerm::Symbol("a".into()),
+                PrecomputedTerm::Numeral(3),
+                PrecomputedTerm::Numeral(4),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_violations() {
+        let specification: fol::Specification = [
+            "spec: forall X (p(X) -> X = 1 or X = a).",
+            "assumption: p(2).",
+            "spec: p(X$i) -> X$i > 1.",
+            "spec: q -> p(3).",
+        ]
+        .join("\n")
+        .parse()
+        .unwrap();
+
+        let violations = structure().violations(&specification).unwrap();
+        assert_eq!(
+            violations
+                .into_iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["spec: p(X$i) -> X$i > 1"]
+        );
+    }
+}
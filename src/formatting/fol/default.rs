@@ -4,10 +4,10 @@ use {
         syntax_tree::{
             fol::{
                 AnnotatedFormula, Atom, AtomicFormula, BinaryConnective, BinaryOperator,
-                Comparison, Direction, Formula, FunctionConstant, GeneralTerm, Guard, IntegerTerm,
-                PlaceholderDeclaration, Predicate, Quantification, Quantifier, Relation, Role,
-                Sort, Specification, SymbolicTerm, Theory, UnaryConnective, UnaryOperator,
-                UserGuide, UserGuideEntry, Variable,
+                Comparison, Direction, Formula, FunctionConstant, GeneralTerm, Guard,
+                InductionScheme, IntegerTerm, PlaceholderDeclaration, Predicate, Quantification,
+                Quantifier, Relation, Role, Sort, Specification, SymbolicTerm, Theory,
+                UnaryConnective, UnaryOperator, UserGuide, UserGuideEntry, Variable,
             },
             Node,
         },
@@ -343,6 +343,16 @@ impl Display for Format<'_, Formula> {
         match self.0 {
             Formula::AtomicFormula(a) => Format(a).fmt(f),
             Formula::UnaryFormula { formula, .. } => self.fmt_unary(Format(formula.as_ref()), f),
+            // A comparison may begin with a variable, which would otherwise be read as a quantified variable
+            Formula::QuantifiedFormula { formula, .. }
+                if matches!(
+                    formula.as_ref(),
+                    Formula::AtomicFormula(AtomicFormula::Comparison(_))
+                ) =>
+            {
+                self.fmt_operator(f)?;
+                write!(f, "({})", Format(formula.as_ref()))
+            }
             Formula::QuantifiedFormula { formula, .. } => {
                 self.fmt_unary(Format(formula.as_ref()), f)
             }
@@ -371,7 +381,10 @@ impl Display for Format<'_, Role> {
             Role::Spec => write!(f, "spec"),
             Role::Lemma => write!(f, "lemma"),
             Role::Definition => write!(f, "definition"),
-            Role::InductiveLemma => write!(f, "inductive-lemma"),
+            Role::InductiveLemma(InductionScheme::Simple) => write!(f, "inductive-lemma"),
+            Role::InductiveLemma(InductionScheme::Strong) => write!(f, "strong-inductive-lemma"),
+            Role::InductiveLemma(InductionScheme::Step(k)) => write!(f, "inductive-lemma-step-{k}"),
+            Role::CaseLemma => write!(f, "case-lemma"),
         }
     }
 }
@@ -398,6 +411,10 @@ impl Display for Format<'_, AnnotatedFormula> {
             write!(f, "[{}]", self.0.name)?;
         }
 
+        if let Some(using) = &self.0.using {
+            write!(f, " using [{}]", using.join(", "))?;
+        }
+
         write!(f, ": ")?;
 
         Format(&self.0.formula).fmt(f)?;
@@ -449,9 +466,9 @@ mod tests {
         formatting::fol::default::Format,
         syntax_tree::fol::{
             AnnotatedFormula, Atom, AtomicFormula, BinaryConnective, BinaryOperator, Comparison,
-            Direction, Formula, GeneralTerm, Guard, IntegerTerm, Quantification, Quantifier,
-            Relation, Role, Sort, Specification, SymbolicTerm, UnaryConnective, UnaryOperator,
-            Variable,
+            Direction, Formula, GeneralTerm, Guard, InductionScheme, IntegerTerm, Quantification,
+            Quantifier, Relation, Role, Sort, Specification, SymbolicTerm, UnaryConnective,
+            UnaryOperator, Variable,
         },
     };
 
@@ -865,6 +882,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn format_quantified_comparison() {
+        let formula = Formula::QuantifiedFormula {
+            quantification: Quantification {
+                quantifier: Quantifier::Exists,
+                variables: vec![Variable {
+                    name: "N".into(),
+                    sort: Sort::Integer,
+                }],
+            },
+            formula: Formula::AtomicFormula(AtomicFormula::Comparison(Comparison {
+                term: GeneralTerm::Variable("X".into()),
+                guards: vec![Guard {
+                    relation: Relation::Equal,
+                    term: GeneralTerm::IntegerTerm(IntegerTerm::Variable("N".into())),
+                }],
+            }))
+            .into(),
+        };
+
+        // Without parentheses, the variable `X` would be read as a second quantified variable
+        let formatted = Format(&formula).to_string();
+        assert_eq!(formatted, "exists N$i (X = N$i)");
+        assert_eq!(formatted.parse::<Formula>().unwrap(), formula);
+    }
+
     #[test]
     fn format_specification() {
         let left = Format(&Specification {
@@ -873,6 +916,7 @@ mod tests {
                     role: Role::Spec,
                     direction: Direction::Forward,
                     name: "about_p_0".to_string(),
+                    using: None,
                     formula: Formula::UnaryFormula {
                         connective: UnaryConnective::Negation,
                         formula: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
@@ -886,15 +930,17 @@ mod tests {
                     role: Role::Assumption,
                     direction: Direction::Universal,
                     name: String::default(),
+                    using: None,
                     formula: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
                         predicate_symbol: "p".into(),
                         terms: vec![GeneralTerm::IntegerTerm(IntegerTerm::Numeral(5))],
                     })),
                 },
                 AnnotatedFormula {
-                    role: Role::InductiveLemma,
+                    role: Role::InductiveLemma(InductionScheme::Simple),
                     direction: Direction::Backward,
                     name: "il1".to_string(),
+                    using: None,
                     formula: Formula::QuantifiedFormula {
                         quantification: Quantification {
                             quantifier: Quantifier::Forall,
@@ -2,15 +2,17 @@ pub mod analyzing;
 pub mod breaking;
 pub mod command_line;
 pub mod convenience;
+pub mod evaluating;
 pub mod formatting;
 pub mod parsing;
 pub mod simplifying;
+pub mod solving;
 pub mod syntax_tree;
 pub mod translating;
 pub mod verifying;
 
-use anyhow::Result;
+use std::process::ExitCode;
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     crate::command_line::procedures::main()
 }
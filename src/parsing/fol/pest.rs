@@ -2,7 +2,7 @@ use crate::{
     parsing::PestParser,
     syntax_tree::fol::{
         AnnotatedFormula, Atom, AtomicFormula, BinaryConnective, BinaryOperator, Comparison,
-        Direction, Formula, FunctionConstant, GeneralTerm, Guard, IntegerTerm,
+        Direction, Formula, FunctionConstant, GeneralTerm, Guard, InductionScheme, IntegerTerm,
         PlaceholderDeclaration, Predicate, Quantification, Quantifier, Relation, Role, Sort,
         Specification, SymbolicTerm, Theory, UnaryConnective, UnaryOperator, UserGuide,
         UserGuideEntry, Variable,
@@ -632,7 +632,14 @@ impl PestParser for RoleParser {
             internal::Rule::spec => Role::Spec,
             internal::Rule::lemma => Role::Lemma,
             internal::Rule::definition => Role::Definition,
-            internal::Rule::inductive_lemma => Role::InductiveLemma,
+            internal::Rule::strong_inductive_lemma => Role::InductiveLemma(InductionScheme::Strong),
+            internal::Rule::inductive_lemma => match pair.into_inner().next() {
+                Some(step) => {
+                    Role::InductiveLemma(InductionScheme::Step(step.as_str().parse().unwrap()))
+                }
+                None => Role::InductiveLemma(InductionScheme::Simple),
+            },
+            internal::Rule::case_lemma => Role::CaseLemma,
             _ => Self::report_unexpected_pair(pair),
         }
     }
@@ -694,6 +701,14 @@ impl PestParser for AnnotatedFormulaParser {
             name = String::default();
         }
 
+        let using;
+        if matches!(next.as_rule(), internal::Rule::using) {
+            using = Some(next.into_inner().map(|pair| pair.as_str().into()).collect());
+            next = pairs.next().unwrap_or_else(|| Self::report_missing_pair());
+        } else {
+            using = None;
+        }
+
         let formula = FormulaParser::translate_pair(next);
 
         if let Some(pair) = pairs.next() {
@@ -704,6 +719,7 @@ impl PestParser for AnnotatedFormulaParser {
             role,
             direction,
             name,
+            using,
             formula,
         }
     }
@@ -824,14 +840,15 @@ mod tests {
             AnnotatedFormulaParser, AtomParser, AtomicFormulaParser, BinaryConnectiveParser,
             BinaryOperatorParser, ComparisonParser, FormulaParser, GeneralTermParser, GuardParser,
             IntegerTermParser, PredicateParser, QuantificationParser, QuantifierParser,
-            RelationParser, SortParser, SpecificationParser, SymbolicTermParser, TheoryParser,
-            UnaryConnectiveParser, UnaryOperatorParser, UserGuideParser, VariableParser,
+            RelationParser, RoleParser, SortParser, SpecificationParser, SymbolicTermParser,
+            TheoryParser, UnaryConnectiveParser, UnaryOperatorParser, UserGuideParser,
+            VariableParser,
         },
         crate::{
             parsing::TestedParser,
             syntax_tree::fol::{
                 AnnotatedFormula, Atom, AtomicFormula, BinaryConnective, BinaryOperator,
-                Comparison, Direction, Formula, GeneralTerm, Guard, IntegerTerm,
+                Comparison, Direction, Formula, GeneralTerm, Guard, InductionScheme, IntegerTerm,
                 PlaceholderDeclaration, Predicate, Quantification, Quantifier, Relation, Role,
                 Sort, Specification, SymbolicTerm, Theory, UnaryConnective, UnaryOperator,
                 UserGuide, UserGuideEntry, Variable,
@@ -1629,6 +1646,7 @@ mod tests {
                         role: Role::Lemma,
                         direction: Direction::Universal,
                         name: String::default(),
+                        using: None,
                         formula: Formula::AtomicFormula(AtomicFormula::Comparison(Comparison {
                             term: GeneralTerm::IntegerTerm(IntegerTerm::Numeral(2)),
                             guards: vec![Guard {
@@ -1638,12 +1656,33 @@ mod tests {
                         })),
                     },
                 ),
+                (
+                    "lemma(backward)[l3] using [l1, l2]: #false",
+                    AnnotatedFormula {
+                        role: Role::Lemma,
+                        direction: Direction::Backward,
+                        name: "l3".to_string(),
+                        using: Some(vec!["l1".to_string(), "l2".to_string()]),
+                        formula: Formula::AtomicFormula(AtomicFormula::Falsity),
+                    },
+                ),
+                (
+                    "lemma using []: #false",
+                    AnnotatedFormula {
+                        role: Role::Lemma,
+                        direction: Direction::Universal,
+                        name: String::default(),
+                        using: Some(vec![]),
+                        formula: Formula::AtomicFormula(AtomicFormula::Falsity),
+                    },
+                ),
                 (
                     "spec(forward)[about_p_0]: not p(0)",
                     AnnotatedFormula {
                         role: Role::Spec,
                         direction: Direction::Forward,
                         name: "about_p_0".to_string(),
+                        using: None,
                         formula: Formula::UnaryFormula {
                             connective: UnaryConnective::Negation,
                             formula: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
@@ -1660,6 +1699,7 @@ mod tests {
                         role: Role::Assumption,
                         direction: Direction::Universal,
                         name: String::default(),
+                        using: None,
                         formula: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
                             predicate_symbol: "p".into(),
                             terms: vec![GeneralTerm::IntegerTerm(IntegerTerm::Numeral(5))],
@@ -1672,6 +1712,7 @@ mod tests {
                         role: Role::Lemma,
                         direction: Direction::Forward,
                         name: String::default(),
+                        using: None,
                         formula: Formula::AtomicFormula(AtomicFormula::Comparison(Comparison {
                             term: GeneralTerm::SymbolicTerm(SymbolicTerm::Symbol("a".to_string())),
                             guards: vec![Guard {
@@ -1687,6 +1728,7 @@ mod tests {
                         role: Role::Lemma,
                         name: "false".to_string(),
                         direction: Direction::Backward,
+                        using: None,
                         formula: Formula::AtomicFormula(AtomicFormula::Falsity),
                     },
                 ),
@@ -1696,6 +1738,7 @@ mod tests {
                         role: Role::Definition,
                         name: "comp_1".to_string(),
                         direction: Direction::Universal,
+                        using: None,
                         formula: Formula::QuantifiedFormula {
                             quantification: Quantification {
                                 quantifier: Quantifier::Forall,
@@ -1742,6 +1785,7 @@ mod tests {
                                 role: Role::Assumption,
                                 direction: Direction::Universal,
                                 name: String::default(),
+                                using: None,
                                 formula: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
                                     predicate_symbol: "p".into(),
                                     terms: vec![GeneralTerm::IntegerTerm(IntegerTerm::Numeral(5))],
@@ -1754,6 +1798,38 @@ mod tests {
             .should_reject(["conjecture: p(5)."]);
     }
 
+    #[test]
+    fn parse_role() {
+        RoleParser
+            .should_parse_into([
+                ("lemma", Role::Lemma),
+                (
+                    "inductive-lemma",
+                    Role::InductiveLemma(InductionScheme::Simple),
+                ),
+                (
+                    "strong-inductive-lemma",
+                    Role::InductiveLemma(InductionScheme::Strong),
+                ),
+                (
+                    "inductive-lemma-step-2",
+                    Role::InductiveLemma(InductionScheme::Step(2)),
+                ),
+                (
+                    "inductive-lemma-step-99",
+                    Role::InductiveLemma(InductionScheme::Step(99)),
+                ),
+                ("case-lemma", Role::CaseLemma),
+            ])
+            .should_reject([
+                "inductive-lemma-step-0",
+                "inductive-lemma-step-100",
+                "inductive-lemma-step-99999999999",
+                "inductive-lemma - step-2",
+                "strong-inductive-lemma-step-2",
+            ]);
+    }
+
     #[test]
     fn parse_specification() {
         SpecificationParser
@@ -1767,6 +1843,7 @@ mod tests {
                                 role: Role::Spec,
                                 direction: Direction::Forward,
                                 name: "about_p_0".to_string(),
+                                using: None,
                                 formula: Formula::UnaryFormula {
                                     connective: UnaryConnective::Negation,
                                     formula: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
@@ -1782,15 +1859,17 @@ mod tests {
                                 role: Role::Assumption,
                                 direction: Direction::Universal,
                                 name: String::default(),
+                                using: None,
                                 formula: Formula::AtomicFormula(AtomicFormula::Atom(Atom {
                                     predicate_symbol: "p".into(),
                                     terms: vec![GeneralTerm::IntegerTerm(IntegerTerm::Numeral(5))],
                                 })),
                             },
                             AnnotatedFormula {
-                                role: Role::InductiveLemma,
+                                role: Role::InductiveLemma(InductionScheme::Simple),
                                 direction: Direction::Universal,
                                 name: String::default(),
+                                using: None,
                                 formula: Formula::QuantifiedFormula {
                                     quantification: Quantification { quantifier: Quantifier::Forall, variables: vec![Variable {name: "N".to_string(), sort: Sort::Integer}] },
                                     formula: Formula::BinaryFormula {
@@ -942,6 +942,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::single_element_loop)]
     fn test_simplify_transitive_equality() {
         for (src, target) in [(
             "exists X Y Z ( X = 5 and Y = 5 and not p(X,Y))",
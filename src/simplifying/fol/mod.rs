@@ -362,6 +362,7 @@ pub fn transitive_equality(
 }
 
 #[test]
+#[allow(clippy::single_element_loop)]
 fn test_simplify_conjunction_tree() {
     for (src, target) in [(
         (
@@ -0,0 +1,714 @@
+use {
+    crate::syntax_tree::asp::{
+        Atom, AtomicFormula, BinaryOperator, Comparison, ConditionalHead, Head, Literal,
+        PrecomputedTerm, Program, Relation, Rule, Sign, Term, UnaryOperator, Variable,
+    },
+    indexmap::IndexSet,
+    std::{
+        cmp::Ordering,
+        collections::HashMap,
+        fmt::{self, Display, Formatter},
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+/// The maximal number of ground atoms, guards against programs without a finite grounding
+const MAX_ATOMS: usize = 100_000;
+
+#[derive(Error, Debug)]
+pub enum GroundingError {
+    #[error("the rule `{0}` contains a conditional literal, which is not supported by the solver")]
+    ConditionalLiteral(Rule),
+    #[error("the rule `{0}` is unsafe")]
+    UnsafeRule(Rule),
+    #[error(
+        "the grounding exceeds {MAX_ATOMS} atoms, the program may not have a finite grounding"
+    )]
+    TooManyAtoms,
+}
+
+#[derive(Error, Debug)]
+pub enum ConstantError {
+    #[error("expected a definition of the form `<name>=<value>`")]
+    MissingValue,
+    #[error("the value `{0}` is not a precomputed term")]
+    InvalidValue(String),
+}
+
+/// The definition of a symbolic constant, such as `n=10`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constant {
+    pub name: String,
+    pub value: PrecomputedTerm,
+}
+
+impl FromStr for Constant {
+    type Err = ConstantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.split_once('=').ok_or(ConstantError::MissingValue)?;
+        let value = value.trim();
+
+        Ok(Constant {
+            name: name.trim().to_string(),
+            value: match value.parse() {
+                Ok(value) => value,
+                Err(_) => match value.parse::<isize>() {
+                    Ok(n) => PrecomputedTerm::Numeral(n),
+                    Err(_) => return Err(ConstantError::InvalidValue(value.to_string())),
+                },
+            },
+        })
+    }
+}
+
+/// Compare precomputed terms by the total order `#inf` < integers < symbols < `#sup`
+pub fn compare(lhs: &PrecomputedTerm, rhs: &PrecomputedTerm) -> Ordering {
+    fn rank(term: &PrecomputedTerm) -> usize {
+        match term {
+            PrecomputedTerm::Infimum => 0,
+            PrecomputedTerm::Numeral(_) => 1,
+            PrecomputedTerm::Symbol(_) => 2,
+            PrecomputedTerm::Supremum => 3,
+        }
+    }
+
+    match (lhs, rhs) {
+        (PrecomputedTerm::Numeral(m), PrecomputedTerm::Numeral(n)) => m.cmp(n),
+        (PrecomputedTerm::Symbol(s), PrecomputedTerm::Symbol(t)) => s.cmp(t),
+        _ => rank(lhs).cmp(&rank(rhs)),
+    }
+}
+
+fn holds(relation: &Relation, lhs: &PrecomputedTerm, rhs: &PrecomputedTerm) -> bool {
+    let ordering = compare(lhs, rhs);
+    match relation {
+        Relation::Equal => ordering.is_eq(),
+        Relation::NotEqual => ordering.is_ne(),
+        Relation::Less => ordering.is_lt(),
+        Relation::LessEqual => ordering.is_le(),
+        Relation::Greater => ordering.is_gt(),
+        Relation::GreaterEqual => ordering.is_ge(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroundAtom {
+    pub predicate_symbol: String,
+    pub terms: Vec<PrecomputedTerm>,
+}
+
+impl GroundAtom {
+    fn key(&self) -> (String, usize) {
+        (self.predicate_symbol.clone(), self.terms.len())
+    }
+}
+
+impl From<GroundAtom> for Atom {
+    fn from(atom: GroundAtom) -> Self {
+        Atom {
+            predicate_symbol: atom.predicate_symbol,
+            terms: atom.terms.into_iter().map(Term::PrecomputedTerm).collect(),
+        }
+    }
+}
+
+impl Ord for GroundAtom {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.predicate_symbol
+            .cmp(&other.predicate_symbol)
+            .then(self.terms.len().cmp(&other.terms.len()))
+            .then_with(|| {
+                self.terms
+                    .iter()
+                    .zip(&other.terms)
+                    .map(|(lhs, rhs)| compare(lhs, rhs))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+}
+
+impl PartialOrd for GroundAtom {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats the atom as clingo does, e.g. `p(1,a)`
+impl Display for GroundAtom {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.predicate_symbol)?;
+
+        let mut iter = self.terms.iter();
+        if let Some(term) = iter.next() {
+            write!(f, "({term}")?;
+            for term in iter {
+                write!(f, ",{term}")?;
+            }
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GroundHead {
+    Basic(usize),
+    Choice(usize),
+    Falsity,
+}
+
+/// A rule whose atoms are given by their indices within a ground program
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroundRule {
+    pub head: GroundHead,
+    pub positive: Vec<usize>,
+    pub negative: Vec<usize>,
+    pub double_negative: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GroundProgram {
+    pub atoms: IndexSet<GroundAtom>,
+    pub rules: IndexSet<GroundRule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum InstanceHead {
+    Basic(GroundAtom),
+    Choice(GroundAtom),
+    Falsity,
+}
+
+/// A ground instance of a rule before its atoms are indexed
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Instance {
+    head: InstanceHead,
+    body: Vec<(Sign, GroundAtom)>,
+}
+
+/// A step of the instantiation of a rule body
+///
+/// The steps are ordered such that every variable is bound before its value is needed.
+enum Step<'a> {
+    /// Match a positive literal against the atoms derived so far, binding its unbound variables
+    Match(&'a Atom),
+    /// Bind a variable to each value of a term
+    Assign(&'a Variable, &'a Term),
+    /// Check a comparison between bound terms
+    Compare(&'a Comparison),
+    /// Instantiate a negated or doubly negated literal whose variables are bound
+    Negate(&'a Literal),
+}
+
+/// Order the body of a rule into steps of instantiation, provided that the rule is safe
+///
+/// Returns `None` if the body contains `#false` and the rule is therefore void.
+fn plan(rule: &Rule) -> Result<Option<Vec<Step<'_>>>, GroundingError> {
+    let mut remaining = vec![];
+    for formula in &rule.body.formulas {
+        if !formula.basic() {
+            return Err(GroundingError::ConditionalLiteral(rule.clone()));
+        }
+
+        match &formula.head {
+            ConditionalHead::AtomicFormula(formula) => remaining.push(formula),
+            ConditionalHead::Falsity => return Ok(None),
+        }
+    }
+
+    let mut bound = IndexSet::new();
+    let mut steps = vec![];
+
+    while !remaining.is_empty() {
+        let is_bound = |formula: &&AtomicFormula| formula.variables().is_subset(&bound);
+
+        // Tests on bound variables come first since they prune the instantiation early
+        let position = remaining
+            .iter()
+            .position(is_bound)
+            .or_else(|| remaining.iter().position(|f| binds(f, &bound)))
+            .ok_or_else(|| GroundingError::UnsafeRule(rule.clone()))?;
+
+        let formula = remaining.remove(position);
+        steps.push(match formula {
+            AtomicFormula::Literal(Literal {
+                sign: Sign::NoSign,
+                atom,
+            }) => Step::Match(atom),
+            AtomicFormula::Literal(literal) => Step::Negate(literal),
+            AtomicFormula::Comparison(comparison) if comparison.variables().is_subset(&bound) => {
+                Step::Compare(comparison)
+            }
+            AtomicFormula::Comparison(comparison) => match (&comparison.lhs, &comparison.rhs) {
+                (Term::Variable(variable), term) if !bound.contains(variable) => {
+                    Step::Assign(variable, term)
+                }
+                (term, Term::Variable(variable)) => Step::Assign(variable, term),
+                _ => unreachable!("the comparison does not bind a variable"),
+            },
+        });
+
+        bound.extend(formula.variables());
+    }
+
+    if rule.head.variables().is_subset(&bound) {
+        Ok(Some(steps))
+    } else {
+        Err(GroundingError::UnsafeRule(rule.clone()))
+    }
+}
+
+/// Whether the formula binds its unbound variables, given that the variables in `bound` are bound
+fn binds(formula: &AtomicFormula, bound: &IndexSet<Variable>) -> bool {
+    let is_bound = |term: &Term| term.variables().is_subset(bound);
+
+    match formula {
+        AtomicFormula::Literal(Literal {
+            sign: Sign::NoSign,
+            atom,
+        }) => atom
+            .terms
+            .iter()
+            .all(|term| matches!(term, Term::Variable(_)) || is_bound(term)),
+        AtomicFormula::Comparison(Comparison {
+            relation: Relation::Equal,
+            lhs,
+            rhs,
+        }) => {
+            matches!(lhs, Term::Variable(_)) && is_bound(rhs)
+                || matches!(rhs, Term::Variable(_)) && is_bound(lhs)
+        }
+        AtomicFormula::Literal(_) | AtomicFormula::Comparison(_) => false,
+    }
+}
+
+/// All tuples choosing one value per position
+pub fn product<T: Clone>(values: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    values.into_iter().fold(vec![vec![]], |tuples, values| {
+        tuples
+            .iter()
+            .flat_map(|tuple| {
+                values.iter().map(move |value| {
+                    let mut tuple = tuple.clone();
+                    tuple.push(value.clone());
+                    tuple
+                })
+            })
+            .collect()
+    })
+}
+
+type Substitution = HashMap<Variable, PrecomputedTerm>;
+
+/// A semi-naive grounder, which only matches rules against combinations of atoms that involve a new atom
+struct Grounder {
+    constants: HashMap<String, PrecomputedTerm>,
+    /// The atoms derived so far, in order of their derivation
+    domain: HashMap<(String, usize), IndexSet<GroundAtom>>,
+    /// The number of atoms per predicate that were derived before the previous round
+    boundary: HashMap<(String, usize), usize>,
+}
+
+impl Grounder {
+    /// The values of a term whose variables are bound, following the abstract gringo compliant semantics of arithmetic
+    fn evaluate(&self, term: &Term, substitution: &Substitution) -> Vec<PrecomputedTerm> {
+        fn integers(values: &[PrecomputedTerm]) -> impl Iterator<Item = isize> + '_ {
+            values.iter().filter_map(|value| match value {
+                PrecomputedTerm::Numeral(n) => Some(*n),
+                _ => None,
+            })
+        }
+
+        match term {
+            Term::PrecomputedTerm(PrecomputedTerm::Symbol(s)) => {
+                vec![self
+                    .constants
+                    .get(s)
+                    .cloned()
+                    .unwrap_or_else(|| PrecomputedTerm::Symbol(s.clone()))]
+            }
+            Term::PrecomputedTerm(t) => vec![t.clone()],
+            Term::Variable(v) => vec![substitution
+                .get(v)
+                .expect("variables are bound before they are evaluated")
+                .clone()],
+            Term::UnaryOperation { op, arg } => {
+                let args = self.evaluate(arg, substitution);
+                integers(&args)
+                    .filter_map(|n| match op {
+                        UnaryOperator::Negative => n.checked_neg(),
+                        UnaryOperator::AbsoluteValue => n.checked_abs(),
+                    })
+                    .map(PrecomputedTerm::Numeral)
+                    .collect()
+            }
+            Term::BinaryOperation { op, lhs, rhs } => {
+                let lhs = self.evaluate(lhs, substitution);
+                let rhs = self.evaluate(rhs, substitution);
+
+                let mut result = IndexSet::new();
+                for m in integers(&lhs) {
+                    for n in integers(&rhs) {
+                        match op {
+                            BinaryOperator::Add => result.extend(m.checked_add(n)),
+                            BinaryOperator::Subtract => result.extend(m.checked_sub(n)),
+                            BinaryOperator::Multiply => result.extend(m.checked_mul(n)),
+                            // Integer division and modulo round towards zero
+                            BinaryOperator::Divide => result.extend(m.checked_div(n)),
+                            BinaryOperator::Modulo => result.extend(m.checked_rem(n)),
+                            BinaryOperator::Interval => result.extend(m..=n),
+                        }
+                    }
+                }
+
+                result.into_iter().map(PrecomputedTerm::Numeral).collect()
+            }
+        }
+    }
+
+    fn atoms(&self, atom: &Atom, substitution: &Substitution) -> Vec<GroundAtom> {
+        product(
+            atom.terms
+                .iter()
+                .map(|term| self.evaluate(term, substitution))
+                .collect(),
+        )
+        .into_iter()
+        .map(|terms| GroundAtom {
+            predicate_symbol: atom.predicate_symbol.clone(),
+            terms,
+        })
+        .collect()
+    }
+
+    /// Extend the substitution such that the terms evaluate to the given values
+    fn unify(
+        &self,
+        terms: &[Term],
+        values: &[PrecomputedTerm],
+        substitution: &mut Substitution,
+    ) -> bool {
+        terms.iter().zip(values).all(|(term, value)| match term {
+            Term::Variable(variable) if !substitution.contains_key(variable) => {
+                substitution.insert(variable.clone(), value.clone());
+                true
+            }
+            term => self.evaluate(term, substitution).contains(value),
+        })
+    }
+
+    /// Instantiate the remaining steps of a rule
+    ///
+    /// If a focus is given, the match at this position is restricted to the atoms derived in the previous round,
+    /// and the preceding matches are restricted to the atoms derived before.
+    #[allow(clippy::too_many_arguments)]
+    fn instantiate(
+        &self,
+        rule: &Rule,
+        steps: &[Step],
+        position: usize,
+        focus: Option<usize>,
+        substitution: &Substitution,
+        body: &mut Vec<(Sign, GroundAtom)>,
+        instances: &mut IndexSet<Instance>,
+    ) {
+        let Some((step, steps)) = steps.split_first() else {
+            match &rule.head {
+                Head::Basic(atom) | Head::Choice(atom) => {
+                    for atom in self.atoms(atom, substitution) {
+                        instances.insert(Instance {
+                            head: match rule.head {
+                                Head::Choice(_) => InstanceHead::Choice(atom),
+                                _ => InstanceHead::Basic(atom),
+                            },
+                            body: body.clone(),
+                        });
+                    }
+                }
+                Head::Falsity => {
+                    instances.insert(Instance {
+                        head: InstanceHead::Falsity,
+                        body: body.clone(),
+                    });
+                }
+            }
+            return;
+        };
+
+        match step {
+            Step::Match(atom) => {
+                let key = (atom.predicate_symbol.clone(), atom.terms.len());
+                let Some(candidates) = self.domain.get(&key) else {
+                    return;
+                };
+
+                let boundary = self.boundary.get(&key).copied().unwrap_or(0);
+                let (start, end) = match focus {
+                    Some(focus) if position < focus => (0, boundary),
+                    Some(focus) if position == focus => (boundary, candidates.len()),
+                    _ => (0, candidates.len()),
+                };
+
+                for candidate in candidates.iter().take(end).skip(start) {
+                    let mut substitution = substitution.clone();
+                    if self.unify(&atom.terms, &candidate.terms, &mut substitution) {
+                        body.push((Sign::NoSign, candidate.clone()));
+                        self.instantiate(
+                            rule,
+                            steps,
+                            position + 1,
+                            focus,
+                            &substitution,
+                            body,
+                            instances,
+                        );
+                        body.pop();
+                    }
+                }
+            }
+            Step::Assign(variable, term) => {
+                for value in self.evaluate(term, substitution) {
+                    let mut substitution = substitution.clone();
+                    substitution.insert((*variable).clone(), value);
+                    self.instantiate(
+                        rule,
+                        steps,
+                        position + 1,
+                        focus,
+                        &substitution,
+                        body,
+                        instances,
+                    );
+                }
+            }
+            Step::Compare(comparison) => {
+                let lhs = self.evaluate(&comparison.lhs, substitution);
+                let rhs = self.evaluate(&comparison.rhs, substitution);
+                if lhs
+                    .iter()
+                    .any(|l| rhs.iter().any(|r| holds(&comparison.relation, l, r)))
+                {
+                    self.instantiate(
+                        rule,
+                        steps,
+                        position + 1,
+                        focus,
+                        substitution,
+                        body,
+                        instances,
+                    );
+                }
+            }
+            Step::Negate(literal) => {
+                for atom in self.atoms(&literal.atom, substitution) {
+                    body.push((literal.sign.clone(), atom));
+                    self.instantiate(
+                        rule,
+                        steps,
+                        position + 1,
+                        focus,
+                        substitution,
+                        body,
+                        instances,
+                    );
+                    body.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Ground a program by instantiating its rules until no further atoms can be derived
+pub fn ground(program: &Program, constants: &[Constant]) -> Result<GroundProgram, GroundingError> {
+    let mut plans = vec![];
+    for rule in &program.rules {
+        if let Some(steps) = plan(rule)? {
+            plans.push((rule, steps));
+        }
+    }
+
+    let mut grounder = Grounder {
+        constants: constants
+            .iter()
+            .map(|constant| (constant.name.clone(), constant.value.clone()))
+            .collect(),
+        domain: HashMap::new(),
+        boundary: HashMap::new(),
+    };
+
+    let mut instances = IndexSet::new();
+    for (rule, steps) in &plans {
+        grounder.instantiate(
+            rule,
+            steps,
+            0,
+            None,
+            &HashMap::new(),
+            &mut vec![],
+            &mut instances,
+        );
+    }
+
+    let mut size = 0;
+    let mut start = 0;
+
+    loop {
+        grounder.boundary = grounder
+            .domain
+            .iter()
+            .map(|(key, atoms)| (key.clone(), atoms.len()))
+            .collect();
+
+        let mut changed = false;
+        for instance in instances.iter().skip(start) {
+            if let InstanceHead::Basic(atom) | InstanceHead::Choice(atom) = &instance.head {
+                if grounder
+                    .domain
+                    .entry(atom.key())
+                    .or_default()
+                    .insert(atom.clone())
+                {
+                    changed = true;
+                    size += 1;
+                }
+            }
+        }
+
+        if size > MAX_ATOMS {
+            return Err(GroundingError::TooManyAtoms);
+        }
+
+        if !changed {
+            return Ok(index(instances));
+        }
+
+        start = instances.len();
+        for (rule, steps) in &plans {
+            for (focus, step) in steps.iter().enumerate() {
+                if let Step::Match(_) = step {
+                    grounder.instantiate(
+                        rule,
+                        steps,
+                        0,
+                        Some(focus),
+                        &HashMap::new(),
+                        &mut vec![],
+                        &mut instances,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Index the atoms of the instances, dropping literals and rules that are decided by the absence of an atom
+fn index(instances: IndexSet<Instance>) -> GroundProgram {
+    let mut atoms = IndexSet::new();
+    for instance in &instances {
+        if let InstanceHead::Basic(atom) | InstanceHead::Choice(atom) = &instance.head {
+            atoms.insert(atom.clone());
+        }
+    }
+
+    let mut rules = IndexSet::new();
+    'instances: for instance in instances {
+        let mut rule = GroundRule {
+            head: match &instance.head {
+                InstanceHead::Basic(atom) => GroundHead::Basic(atoms.get_index_of(atom).unwrap()),
+                InstanceHead::Choice(atom) => GroundHead::Choice(atoms.get_index_of(atom).unwrap()),
+                InstanceHead::Falsity => GroundHead::Falsity,
+            },
+            positive: vec![],
+            negative: vec![],
+            double_negative: vec![],
+        };
+
+        for (sign, atom) in instance.body {
+            match (sign, atoms.get_index_of(&atom)) {
+                (Sign::NoSign, Some(i)) => rule.positive.push(i),
+                (Sign::Negation, Some(i)) => rule.negative.push(i),
+                (Sign::DoubleNegation, Some(i)) => rule.double_negative.push(i),
+                (Sign::Negation, None) => (),
+                (Sign::NoSign | Sign::DoubleNegation, None) => continue 'instances,
+            }
+        }
+
+        rules.insert(rule);
+    }
+
+    GroundProgram { atoms, rules }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{ground, Constant, GroundingError},
+        crate::syntax_tree::asp::PrecomputedTerm,
+    };
+
+    fn atoms(program: &str, constants: &[Constant]) -> Vec<String> {
+        let mut atoms: Vec<_> = ground(&program.parse().unwrap(), constants)
+            .unwrap()
+            .atoms
+            .into_iter()
+            .collect();
+        atoms.sort();
+        atoms.into_iter().map(|atom| atom.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ground() {
+        for (program, target) in [
+            ("p(1..3).", vec!["p(1)", "p(2)", "p(3)"]),
+            (
+                "p(X) :- X = 1..2. q(X * 2) :- p(X).",
+                vec!["p(1)", "p(2)", "q(2)", "q(4)"],
+            ),
+            ("p(7 / 2, -7 / 2, 7 \\ -2, -7 \\ 2, 1 / 0).", vec![]),
+            (
+                "p(7 / 2). p(-7 / 2). p(7 \\ -2). p(-7 \\ 2). p(a + 1).",
+                vec!["p(-3)", "p(-1)", "p(1)", "p(3)"],
+            ),
+            (
+                "p(a). p(1). q(X) :- p(X), X > 5.",
+                vec!["p(1)", "p(a)", "q(a)"],
+            ),
+            (
+                "{p(1..2)}. q(X) :- p(X), not r(X). r(2).",
+                vec!["p(1)", "p(2)", "q(1)", "q(2)", "r(2)"],
+            ),
+            (
+                "e(1, 2). e(2, 3). t(X, Y) :- e(X, Y). t(X, Z) :- t(X, Y), e(Y, Z).",
+                vec!["e(1,2)", "e(2,3)", "t(1,2)", "t(1,3)", "t(2,3)"],
+            ),
+        ] {
+            assert_eq!(atoms(program, &[]), target, "{program}");
+        }
+    }
+
+    #[test]
+    fn test_ground_constants() {
+        let constants = ["n=3".parse::<Constant>().unwrap()];
+        assert_eq!(constants[0].value, PrecomputedTerm::Numeral(3));
+        assert_eq!(atoms("p(1..n).", &constants), vec!["p(1)", "p(2)", "p(3)"]);
+    }
+
+    #[test]
+    fn test_ground_errors() {
+        for program in ["p(X) :- not q(X).", "p(X) :- q(Y).", "p :- q(X) : r(X)."] {
+            assert!(matches!(
+                ground(&program.parse().unwrap(), &[]),
+                Err(GroundingError::UnsafeRule(_) | GroundingError::ConditionalLiteral(_))
+            ));
+        }
+
+        assert!(matches!(
+            ground(&"p(0). p(X + 1) :- p(X).".parse().unwrap(), &[]),
+            Err(GroundingError::TooManyAtoms)
+        ));
+    }
+}
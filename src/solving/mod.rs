@@ -0,0 +1,2 @@
+pub mod grounding;
+pub mod stable_models;
@@ -0,0 +1,259 @@
+use crate::solving::grounding::{GroundAtom, GroundHead, GroundProgram, GroundRule};
+
+/// The stable models found by an enumeration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Enumeration {
+    /// The stable models, each sorted by the order of ground atoms
+    pub models: Vec<Vec<GroundAtom>>,
+    /// Whether the search space was exhausted, i.e. there are no further stable models
+    pub exhausted: bool,
+}
+
+type Assignment = Vec<Option<bool>>;
+
+/// A depth-first search for stable models, which branches on the truth values of atoms
+struct Search<'a> {
+    program: &'a GroundProgram,
+    limit: Option<usize>,
+    models: Vec<Vec<GroundAtom>>,
+}
+
+impl Search<'_> {
+    fn body_is_true(rule: &GroundRule, assignment: &Assignment) -> bool {
+        rule.positive.iter().all(|&a| assignment[a] == Some(true))
+            && rule.negative.iter().all(|&a| assignment[a] == Some(false))
+            && rule
+                .double_negative
+                .iter()
+                .all(|&a| assignment[a] == Some(true))
+    }
+
+    fn body_is_false(rule: &GroundRule, assignment: &Assignment) -> bool {
+        rule.positive.iter().any(|&a| assignment[a] == Some(false))
+            || rule.negative.iter().any(|&a| assignment[a] == Some(true))
+            || rule
+                .double_negative
+                .iter()
+                .any(|&a| assignment[a] == Some(false))
+    }
+
+    /// The least model of the rules whose bodies are not false, taking only their positive bodies into account
+    ///
+    /// Every stable model extending the assignment is contained in this set.
+    fn supported(&self, assignment: &Assignment) -> Vec<bool> {
+        let mut derived = vec![false; assignment.len()];
+
+        let rules: Vec<_> = self
+            .program
+            .rules
+            .iter()
+            .filter(|rule| !Self::body_is_false(rule, assignment))
+            .filter_map(|rule| match rule.head {
+                GroundHead::Basic(head) | GroundHead::Choice(head) => Some((head, rule)),
+                GroundHead::Falsity => None,
+            })
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (head, rule) in &rules {
+                if !derived[*head] && rule.positive.iter().all(|&a| derived[a]) {
+                    derived[*head] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        derived
+    }
+
+    /// Derive the consequences of the assignment, returns `false` on a conflict
+    fn propagate(&self, assignment: &mut Assignment) -> bool {
+        loop {
+            let mut changed = false;
+
+            for rule in &self.program.rules {
+                if Self::body_is_true(rule, assignment) {
+                    match rule.head {
+                        GroundHead::Basic(head) => match assignment[head] {
+                            Some(true) => (),
+                            Some(false) => return false,
+                            None => {
+                                assignment[head] = Some(true);
+                                changed = true;
+                            }
+                        },
+                        GroundHead::Choice(_) => (),
+                        GroundHead::Falsity => return false,
+                    }
+                }
+            }
+
+            for (atom, supported) in self.supported(assignment).into_iter().enumerate() {
+                if !supported {
+                    match assignment[atom] {
+                        Some(false) => (),
+                        Some(true) => return false,
+                        None => {
+                            assignment[atom] = Some(false);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+
+            if !changed {
+                return true;
+            }
+        }
+    }
+
+    /// Whether a total assignment is a stable model, i.e. the least model of its reduct
+    fn is_stable(&self, assignment: &Assignment) -> bool {
+        let model: Vec<bool> = assignment
+            .iter()
+            .map(|value| *value == Some(true))
+            .collect();
+
+        let mut derived = vec![false; model.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for rule in &self.program.rules {
+                let applicable = rule.positive.iter().all(|&a| derived[a])
+                    && rule.negative.iter().all(|&a| !model[a])
+                    && rule.double_negative.iter().all(|&a| model[a]);
+
+                if !applicable {
+                    continue;
+                }
+
+                match rule.head {
+                    GroundHead::Basic(head) => {
+                        if !derived[head] {
+                            derived[head] = true;
+                            changed = true;
+                        }
+                    }
+                    GroundHead::Choice(head) => {
+                        if model[head] && !derived[head] {
+                            derived[head] = true;
+                            changed = true;
+                        }
+                    }
+                    GroundHead::Falsity => return false,
+                }
+            }
+        }
+
+        derived == model
+    }
+
+    fn limit_reached(&self) -> bool {
+        self.limit.is_some_and(|limit| self.models.len() >= limit)
+    }
+
+    fn search(&mut self, mut assignment: Assignment) {
+        if self.limit_reached() || !self.propagate(&mut assignment) {
+            return;
+        }
+
+        match assignment.iter().position(Option::is_none) {
+            Some(atom) => {
+                for value in [true, false] {
+                    let mut assignment = assignment.clone();
+                    assignment[atom] = Some(value);
+                    self.search(assignment);
+                }
+            }
+            None => {
+                if self.is_stable(&assignment) {
+                    let mut model: Vec<_> = self
+                        .program
+                        .atoms
+                        .iter()
+                        .zip(&assignment)
+                        .filter(|(_, value)| **value == Some(true))
+                        .map(|(atom, _)| atom.clone())
+                        .collect();
+                    model.sort();
+                    self.models.push(model);
+                }
+            }
+        }
+    }
+}
+
+/// Enumerate the stable models of a ground program, at most `limit` many if a limit is given
+pub fn stable_models(program: &GroundProgram, limit: Option<usize>) -> Enumeration {
+    let mut search = Search {
+        program,
+        limit,
+        models: vec![],
+    };
+
+    search.search(vec![None; program.atoms.len()]);
+
+    // The search only stops early when the limit is reached
+    let exhausted = !search.limit_reached();
+
+    Enumeration {
+        models: search.models,
+        exhausted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::stable_models,
+        crate::solving::grounding::{ground, Constant},
+    };
+
+    fn models(program: &str, constants: &[Constant], limit: Option<usize>) -> Vec<String> {
+        let program = ground(&program.parse().unwrap(), constants).unwrap();
+        stable_models(&program, limit)
+            .models
+            .into_iter()
+            .map(|model| {
+                model
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stable_models() {
+        for (program, target) in [
+            ("p. q :- p.", vec!["p q"]),
+            ("p :- not q. q :- not p.", vec!["p", "q"]),
+            ("p :- not p.", vec![]),
+            ("p :- p.", vec![""]),
+            ("{p}. :- not p.", vec!["p"]),
+            ("p :- not not p.", vec!["p", ""]),
+            (
+                "{p(1..2)}. q :- p(X). :- p(1), p(2).",
+                vec!["p(1) q", "p(2) q", ""],
+            ),
+            (
+                "e(1, 2). e(2, 1). t(X, Y) :- e(X, Y). t(X, Z) :- t(X, Y), e(Y, Z).",
+                vec!["e(1,2) e(2,1) t(1,1) t(1,2) t(2,1) t(2,2)"],
+            ),
+        ] {
+            assert_eq!(models(program, &[], None), target, "{program}");
+        }
+    }
+
+    #[test]
+    fn test_stable_models_limit() {
+        let program = "{p(1..n)}.";
+        let constants = ["n=3".parse().unwrap()];
+
+        assert_eq!(models(program, &constants, None).len(), 8);
+        assert_eq!(models(program, &constants, Some(3)).len(), 3);
+    }
+}
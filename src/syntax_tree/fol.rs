@@ -761,6 +761,24 @@ impl Formula {
             .unwrap_or(Formula::AtomicFormula(AtomicFormula::Falsity))
     }
 
+    /// Inverse function to disjoin
+    pub fn disjoin_invert(formula: Formula) -> Vec<Formula> {
+        match formula {
+            Formula::BinaryFormula {
+                connective: BinaryConnective::Disjunction,
+                lhs,
+                rhs,
+            } => {
+                let mut formulas = Self::disjoin_invert(*lhs);
+                formulas.append(&mut Self::disjoin_invert(*rhs));
+                formulas
+            }
+            _ => {
+                vec![formula]
+            }
+        }
+    }
+
     pub fn variables(&self) -> IndexSet<Variable> {
         match &self {
             Formula::AtomicFormula(f) => f.variables(),
@@ -987,11 +1005,24 @@ pub enum Role {
     Spec,
     Lemma,
     Definition,
-    InductiveLemma,
+    InductiveLemma(InductionScheme),
+    CaseLemma,
 }
 
 impl_node!(Role, Format, RoleParser);
 
+/// The induction principle used to prove an inductive lemma
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum InductionScheme {
+    /// Prove `F(N+1)` from `F(N)`
+    #[default]
+    Simple,
+    /// Prove `F(N)` from `F(M)` for all `M` preceding `N` (course-of-values induction)
+    Strong,
+    /// Prove `F(N+k)` from `F(N)` for the given step size `k`
+    Step(u32),
+}
+
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, ValueEnum)]
 pub enum Direction {
     #[default]
@@ -1007,6 +1038,8 @@ pub struct AnnotatedFormula {
     pub role: Role,
     pub direction: Direction,
     pub name: String,
+    /// The names of the earlier formulas of a proof outline a lemma may use, if restricted by `using [...]`
+    pub using: Option<Vec<String>>,
     pub formula: Formula,
 }
 
@@ -1040,6 +1073,7 @@ impl AnnotatedFormula {
             role: self.role,
             direction: self.direction,
             name: self.name.clone(),
+            using: self.using.clone(),
             formula: self.formula.clone().universal_closure(),
         }
     }
@@ -1049,6 +1083,7 @@ impl AnnotatedFormula {
             role: self.role,
             direction: self.direction,
             name: self.name.clone(),
+            using: self.using.clone(),
             formula: self
                 .formula
                 .clone()
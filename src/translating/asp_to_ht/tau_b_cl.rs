@@ -326,6 +326,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::single_element_loop)]
     fn test_tau_b_cl_original() {
         for (src, target) in [
             (("q(X)", IndexSet::from_iter(vec![asp::Variable("X".to_string())])), "exists Z (Z = X and q(Z))"),
@@ -3,7 +3,7 @@ use {
         convenience::unbox::{fol::UnboxedFormula, Unbox},
         syntax_tree::fol,
     },
-    indexmap::{map::Entry, IndexMap},
+    indexmap::{map::Entry, IndexMap, IndexSet},
     itertools::Itertools,
 };
 
@@ -19,19 +19,9 @@ pub fn completion(theory: fol::Theory) -> Option<fol::Theory> {
     }
 
     // Complete the definitions
-    let completed_definitions = definitions.into_iter().map(|(g, a)| {
-        let v = g.variables();
-        fol::Formula::BinaryFormula {
-            connective: fol::BinaryConnective::Equivalence,
-            lhs: fol::Formula::AtomicFormula(g).into(),
-            rhs: fol::Formula::disjoin(a.into_iter().map(|f_i| {
-                let u_i = f_i.free_variables().difference(&v).cloned().collect();
-                f_i.quantify(fol::Quantifier::Exists, u_i)
-            }))
-            .into(),
-        }
-        .quantify(fol::Quantifier::Forall, v.into_iter().collect())
-    });
+    let completed_definitions = definitions
+        .into_iter()
+        .map(|(g, a)| complete_definition(g, a));
 
     let mut formulas: Vec<_> = constraints
         .into_iter()
@@ -42,6 +32,268 @@ pub fn completion(theory: fol::Theory) -> Option<fol::Theory> {
     Some(fol::Theory { formulas })
 }
 
+/// Complete a theory in which the predicates of each given loop depend positively on each other
+///
+/// Every predicate `p` of a loop is defined by a fresh level mapping `p_level`, where `p_level(V, N$)` holds
+/// if `p(V)` can be derived within `N$` steps from the predicates outside of the loop.
+/// Unlike the plain completion, the result is sound for non-tight programs.
+/// Positive occurrences of loop predicates within implications (e.g. conditional literals) are not supported.
+pub fn ordered_completion(
+    theory: fol::Theory,
+    loops: &[IndexSet<fol::Predicate>],
+) -> Option<fol::Theory> {
+    // Retrieve the definitions and constraints
+    let (definitions, constraints) = components(theory)?;
+
+    // Confirm there are no head mismatches
+    for (_, heads) in heads(&definitions) {
+        if !heads.iter().all_equal() {
+            return None;
+        }
+    }
+
+    // Choose a fresh level predicate for every predicate of a loop
+    let mut taken_predicates = IndexSet::new();
+    for (g, a) in &definitions {
+        taken_predicates.extend(g.predicates());
+        for f in a {
+            taken_predicates.extend(f.predicates());
+        }
+    }
+    for c in &constraints {
+        taken_predicates.extend(c.predicates());
+    }
+
+    let mut level_predicates: Vec<IndexMap<fol::Predicate, String>> = Vec::new();
+    for predicates in loops {
+        let mut mapping = IndexMap::new();
+        for predicate in predicates {
+            let level_predicate = (0..)
+                .map(|i| fol::Predicate {
+                    symbol: match i {
+                        0 => format!("{}_level", predicate.symbol),
+                        i => format!("{}_level{i}", predicate.symbol),
+                    },
+                    arity: predicate.arity + 1,
+                })
+                .find(|p| !taken_predicates.contains(p))
+                .unwrap();
+            taken_predicates.insert(level_predicate.clone());
+            mapping.insert(predicate.clone(), level_predicate.symbol);
+        }
+        level_predicates.push(mapping);
+    }
+
+    let mut formulas: Vec<_> = constraints
+        .into_iter()
+        .map(fol::Formula::universal_closure)
+        .collect();
+
+    for (g, a) in definitions {
+        let predicate = match g {
+            fol::AtomicFormula::Atom(ref atom) => atom.predicate(),
+            _ => unreachable!(),
+        };
+
+        match level_predicates
+            .iter()
+            .find(|mapping| mapping.contains_key(&predicate))
+        {
+            Some(mapping) => formulas.extend(complete_definition_with_levels(g, a, mapping)?),
+            None => formulas.push(complete_definition(g, a)),
+        }
+    }
+
+    Some(fol::Theory { formulas })
+}
+
+fn complete_definition(g: fol::AtomicFormula, a: Vec<fol::Formula>) -> fol::Formula {
+    let v = g.variables();
+    equivalence(
+        fol::Formula::AtomicFormula(g),
+        fol::Formula::disjoin(a.into_iter().map(|f_i| {
+            let u_i = f_i.free_variables().difference(&v).cloned().collect();
+            f_i.quantify(fol::Quantifier::Exists, u_i)
+        })),
+    )
+    .quantify(fol::Quantifier::Forall, v.into_iter().collect())
+}
+
+/// Complete the definition of a loop predicate `p` by the formulas
+/// `forall V (p(V) <-> exists N$ p_level(V, N$))` and
+/// `forall V N$ (p_level(V, N$) <-> N$ >= 0 and F_1' or ... or F_k')`,
+/// where `F_i'` replaces every positive occurrence `q(t)` of a predicate of the loop in `F_i`
+/// by `exists M$ (M$ < N$ and q_level(t, M$))`
+fn complete_definition_with_levels(
+    g: fol::AtomicFormula,
+    a: Vec<fol::Formula>,
+    level_predicates: &IndexMap<fol::Predicate, String>,
+) -> Option<[fol::Formula; 2]> {
+    let atom = match g {
+        fol::AtomicFormula::Atom(ref atom) => atom.clone(),
+        _ => unreachable!(),
+    };
+    let level_symbol = level_predicates[&atom.predicate()].clone();
+
+    let mut taken_variables: IndexSet<_> = g.variables().into_iter().map(|v| v.name).collect();
+    for f in &a {
+        taken_variables.extend(f.variables().into_iter().map(|v| v.name));
+    }
+
+    let mut fresh_variable = |name: &str| {
+        let name = (0..)
+            .map(|i| match i {
+                0 => name.to_string(),
+                i => format!("{name}{i}"),
+            })
+            .find(|candidate| !taken_variables.contains(candidate))
+            .unwrap();
+        taken_variables.insert(name.clone());
+        name
+    };
+    let n = fresh_variable("N");
+    let m = fresh_variable("M");
+
+    let n_variable = fol::Variable {
+        name: n.clone(),
+        sort: fol::Sort::Integer,
+    };
+
+    let v = g.variables();
+
+    let levels = equivalence(
+        fol::Formula::AtomicFormula(g),
+        level_atom(atom.clone(), level_symbol.clone(), &n)
+            .quantify(fol::Quantifier::Exists, vec![n_variable.clone()]),
+    )
+    .quantify(fol::Quantifier::Forall, v.iter().cloned().collect());
+
+    let mut disjuncts = Vec::new();
+    for f_i in a {
+        let f_i = replace_positive_occurrences(f_i, level_predicates, &n, &m)?;
+        let mut u_i: IndexSet<_> = f_i.free_variables().difference(&v).cloned().collect();
+        u_i.shift_remove(&n_variable);
+        disjuncts.push(f_i.quantify(fol::Quantifier::Exists, u_i.into_iter().collect()));
+    }
+
+    let mut variables: Vec<_> = v.into_iter().collect();
+    variables.push(n_variable);
+
+    let definition = equivalence(
+        level_atom(atom, level_symbol, &n),
+        fol::Formula::conjoin([
+            comparison(
+                &n,
+                fol::Relation::GreaterEqual,
+                fol::IntegerTerm::Numeral(0),
+            ),
+            fol::Formula::disjoin(disjuncts),
+        ]),
+    )
+    .quantify(fol::Quantifier::Forall, variables);
+
+    Some([levels, definition])
+}
+
+fn level_atom(atom: fol::Atom, level_symbol: String, level: &str) -> fol::Formula {
+    let mut terms = atom.terms;
+    terms.push(fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(
+        level.to_string(),
+    )));
+    fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+        predicate_symbol: level_symbol,
+        terms,
+    }))
+}
+
+fn equivalence(lhs: fol::Formula, rhs: fol::Formula) -> fol::Formula {
+    fol::Formula::BinaryFormula {
+        connective: fol::BinaryConnective::Equivalence,
+        lhs: lhs.into(),
+        rhs: rhs.into(),
+    }
+}
+
+fn comparison(variable: &str, relation: fol::Relation, term: fol::IntegerTerm) -> fol::Formula {
+    fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+        term: fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(variable.to_string())),
+        guards: vec![fol::Guard {
+            relation,
+            term: fol::GeneralTerm::IntegerTerm(term),
+        }],
+    }))
+}
+
+fn replace_positive_occurrences(
+    formula: fol::Formula,
+    level_predicates: &IndexMap<fol::Predicate, String>,
+    n: &str,
+    m: &str,
+) -> Option<fol::Formula> {
+    match formula {
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom))
+            if level_predicates.contains_key(&atom.predicate()) =>
+        {
+            let level_symbol = level_predicates[&atom.predicate()].clone();
+            let level_atom = level_atom(atom, level_symbol, m);
+
+            Some(
+                fol::Formula::conjoin([
+                    comparison(
+                        m,
+                        fol::Relation::Less,
+                        fol::IntegerTerm::Variable(n.to_string()),
+                    ),
+                    level_atom,
+                ])
+                .quantify(
+                    fol::Quantifier::Exists,
+                    vec![fol::Variable {
+                        name: m.to_string(),
+                        sort: fol::Sort::Integer,
+                    }],
+                ),
+            )
+        }
+
+        // Occurrences under negation refer to the predicate itself
+        formula @ (fol::Formula::AtomicFormula(_) | fol::Formula::UnaryFormula { .. }) => {
+            Some(formula)
+        }
+
+        fol::Formula::BinaryFormula {
+            connective:
+                connective @ (fol::BinaryConnective::Conjunction | fol::BinaryConnective::Disjunction),
+            lhs,
+            rhs,
+        } => Some(fol::Formula::BinaryFormula {
+            connective,
+            lhs: replace_positive_occurrences(*lhs, level_predicates, n, m)?.into(),
+            rhs: replace_positive_occurrences(*rhs, level_predicates, n, m)?.into(),
+        }),
+
+        formula @ fol::Formula::BinaryFormula { .. } => {
+            if formula
+                .predicates()
+                .iter()
+                .any(|p| level_predicates.contains_key(p))
+            {
+                None
+            } else {
+                Some(formula)
+            }
+        }
+
+        fol::Formula::QuantifiedFormula {
+            quantification,
+            formula,
+        } => Some(fol::Formula::QuantifiedFormula {
+            quantification,
+            formula: replace_positive_occurrences(*formula, level_predicates, n, m)?.into(),
+        }),
+    }
+}
+
 fn heads(definitions: &Definitions) -> IndexMap<fol::Predicate, Vec<&fol::AtomicFormula>> {
     let mut result: IndexMap<_, Vec<_>> = IndexMap::new();
     for head in definitions.keys() {
@@ -165,12 +417,16 @@ enum Component {
 
 #[cfg(test)]
 mod tests {
-    use crate::{
-        syntax_tree::fol,
-        translating::{
-            asp_to_ht::{tau_star::tau_star, Version},
-            completion::completion,
+    use {
+        crate::{
+            analyzing::tightness::Tightness,
+            syntax_tree::{asp, fol},
+            translating::{
+                asp_to_ht::{tau_star::tau_star, Version},
+                completion::{completion, ordered_completion},
+            },
         },
+        indexmap::IndexSet,
     };
 
     #[test]
@@ -196,6 +452,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ordered_completion() {
+        for (src, target) in [
+            ("p :- p.", "p <-> exists N$i p_level(N$i). forall N$i (p_level(N$i) <-> N$i >= 0 and exists M$i (M$i < N$i and p_level(M$i)))."),
+            ("tc(X,Y) :- edge(X,Y). tc(X,Z) :- tc(X,Y), edge(Y,Z).", "forall V1 V2 (tc(V1, V2) <-> exists N$i tc_level(V1, V2, N$i)). forall V1 V2 N$i (tc_level(V1, V2, N$i) <-> N$i >= 0 and (exists X Y (V1 = X and V2 = Y and exists Z Z1 (Z = X and Z1 = Y and edge(Z, Z1))) or exists X Z Y (V1 = X and V2 = Z and (exists Z Z1 (Z = X and Z1 = Y and exists M$i (M$i < N$i and tc_level(Z, Z1, M$i))) and exists Z1 Z2 (Z1 = Y and Z2 = Z and edge(Z1, Z2))))))."),
+            ("a :- b, not c. b :- a. b :- d. c :- not a.", "a <-> exists N$i a_level(N$i). forall N$i (a_level(N$i) <-> N$i >= 0 and (exists M$i (M$i < N$i and b_level(M$i)) and not c)). b <-> exists N$i b_level(N$i). forall N$i (b_level(N$i) <-> N$i >= 0 and (exists M$i (M$i < N$i and a_level(M$i)) or d)). c <-> not a."),
+            ("{p} :- p. p_level(1) :- q.", "p <-> exists N$i p_level1(N$i). forall N$i (p_level1(N$i) <-> N$i >= 0 and (exists M$i (M$i < N$i and p_level1(M$i)) and not not p)). forall V1 (p_level(V1) <-> V1 = 1 and q)."),
+        ] {
+            let program: asp::Program = src.parse().unwrap();
+            let loops: Vec<IndexSet<_>> = program
+                .positive_loops()
+                .into_iter()
+                .map(|predicates| predicates.into_iter().map(fol::Predicate::from).collect())
+                .collect();
+
+            let left = ordered_completion(tau_star(program, Version::Original), &loops).unwrap();
+            let right = target.parse().unwrap();
+
+            assert!(
+                left == right,
+                "assertion `left == right` failed:\n left:\n{left}\n right:\n{right}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ordered_completion_unsupported() {
+        let program: asp::Program = "p :- p : q.".parse().unwrap();
+        let loops: Vec<IndexSet<_>> = program
+            .positive_loops()
+            .into_iter()
+            .map(|predicates| predicates.into_iter().map(fol::Predicate::from).collect())
+            .collect();
+
+        assert!(ordered_completion(tau_star(program, Version::Original), &loops).is_none());
+    }
+
     #[test]
     fn test_incompletable() {
         for theory in [
@@ -6,10 +6,14 @@ use crate::syntax_tree::{
 pub fn translate_term(term: asp::Term) -> fol::GeneralTerm {
     match term {
         asp::Term::PrecomputedTerm(t) => match t {
+            asp::PrecomputedTerm::Infimum => fol::GeneralTerm::Infimum,
+            asp::PrecomputedTerm::Numeral(n) => {
+                fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Numeral(n))
+            }
             asp::PrecomputedTerm::Symbol(s) => {
                 fol::GeneralTerm::SymbolicTerm(fol::SymbolicTerm::Symbol(s))
             }
-            _ => panic!("unsupported by shorthand translation"),
+            asp::PrecomputedTerm::Supremum => fol::GeneralTerm::Supremum,
         },
         asp::Term::Variable(v) => fol::GeneralTerm::Variable(v.0),
         asp::Term::UnaryOperation { .. } | asp::Term::BinaryOperation { .. } => {
@@ -148,9 +152,91 @@ pub fn shorthand(p: Program) -> Theory {
     Theory { formulas }
 }
 
+/// Find a rule outside the fragment supported by the shorthand translation
+///
+/// The fragment consists of rules whose terms are precomputed terms or variables and whose bodies contain no conditional literals.
+/// On this fragment, the shorthand translation is equivalent to tau*.
+pub fn unsupported_rule(program: &Program) -> Option<&Rule> {
+    fn supported(term: &asp::Term) -> bool {
+        matches!(term, asp::Term::PrecomputedTerm(_) | asp::Term::Variable(_))
+    }
+
+    program.rules.iter().find(|rule| {
+        !rule.head.terms().unwrap_or_default().iter().all(supported)
+            || !rule.body.formulas.iter().all(|literal| {
+                literal.basic()
+                    && match &literal.head {
+                        asp::ConditionalHead::AtomicFormula(asp::AtomicFormula::Literal(l)) => {
+                            l.atom.terms.iter().all(supported)
+                        }
+                        asp::ConditionalHead::AtomicFormula(asp::AtomicFormula::Comparison(c)) => {
+                            supported(&c.lhs) && supported(&c.rhs)
+                        }
+                        asp::ConditionalHead::Falsity => true,
+                    }
+            })
+    })
+}
+
+/// The shorthand translation of a program in the form expected by the completion
+///
+/// Every head `p(t1, ..., tn)` is replaced by `p(V1, ..., Vn)` for fresh variables `V1, ..., Vn`,
+/// and the equalities `V1 = t1, ..., Vn = tn` are added to the body.
+pub fn completable_shorthand(p: Program) -> Theory {
+    let rules = p.rules.into_iter().map(|rule| {
+        let atom = match rule.head {
+            asp::Head::Basic(ref atom) | asp::Head::Choice(ref atom) => atom.clone(),
+            asp::Head::Falsity => return rule,
+        };
+
+        let taken: Vec<_> = rule.variables().into_iter().map(|v| v.0).collect();
+        let mut fresh = (1..)
+            .map(|i| format!("V{i}"))
+            .filter(|name| !taken.contains(name));
+
+        let mut equalities = Vec::new();
+        let mut terms = Vec::new();
+        for term in atom.terms {
+            let variable = asp::Term::Variable(asp::Variable(fresh.next().unwrap()));
+            equalities.push(asp::ConditionalLiteral {
+                head: asp::ConditionalHead::AtomicFormula(asp::AtomicFormula::Comparison(
+                    asp::Comparison {
+                        relation: asp::Relation::Equal,
+                        lhs: variable.clone(),
+                        rhs: term,
+                    },
+                )),
+                conditions: asp::ConditionalBody { formulas: vec![] },
+            });
+            terms.push(variable);
+        }
+
+        let atom = asp::Atom {
+            predicate_symbol: atom.predicate_symbol,
+            terms,
+        };
+        let head = match rule.head {
+            asp::Head::Basic(_) => asp::Head::Basic(atom),
+            _ => asp::Head::Choice(atom),
+        };
+
+        equalities.extend(rule.body.formulas);
+        Rule {
+            head,
+            body: asp::Body {
+                formulas: equalities,
+            },
+        }
+    });
+
+    Theory {
+        formulas: rules.map(shorthand_rule).collect(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::shorthand;
+    use super::{completable_shorthand, shorthand, unsupported_rule};
 
     #[test]
     fn test_shorthand() {
@@ -184,4 +270,47 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_completable_shorthand() {
+        for (src, target) in [
+            ("p.", "#true -> p."),
+            (
+                "p(a, 1). q(X) :- p(X, V1).",
+                "forall V1 V2 (V1 = a and V2 = 1 -> p(V1, V2)). forall V1 V2 X (V2 = X and p(X, V1) -> q(V2)).",
+            ),
+            (
+                "{q(X)} :- p(X).",
+                "forall V1 X (V1 = X and p(X) and not not q(V1) -> q(V1)).",
+            ),
+            (":- p(X).", "forall X (p(X) -> #false)."),
+        ] {
+            let left = completable_shorthand(src.parse().unwrap());
+            let right = target.parse().unwrap();
+
+            assert!(
+                left == right,
+                "assertion `left == right` failed:\n left:\n{left}\n right:\n{right}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unsupported_rule() {
+        for (src, target) in [
+            ("p(a). q(X) :- p(X), X < 3, not r(X).", None),
+            ("p(X+1) :- p(X).", Some("p(X + 1) :- p(X).")),
+            ("q :- p(1..3).", Some("q :- p(1..3).")),
+            ("q :- p(X) : r(X).", Some("q :- p(X) : r(X).")),
+        ] {
+            let program = src.parse().unwrap();
+            assert_eq!(
+                unsupported_rule(&program)
+                    .map(ToString::to_string)
+                    .as_deref(),
+                target,
+                "{src}"
+            );
+        }
+    }
 }
@@ -0,0 +1,463 @@
+use {
+    crate::{
+        evaluating::fol::{EvaluationError, Structure},
+        solving::{
+            grounding::{ground, product, Constant, GroundAtom, GroundingError},
+            stable_models::stable_models,
+        },
+        syntax_tree::{
+            asp::{self, PrecomputedTerm},
+            fol,
+        },
+    },
+    either::Either,
+    indexmap::{IndexMap, IndexSet},
+    itertools::Itertools as _,
+    std::{
+        collections::BTreeSet,
+        fmt::{self, Display, Formatter},
+    },
+    thiserror::Error,
+};
+
+/// The universes of the input instances are `{1}`, `{1, 2}` and `{1, 2, 3}`
+const MAX_UNIVERSE_SIZE: isize = 3;
+
+/// The maximal number of input instances to check
+const MAX_INSTANCES: usize = 10_000;
+
+#[derive(Error, Debug)]
+pub enum BoundedCheckError {
+    #[error("unable to ground the {0}")]
+    Grounding(&'static str, #[source] GroundingError),
+    #[error("unable to evaluate the formula `{0}`")]
+    Evaluation(Box<fol::AnnotatedFormula>, #[source] EvaluationError),
+    #[error("the specification contains the private predicates {}, which are not supported by the bounded check", .0.iter().format(", "))]
+    PrivatePredicates(Vec<fol::Predicate>),
+}
+
+/// An input instance, consisting of values of the placeholders and an extension of the input predicates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instance {
+    pub placeholders: IndexMap<String, PrecomputedTerm>,
+    pub input: Vec<GroundAtom>,
+}
+
+impl Display for Instance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.placeholders.is_empty() {
+            writeln!(
+                f,
+                "placeholders: {}",
+                self.placeholders
+                    .iter()
+                    .format_with(", ", |(name, value), f| f(&format_args!(
+                        "{name} = {value}"
+                    )))
+            )?;
+        }
+        write!(f, "input: {{{}}}", self.input.iter().format(" "))
+    }
+}
+
+/// The outputs of the stable models of a program, projected onto the output predicates
+type Outputs = BTreeSet<Vec<GroundAtom>>;
+
+fn format_outputs(outputs: &Outputs) -> String {
+    if outputs.is_empty() {
+        "no stable model".to_string()
+    } else {
+        outputs
+            .iter()
+            .map(|output| format!("{{{}}}", output.iter().format(" ")))
+            .join(", ")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// The outputs of the specification and the program differ
+    Outputs {
+        specification: Outputs,
+        program: Outputs,
+    },
+    /// An output of the program violates a formula of the specification
+    Violation {
+        output: Vec<GroundAtom>,
+        formula: fol::AnnotatedFormula,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counterexample {
+    pub instance: Instance,
+    pub discrepancy: Discrepancy,
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.instance)?;
+        match &self.discrepancy {
+            Discrepancy::Outputs {
+                specification,
+                program,
+            } => {
+                writeln!(f, "specification output: {}", format_outputs(specification))?;
+                write!(f, "program output: {}", format_outputs(program))
+            }
+            Discrepancy::Violation { output, formula } => {
+                writeln!(f, "program output: {{{}}}", output.iter().format(" "))?;
+                write!(f, "violated formula: {formula}")
+            }
+        }
+    }
+}
+
+/// The result of a bounded check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundedCheckReport<C = Counterexample> {
+    /// The number of checked instances, e.g. input instances satisfying the assumptions
+    pub instances: usize,
+    /// Whether every instance within the bounds was checked
+    pub complete: bool,
+    /// Whether a counterexample refutes the claim, rather than only its approximation on a finite universe
+    pub exact: bool,
+    pub counterexample: Option<C>,
+}
+
+/// Search for a counterexample to the external equivalence of a program and a specification
+///
+/// The input instances range over all extensions of the input predicates over small universes of integers,
+/// and all combinations of small placeholder values, that satisfy the assumptions of the user guide.
+/// For every instance, the outputs of the program are compared to those of the specification.
+/// If the specification is given as formulas, the outputs of the program are checked against them instead.
+/// Since the quantifiers of these formulas and of the assumptions only range over a small universe,
+/// a violation, or an instance that only satisfies a quantified assumption within the universe, is merely a possible counterexample.
+pub struct BoundedCheck {
+    pub specification: Either<asp::Program, fol::Specification>,
+    pub program: asp::Program,
+    pub user_guide: fol::UserGuide,
+}
+
+impl BoundedCheck {
+    pub fn run(self) -> Result<BoundedCheckReport, BoundedCheckError> {
+        let mapping: IndexMap<_, _> = self
+            .user_guide
+            .placeholders()
+            .into_iter()
+            .map(|p| (p.name.clone(), p))
+            .collect();
+
+        let input_predicates = self.user_guide.input_predicates();
+        let output_predicates = self.user_guide.output_predicates();
+
+        let mut assumptions: Vec<_> = self
+            .user_guide
+            .formulas()
+            .into_iter()
+            .filter(|formula| formula.role == fol::Role::Assumption)
+            .map(|formula| formula.replace_placeholders(&mapping))
+            .collect();
+
+        let specification = match self.specification {
+            Either::Left(program) => Either::Left(program),
+            Either::Right(specification) => {
+                let public_predicates = self.user_guide.public_predicates();
+                let private_predicates: Vec<_> = specification
+                    .predicates()
+                    .into_iter()
+                    .filter(|p| !public_predicates.contains(p))
+                    .collect();
+                if !private_predicates.is_empty() {
+                    return Err(BoundedCheckError::PrivatePredicates(private_predicates));
+                }
+
+                let (specification_assumptions, formulas): (Vec<_>, Vec<_>) = specification
+                    .replace_placeholders(&mapping)
+                    .formulas
+                    .into_iter()
+                    .partition(|formula| formula.role == fol::Role::Assumption);
+                assumptions.extend(specification_assumptions);
+
+                Either::Right(formulas)
+            }
+        };
+
+        let mut report = BoundedCheckReport {
+            instances: 0,
+            complete: true,
+            exact: specification.is_left()
+                && assumptions
+                    .iter()
+                    .all(|assumption| assumption.formula.variables().is_empty()),
+            counterexample: None,
+        };
+        let mut candidates = 0;
+
+        for size in 1..=MAX_UNIVERSE_SIZE {
+            let universe: Vec<_> = (1..=size).map(PrecomputedTerm::Numeral).collect();
+
+            let atoms: Vec<_> = input_predicates
+                .iter()
+                .flat_map(|predicate| {
+                    product(vec![universe.clone(); predicate.arity])
+                        .into_iter()
+                        .map(|terms| GroundAtom {
+                            predicate_symbol: predicate.symbol.clone(),
+                            terms,
+                        })
+                })
+                .collect();
+
+            for placeholders in placeholder_values(&mapping) {
+                for input in (0..=atoms.len()).flat_map(|k| atoms.iter().cloned().combinations(k)) {
+                    if candidates == MAX_INSTANCES {
+                        report.complete = false;
+                        return Ok(report);
+                    }
+                    candidates += 1;
+
+                    let instance = Instance {
+                        placeholders: placeholders.clone(),
+                        input,
+                    };
+
+                    if !check_assumptions(&instance, &universe, &assumptions)? {
+                        continue;
+                    }
+                    report.instances += 1;
+
+                    let program = solve(&self.program, &instance, &output_predicates)
+                        .map_err(|e| BoundedCheckError::Grounding("program", e))?;
+
+                    let discrepancy = match &specification {
+                        Either::Left(specification) => {
+                            let specification = solve(specification, &instance, &output_predicates)
+                                .map_err(|e| BoundedCheckError::Grounding("specification", e))?;
+
+                            (specification != program).then_some(Discrepancy::Outputs {
+                                specification,
+                                program,
+                            })
+                        }
+                        Either::Right(formulas) => {
+                            check_outputs(&instance, &universe, program, formulas)?
+                        }
+                    };
+
+                    if let Some(discrepancy) = discrepancy {
+                        report.counterexample = Some(Counterexample {
+                            instance,
+                            discrepancy,
+                        });
+                        return Ok(report);
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// All combinations of small values of the placeholders
+fn placeholder_values(
+    mapping: &IndexMap<String, fol::FunctionConstant>,
+) -> Vec<IndexMap<String, PrecomputedTerm>> {
+    let values = mapping
+        .values()
+        .map(|placeholder| {
+            let values: Vec<_> = match placeholder.sort {
+                fol::Sort::General | fol::Sort::Integer => (0..=MAX_UNIVERSE_SIZE)
+                    .map(PrecomputedTerm::Numeral)
+                    .collect(),
+                fol::Sort::Symbol => vec![
+                    PrecomputedTerm::Symbol("a".into()),
+                    PrecomputedTerm::Symbol("b".into()),
+                ],
+            };
+            values
+                .into_iter()
+                .map(|value| (placeholder.name.clone(), value))
+                .collect()
+        })
+        .collect();
+
+    product(values)
+        .into_iter()
+        .map(|values| values.into_iter().collect())
+        .collect()
+}
+
+/// The structure of an instance and an output, whose universe contains every value that occurs in them
+fn structure<'a>(
+    instance: &'a Instance,
+    universe: &[PrecomputedTerm],
+    atoms: impl Iterator<Item = &'a GroundAtom>,
+) -> Structure {
+    let mut structure = Structure::from_atoms(
+        instance.input.iter().chain(atoms),
+        instance.placeholders.clone(),
+    );
+    structure.universe.extend(universe.iter().cloned());
+    structure
+}
+
+fn check_assumptions(
+    instance: &Instance,
+    universe: &[PrecomputedTerm],
+    assumptions: &[fol::AnnotatedFormula],
+) -> Result<bool, BoundedCheckError> {
+    let structure = structure(instance, universe, std::iter::empty());
+    for assumption in assumptions {
+        let satisfied = structure
+            .satisfies(&assumption.formula)
+            .map_err(|e| BoundedCheckError::Evaluation(Box::new(assumption.clone()), e))?;
+        if !satisfied {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Find an output of the program that violates a formula of the specification
+fn check_outputs(
+    instance: &Instance,
+    universe: &[PrecomputedTerm],
+    outputs: Outputs,
+    formulas: &[fol::AnnotatedFormula],
+) -> Result<Option<Discrepancy>, BoundedCheckError> {
+    for output in outputs {
+        let structure = structure(instance, universe, output.iter());
+        for formula in formulas {
+            let satisfied = structure
+                .satisfies(&formula.formula)
+                .map_err(|e| BoundedCheckError::Evaluation(Box::new(formula.clone()), e))?;
+            if !satisfied {
+                return Ok(Some(Discrepancy::Violation {
+                    output,
+                    formula: formula.clone(),
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Compute the outputs of a program on an instance
+fn solve(
+    program: &asp::Program,
+    instance: &Instance,
+    output_predicates: &IndexSet<fol::Predicate>,
+) -> Result<Outputs, GroundingError> {
+    let mut program = program.clone();
+    program
+        .rules
+        .extend(instance.input.iter().map(|atom| asp::Rule {
+            head: asp::Head::Basic(atom.clone().into()),
+            body: asp::Body { formulas: vec![] },
+        }));
+
+    let constants: Vec<_> = instance
+        .placeholders
+        .iter()
+        .map(|(name, value)| Constant {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+
+    let program = ground(&program, &constants)?;
+
+    Ok(stable_models(&program, None)
+        .models
+        .into_iter()
+        .map(|model| {
+            model
+                .into_iter()
+                .filter(|atom| {
+                    output_predicates.contains(&fol::Predicate {
+                        symbol: atom.predicate_symbol.clone(),
+                        arity: atom.terms.len(),
+                    })
+                })
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{BoundedCheck, Discrepancy},
+        crate::syntax_tree::fol,
+        either::Either,
+    };
+
+    #[test]
+    fn test_bounded_check() {
+        let user_guide = "input: n -> integer. input: p/1. output: q/1. assumption: n >= 1."
+            .parse()
+            .unwrap();
+
+        let report = BoundedCheck {
+            specification: Either::Left("q(X) :- p(X), X <= n.".parse().unwrap()),
+            program: "q(X) :- p(X), X < n.".parse().unwrap(),
+            user_guide,
+        }
+        .run()
+        .unwrap();
+
+        assert!(report.exact);
+        let counterexample = report.counterexample.unwrap();
+        assert_eq!(
+            counterexample.to_string(),
+            "placeholders: n = 1\ninput: {p(1)}\nspecification output: {q(1)}\nprogram output: {}"
+        );
+    }
+
+    #[test]
+    fn test_bounded_check_quantified_assumption() {
+        // Every input satisfying the assumption contains `s(4)`, so both programs have no output,
+        // but the instances only satisfy the assumption within universes lacking 4
+        let user_guide = "input: s/1. output: q/0. assumption: forall X$i (X$i > 0 -> s(X$i))."
+            .parse()
+            .unwrap();
+
+        let report = BoundedCheck {
+            specification: Either::Left("q :- not s(4).".parse().unwrap()),
+            program: "".parse().unwrap(),
+            user_guide,
+        }
+        .run()
+        .unwrap();
+
+        assert!(report.counterexample.is_some());
+        assert!(!report.exact);
+    }
+
+    #[test]
+    fn test_bounded_check_specification() {
+        let user_guide: fol::UserGuide = "input: p/1. output: q/1.".parse().unwrap();
+
+        for (program, counterexample) in [("q(X) :- p(X).", false), ("q(1).", true)] {
+            let report = BoundedCheck {
+                specification: Either::Right("spec: forall X (q(X) <-> p(X)).".parse().unwrap()),
+                program: program.parse().unwrap(),
+                user_guide: user_guide.clone(),
+            }
+            .run()
+            .unwrap();
+
+            assert!(!report.exact);
+            assert_eq!(
+                matches!(
+                    report.counterexample.map(|c| c.discrepancy),
+                    Some(Discrepancy::Violation { .. })
+                ),
+                counterexample,
+                "{program}"
+            );
+        }
+    }
+}
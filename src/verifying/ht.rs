@@ -0,0 +1,440 @@
+use {
+    crate::{
+        solving::grounding::{ground, GroundAtom, GroundHead, GroundProgram, GroundingError},
+        syntax_tree::asp,
+        verifying::bounded::{BoundedCheckError, BoundedCheckReport},
+    },
+    indexmap::IndexSet,
+    itertools::Itertools as _,
+    std::fmt::{self, Display, Formatter},
+};
+
+/// The universes of the search are `{1}`, `{1, 2}` and `{1, 2, 3}`, extended by the symbols of the programs
+const MAX_UNIVERSE_SIZE: isize = 3;
+
+/// The maximal number of ground atoms of the HT-interpretations
+const MAX_ATOMS: usize = 12;
+
+/// The predicate enumerating the elements of the universe while grounding
+const UNIVERSE_PREDICATE: &str = "__universe";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn other(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Left => write!(f, "left"),
+            Side::Right => write!(f, "right"),
+        }
+    }
+}
+
+/// An HT-interpretation, given by the atoms true here and the atoms true there
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtInterpretation {
+    pub here: Vec<GroundAtom>,
+    pub there: Vec<GroundAtom>,
+}
+
+impl Display for HtInterpretation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<{{{}}}, {{{}}}>",
+            self.here.iter().format(" "),
+            self.there.iter().format(" ")
+        )
+    }
+}
+
+/// A witness that two programs are not strongly equivalent
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtCounterexample {
+    pub interpretation: HtInterpretation,
+    /// The program of which the interpretation is an HT-model, it is no HT-model of the other program
+    pub model_of: Side,
+    /// The rules whose addition to both programs results in different stable models
+    pub context: asp::Program,
+    /// The program that has the there-part of the interpretation as a stable model once the context is added
+    pub stable: Side,
+}
+
+impl Display for HtCounterexample {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "HT-interpretation: {}", self.interpretation)?;
+        writeln!(
+            f,
+            "The interpretation is an HT-model of the {} program but not of the {} program.",
+            self.model_of,
+            self.model_of.other()
+        )?;
+        writeln!(f, "added rules: {}", self.context.rules.iter().format(" "))?;
+        write!(
+            f,
+            "With the added rules, {{{}}} is a stable model of the {} program but not of the {} program.",
+            self.interpretation.there.iter().format(" "),
+            self.stable,
+            self.stable.other()
+        )
+    }
+}
+
+/// A ground rule whose body and head are represented by bit sets of atoms
+struct Rule {
+    head: GroundHead,
+    positive: u32,
+    negative: u32,
+    double_negative: u32,
+}
+
+impl Rule {
+    /// Whether the HT-interpretation `(here, there)` satisfies the rule, where `here` is a subset of `there`
+    fn satisfied(&self, here: u32, there: u32) -> bool {
+        let body = |atoms: u32| {
+            self.positive & atoms == self.positive
+                && self.negative & there == 0
+                && self.double_negative & there == self.double_negative
+        };
+
+        let head_there = match self.head {
+            GroundHead::Basic(atom) => there & 1 << atom != 0,
+            GroundHead::Choice(_) => true,
+            GroundHead::Falsity => false,
+        };
+        let head_here = match self.head {
+            GroundHead::Basic(atom) => here & 1 << atom != 0,
+            GroundHead::Choice(atom) => here & 1 << atom != 0 || there & 1 << atom == 0,
+            GroundHead::Falsity => false,
+        };
+
+        (!body(there) || head_there) && (!body(here) || head_here)
+    }
+}
+
+fn satisfied(rules: &[Rule], here: u32, there: u32) -> bool {
+    rules.iter().all(|rule| rule.satisfied(here, there))
+}
+
+/// Search for an HT-interpretation that is an HT-model of exactly one of two programs
+///
+/// The programs are grounded with respect to small universes, such that only finitely many HT-interpretations need to be considered.
+/// A found interpretation is turned into a set of rules whose addition to both programs results in different stable models.
+pub struct StrongEquivalenceCheck {
+    pub left: asp::Program,
+    pub right: asp::Program,
+}
+
+impl StrongEquivalenceCheck {
+    pub fn run(self) -> Result<BoundedCheckReport<HtCounterexample>, BoundedCheckError> {
+        let mut predicates = self.left.predicates();
+        predicates.extend(self.right.predicates());
+
+        let mut symbols = self.left.function_constants();
+        symbols.extend(self.right.function_constants());
+
+        let mut report = BoundedCheckReport {
+            instances: 0,
+            complete: true,
+            exact: true,
+            counterexample: None,
+        };
+
+        for size in 1..=MAX_UNIVERSE_SIZE {
+            let universe: Vec<_> = (1..=size)
+                .map(asp::PrecomputedTerm::Numeral)
+                .chain(symbols.iter().cloned().map(asp::PrecomputedTerm::Symbol))
+                .collect();
+
+            let context = universe_rules(&predicates, &universe);
+            let left = ground_with(&self.left, &context)
+                .map_err(|e| BoundedCheckError::Grounding("left program", e))?;
+            let right = ground_with(&self.right, &context)
+                .map_err(|e| BoundedCheckError::Grounding("right program", e))?;
+
+            let atoms: IndexSet<_> = left
+                .atoms
+                .iter()
+                .chain(&right.atoms)
+                .filter(|atom| atom.predicate_symbol != UNIVERSE_PREDICATE)
+                .cloned()
+                .collect();
+
+            if atoms.len() > MAX_ATOMS {
+                report.complete = false;
+                break;
+            }
+
+            let left_rules = rules(&left, &atoms);
+            let right_rules = rules(&right, &atoms);
+
+            for there in 0..1u32 << atoms.len() {
+                let mut here = there;
+                loop {
+                    report.instances += 1;
+
+                    let model_of = match (
+                        satisfied(&left_rules, here, there),
+                        satisfied(&right_rules, here, there),
+                    ) {
+                        (true, false) => Some(Side::Left),
+                        (false, true) => Some(Side::Right),
+                        _ => None,
+                    };
+
+                    if let Some(model_of) = model_of {
+                        report.counterexample = Some(counterexample(&atoms, here, there, model_of));
+                        return Ok(report);
+                    }
+
+                    // The programs agree on `(here, there)`, the proper subsets of `there` only matter if it is a model of both
+                    if here == there && !satisfied(&left_rules, there, there) {
+                        break;
+                    }
+                    if here == 0 {
+                        break;
+                    }
+                    here = (here - 1) & there;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// The positive body literal consisting of an atom
+fn literal(atom: asp::Atom) -> asp::ConditionalLiteral {
+    asp::ConditionalLiteral {
+        head: asp::ConditionalHead::AtomicFormula(asp::AtomicFormula::Literal(asp::Literal {
+            sign: asp::Sign::NoSign,
+            atom,
+        })),
+        conditions: asp::ConditionalBody { formulas: vec![] },
+    }
+}
+
+/// Choice rules making every atom over the universe possible, together with the facts describing the universe
+fn universe_rules(
+    predicates: &IndexSet<asp::Predicate>,
+    universe: &[asp::PrecomputedTerm],
+) -> Vec<asp::Rule> {
+    // The nullary atom guards the choice rules of nullary predicates, which marks them for removal after grounding
+    let universe_atom = |terms| asp::Atom {
+        predicate_symbol: UNIVERSE_PREDICATE.to_string(),
+        terms,
+    };
+
+    let mut rules: Vec<_> = universe
+        .iter()
+        .map(|element| vec![asp::Term::PrecomputedTerm(element.clone())])
+        .chain([vec![]])
+        .map(|terms| asp::Rule {
+            head: asp::Head::Basic(universe_atom(terms)),
+            body: asp::Body { formulas: vec![] },
+        })
+        .collect();
+
+    for predicate in predicates {
+        let variables: Vec<_> = (1..=predicate.arity)
+            .map(|i| asp::Term::Variable(asp::Variable(format!("X{i}"))))
+            .collect();
+
+        let guards = if variables.is_empty() {
+            vec![literal(universe_atom(vec![]))]
+        } else {
+            variables
+                .iter()
+                .map(|variable| literal(universe_atom(vec![variable.clone()])))
+                .collect()
+        };
+
+        rules.push(asp::Rule {
+            head: asp::Head::Choice(asp::Atom {
+                predicate_symbol: predicate.symbol.clone(),
+                terms: variables,
+            }),
+            body: asp::Body { formulas: guards },
+        });
+    }
+
+    rules
+}
+
+/// Ground a program together with the rules describing the universe, and drop the latter from the result
+fn ground_with(
+    program: &asp::Program,
+    context: &[asp::Rule],
+) -> Result<GroundProgram, GroundingError> {
+    let mut program = program.clone();
+    program.rules.extend(context.iter().cloned());
+
+    let mut ground_program = ground(&program, &[])?;
+
+    let universe =
+        |atom: &usize| ground_program.atoms[*atom].predicate_symbol == UNIVERSE_PREDICATE;
+    let rules = ground_program
+        .rules
+        .iter()
+        .filter(|rule| match rule.head {
+            GroundHead::Basic(atom) | GroundHead::Choice(atom) => !universe(&atom),
+            GroundHead::Falsity => true,
+        })
+        .filter(|rule| !rule.positive.iter().any(universe))
+        .cloned()
+        .collect();
+    ground_program.rules = rules;
+
+    Ok(ground_program)
+}
+
+/// Represent the rules of a ground program by bit sets over the given atoms
+fn rules(program: &GroundProgram, atoms: &IndexSet<GroundAtom>) -> Vec<Rule> {
+    let index = |atom: usize| atoms.get_index_of(&program.atoms[atom]).unwrap();
+    let set = |literals: &[usize]| literals.iter().fold(0, |set, &atom| set | 1 << index(atom));
+
+    program
+        .rules
+        .iter()
+        .map(|rule| Rule {
+            head: match rule.head {
+                GroundHead::Basic(atom) => GroundHead::Basic(index(atom)),
+                GroundHead::Choice(atom) => GroundHead::Choice(index(atom)),
+                GroundHead::Falsity => GroundHead::Falsity,
+            },
+            positive: set(&rule.positive),
+            negative: set(&rule.negative),
+            double_negative: set(&rule.double_negative),
+        })
+        .collect()
+}
+
+/// Turn an HT-model of one program that is no HT-model of the other into distinguishing rules
+///
+/// If `here` equals `there`, adding `there` as facts makes it a stable model of the program it satisfies.
+/// Otherwise, `there` is a classical model of both programs, and adding the facts `here` together with the rules `a :- b`
+/// for all atoms `a` and `b` in `there` but not in `here` makes it a stable model of the program violated by `(here, there)`.
+fn counterexample(
+    atoms: &IndexSet<GroundAtom>,
+    here: u32,
+    there: u32,
+    model_of: Side,
+) -> HtCounterexample {
+    let members = |set: u32| -> Vec<GroundAtom> {
+        atoms
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| set & 1 << i != 0)
+            .map(|(_, atom)| atom.clone())
+            .sorted()
+            .collect()
+    };
+
+    let fact = |atom: GroundAtom| asp::Rule {
+        head: asp::Head::Basic(atom.into()),
+        body: asp::Body { formulas: vec![] },
+    };
+
+    let interpretation = HtInterpretation {
+        here: members(here),
+        there: members(there),
+    };
+
+    let mut context: Vec<_> = interpretation.here.iter().cloned().map(fact).collect();
+    let gap = members(there & !here);
+    for (head, body) in gap.iter().cartesian_product(&gap) {
+        if head != body {
+            context.push(asp::Rule {
+                head: asp::Head::Basic(head.clone().into()),
+                body: asp::Body {
+                    formulas: vec![literal(body.clone().into())],
+                },
+            });
+        }
+    }
+
+    HtCounterexample {
+        interpretation,
+        model_of,
+        context: asp::Program { rules: context },
+        stable: if here == there {
+            model_of
+        } else {
+            model_of.other()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Side, StrongEquivalenceCheck};
+
+    fn check(left: &str, right: &str) -> Option<String> {
+        StrongEquivalenceCheck {
+            left: left.parse().unwrap(),
+            right: right.parse().unwrap(),
+        }
+        .run()
+        .unwrap()
+        .counterexample
+        .map(|counterexample| counterexample.to_string())
+    }
+
+    #[test]
+    fn test_strong_equivalence_check() {
+        for (left, right) in [
+            ("p :- not not p.", "{p}."),
+            ("p :- q. p :- r.", "p :- r. p :- q."),
+            ("q(X) :- p(X), not r(X).", "q(X) :- p(X), not r(X), p(X)."),
+            ("p :- not q.", "p :- not q. p :- not q, r."),
+        ] {
+            assert_eq!(check(left, right), None, "{left} {right}");
+        }
+    }
+
+    #[test]
+    fn test_strong_equivalence_counterexample() {
+        assert_eq!(
+            check("p :- not q.", "p."),
+            Some(
+                [
+                    "HT-interpretation: <{q}, {q}>",
+                    "The interpretation is an HT-model of the left program but not of the right program.",
+                    "added rules: q.",
+                    "With the added rules, {q} is a stable model of the left program but not of the right program.",
+                ]
+                .join("\n")
+            )
+        );
+
+        let counterexample = StrongEquivalenceCheck {
+            left: "p :- not not p.".parse().unwrap(),
+            right: "p :- p.".parse().unwrap(),
+        }
+        .run()
+        .unwrap()
+        .counterexample
+        .unwrap();
+        assert_eq!(counterexample.model_of, Side::Right);
+        assert_eq!(counterexample.interpretation.to_string(), "<{}, {p}>");
+        assert_eq!(counterexample.stable, Side::Left);
+
+        let counterexample = check("q(X) :- p(X).", "q(1) :- p(1).").unwrap();
+        assert!(
+            counterexample.contains("<{p(2)}, {p(2)}>"),
+            "{counterexample}"
+        );
+    }
+}
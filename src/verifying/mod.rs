@@ -1,4 +1,8 @@
+pub mod bounded;
+pub mod ht;
 pub mod outline;
 pub mod problem;
 pub mod prover;
+pub mod session;
+pub mod suggestion;
 pub mod task;
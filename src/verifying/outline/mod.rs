@@ -1,6 +1,7 @@
 use {
     crate::{
         convenience::{
+            choose_fresh_variable_names,
             unbox::{fol::UnboxedFormula, Unbox as _},
             with_warnings::{Result, WithWarnings},
         },
@@ -15,11 +16,58 @@ use {
 // If all the conjectures are proven,
 // then all consequences can be added as axioms to the next proof step
 // A basic lemma F has conjectures [F] and consequences [F]
-// An inductive lemma F has conjectures [Base, Step] and axioms [F]
+// An inductive lemma F has conjectures [Base_1, ..., Base_k, Step] and axioms [F]
+// A case lemma C_1 or ... or C_k -> F has conjectures [C_1 -> F, ..., C_k -> F, C_1 or ... or C_k] and axioms [F]
+// A lemma annotated with `using [...]` is proved from the named formulas only (in addition to the premises of the task)
+// The dependencies of a lemma are the indices of the earlier lemmas it relies on, which must be proven first
 #[derive(Clone, Debug, PartialEq)]
 pub struct GeneralLemma {
     pub conjectures: Vec<problem::AnnotatedFormula>,
     pub consequences: Vec<problem::AnnotatedFormula>,
+    pub axioms: Option<Vec<problem::AnnotatedFormula>>,
+    pub dependencies: Vec<usize>,
+}
+
+impl GeneralLemma {
+    /// The axioms of the problems proving the conjectures of the lemma, given the premises of the task,
+    /// the definitions of the proof outline, and the earlier lemmas of the proof outline
+    ///
+    /// Lemmas annotated with `using [...]` are proved from the premises and the named formulas only.
+    /// Any other lemma is proved from the premises, the definitions, and the consequences of the lemmas it depends on,
+    /// directly or indirectly, so that it is never proven from a lemma whose proof it does not wait for.
+    pub fn proof_axioms(
+        &self,
+        premises: &[problem::AnnotatedFormula],
+        definitions: &[problem::AnnotatedFormula],
+        earlier: &[GeneralLemma],
+    ) -> Vec<problem::AnnotatedFormula> {
+        match &self.axioms {
+            Some(dependencies) => premises.iter().chain(dependencies).cloned().collect(),
+            None => {
+                let mut required = vec![false; earlier.len()];
+                let mut pending = self.dependencies.clone();
+                while let Some(i) = pending.pop() {
+                    if !required[i] {
+                        required[i] = true;
+                        pending.extend(&earlier[i].dependencies);
+                    }
+                }
+
+                premises
+                    .iter()
+                    .chain(definitions)
+                    .chain(
+                        earlier
+                            .iter()
+                            .zip(required)
+                            .filter(|(_, required)| *required)
+                            .flat_map(|(lemma, _)| &lemma.consequences),
+                    )
+                    .cloned()
+                    .collect()
+            }
+        }
+    }
 }
 
 impl TryFrom<fol::AnnotatedFormula> for GeneralLemma {
@@ -35,45 +83,143 @@ impl TryFrom<fol::AnnotatedFormula> for GeneralLemma {
                     .into_problem_formula(problem::Role::Conjecture, problem::FormulaType::Tff)],
                 consequences: vec![annotated_formula
                     .into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff)],
+                axioms: None,
+                dependencies: vec![],
             }),
-            fol::Role::InductiveLemma => {
-                let induction_formulas = annotated_formula.formula.clone().inductive_lemma()?;
-                let (base, step) = induction_formulas.data;
+            fol::Role::InductiveLemma(scheme) => {
+                let induction_formulas =
+                    annotated_formula.formula.clone().inductive_lemma(scheme)?;
+                let (bases, step) = induction_formulas.data;
                 // TODO handle warnings
-                let base_annotated = fol::AnnotatedFormula {
-                    role: fol::Role::Lemma,
-                    direction: annotated_formula.direction,
-                    name: format!("{}base_case", annotated_formula.name),
-                    formula: base,
-                };
+                let numbered = bases.len() > 1;
+                let base_annotated =
+                    bases
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, base)| fol::AnnotatedFormula {
+                            role: fol::Role::Lemma,
+                            direction: annotated_formula.direction,
+                            name: if numbered {
+                                format!("{}base_case_{i}", annotated_formula.name)
+                            } else {
+                                format!("{}base_case", annotated_formula.name)
+                            },
+                            using: None,
+                            formula: base,
+                        });
                 let step_annotated = fol::AnnotatedFormula {
                     role: fol::Role::Lemma,
                     direction: annotated_formula.direction,
                     name: format!("{}inductive_step", annotated_formula.name),
+                    using: None,
                     formula: step,
                 };
                 Ok(GeneralLemma {
-                    conjectures: vec![
-                        base_annotated.into_problem_formula(
-                            problem::Role::Conjecture,
-                            problem::FormulaType::Tff,
-                        ),
-                        step_annotated.into_problem_formula(
-                            problem::Role::Conjecture,
-                            problem::FormulaType::Tff,
-                        ),
-                    ],
+                    conjectures: base_annotated
+                        .chain(std::iter::once(step_annotated))
+                        .map(|formula| {
+                            formula.into_problem_formula(
+                                problem::Role::Conjecture,
+                                problem::FormulaType::Tff,
+                            )
+                        })
+                        .collect(),
                     consequences: vec![annotated_formula
                         .into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff)],
+                    axioms: None,
+                    dependencies: vec![],
+                })
+            }
+            fol::Role::CaseLemma => {
+                let (cases, exhaustiveness, consequence) =
+                    annotated_formula.formula.clone().case_lemma()?.data;
+                let lemma = |name: String, formula| fol::AnnotatedFormula {
+                    role: fol::Role::Lemma,
+                    direction: annotated_formula.direction,
+                    name,
+                    using: None,
+                    formula,
+                };
+                Ok(GeneralLemma {
+                    conjectures: cases
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, case)| lemma(format!("{}case_{i}", annotated_formula.name), case))
+                        .chain(std::iter::once(lemma(
+                            format!("{}exhaustiveness", annotated_formula.name),
+                            exhaustiveness,
+                        )))
+                        .map(|formula| {
+                            formula.into_problem_formula(
+                                problem::Role::Conjecture,
+                                problem::FormulaType::Tff,
+                            )
+                        })
+                        .collect(),
+                    consequences: vec![lemma(annotated_formula.name.clone(), consequence)
+                        .into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff)],
+                    axioms: None,
+                    dependencies: vec![],
                 })
             }
             fol::Role::Assumption | fol::Role::Spec | fol::Role::Definition => Err(
-                ProofOutlineError::InvalidRoleForGeneralLemma(annotated_formula),
+                ProofOutlineError::InvalidRoleForGeneralLemma(annotated_formula.into()),
             ),
         }
     }
 }
 
+/// The lemma stating that the argument of the predicate at the given position (starting from 0) is an integer,
+/// e.g. `lemma[typing_p_2_0]: forall V1 V2 (p(V1, V2) -> exists N$i (V1 = N$i))`
+pub fn typing_lemma(
+    predicate: &fol::Predicate,
+    position: usize,
+    direction: fol::Direction,
+) -> fol::AnnotatedFormula {
+    let variables: Vec<_> = (1..=predicate.arity)
+        .map(|i| fol::Variable {
+            name: format!("V{i}"),
+            sort: fol::Sort::General,
+        })
+        .collect();
+
+    let atom = fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+        predicate_symbol: predicate.symbol.clone(),
+        terms: variables
+            .iter()
+            .map(|v| fol::GeneralTerm::Variable(v.name.clone()))
+            .collect(),
+    }));
+
+    let typing = fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+        term: fol::GeneralTerm::Variable(variables[position].name.clone()),
+        guards: vec![fol::Guard {
+            relation: fol::Relation::Equal,
+            term: fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable("N".into())),
+        }],
+    }))
+    .quantify(
+        fol::Quantifier::Exists,
+        vec![fol::Variable {
+            name: "N".into(),
+            sort: fol::Sort::Integer,
+        }],
+    );
+
+    fol::AnnotatedFormula {
+        role: fol::Role::Lemma,
+        direction,
+        name: format!("typing_{}_{}_{position}", predicate.symbol, predicate.arity),
+        using: None,
+        formula: fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Implication,
+            lhs: atom.into(),
+            rhs: typing.into(),
+        }
+        .quantify(fol::Quantifier::Forall, variables),
+    }
+}
+
 // TODO: Think about the name
 trait CheckInternal {
     // Returns the predicate defined in the LHS of the formula if it is a valid definition, else returns an error
@@ -82,10 +228,27 @@ trait CheckInternal {
         taken_predicates: &IndexSet<fol::Predicate>,
     ) -> Result<fol::Predicate, ProofOutlineWarning, ProofOutlineError>;
 
-    // Returns the base case and inductive step formulas if the formula is a valid inductive lemma, else returns an error
+    // Returns the axioms defining the predicate of a valid definition
+    // These are the definition itself or, for recursive definitions, its base case, recursive step, and range
+    fn defining_axioms(
+        &self,
+        predicate: &fol::Predicate,
+    ) -> Result<Vec<fol::Formula>, ProofOutlineWarning, ProofOutlineError>;
+
+    // Returns the base cases and inductive step formulas if the formula is a valid inductive lemma under the given scheme, else returns an error
     fn inductive_lemma(
         self,
-    ) -> Result<(fol::Formula, fol::Formula), ProofOutlineWarning, ProofOutlineError>;
+        scheme: fol::InductionScheme,
+    ) -> Result<(Vec<fol::Formula>, fol::Formula), ProofOutlineWarning, ProofOutlineError>;
+
+    // Returns the case conjectures, the exhaustiveness conjecture and the consequence if the formula is a valid case lemma, else returns an error
+    fn case_lemma(
+        self,
+    ) -> Result<
+        (Vec<fol::Formula>, fol::Formula, fol::Formula),
+        ProofOutlineWarning,
+        ProofOutlineError,
+    >;
 }
 
 impl CheckInternal for fol::Formula {
@@ -155,15 +318,24 @@ impl CheckInternal for fol::Formula {
                         warnings.push(ProofOutlineWarning::ExcessQuantifiedVariables(self.clone()));
                     }
 
-                    // check RHS has no predicates other than taken predicates
+                    // check RHS has no predicates other than taken predicates and the defined predicate
                     // this should ensure no recursion through definition sequence
-                    if let Some(predicate) = rhs.predicates().difference(taken_predicates).next() {
+                    if let Some(undefined) = rhs
+                        .predicates()
+                        .difference(taken_predicates)
+                        .find(|p| **p != predicate)
+                    {
                         return Err(ProofOutlineError::UndefinedRhsPredicate {
                             definition: self.clone(),
-                            predicate: predicate.clone(),
+                            predicate: undefined.clone(),
                         });
                     }
 
+                    // check recursion through the defined predicate is well-founded
+                    if rhs.predicates().contains(&predicate) {
+                        self.defining_axioms(&predicate)?;
+                    }
+
                     Ok(WithWarnings::flawless(predicate).preface_warnings(warnings))
                 }
                 _ => Err(ProofOutlineError::MalformedDefinition(self.clone())),
@@ -173,11 +345,137 @@ impl CheckInternal for fol::Formula {
         }
     }
 
+    fn defining_axioms(
+        &self,
+        predicate: &fol::Predicate,
+    ) -> Result<Vec<fol::Formula>, ProofOutlineWarning, ProofOutlineError> {
+        let (lhs, rhs) = match self.clone().unbox() {
+            UnboxedFormula::QuantifiedFormula {
+                quantification:
+                    fol::Quantification {
+                        quantifier: fol::Quantifier::Forall,
+                        ..
+                    },
+                formula:
+                    fol::Formula::BinaryFormula {
+                        connective: fol::BinaryConnective::Equivalence,
+                        lhs,
+                        rhs,
+                    },
+            } => (*lhs, *rhs),
+            _ => return Err(ProofOutlineError::MalformedDefinition(self.clone())),
+        };
+        if !rhs.predicates().contains(predicate) {
+            return Ok(WithWarnings::flawless(vec![self.clone()]));
+        }
+
+        let malformed = || ProofOutlineError::MalformedRecursiveDefinition(self.clone());
+
+        // The RHS must be of the form `N = n and B or N > n and S`
+        let mut disjuncts = fol::Formula::disjoin_invert(rhs).into_iter();
+        let (base, step) = match (disjuncts.next(), disjuncts.next(), disjuncts.next()) {
+            (Some(base), Some(step), None) => (base, step),
+            _ => return Err(malformed()),
+        };
+        let guarded = |disjunct: fol::Formula, relation: fol::Relation| {
+            let mut conjuncts = fol::Formula::conjoin_invert(disjunct).into_iter();
+            match conjuncts.next() {
+                Some(fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(
+                    fol::Comparison { term, guards },
+                ))) => match guards.as_slice() {
+                    [fol::Guard {
+                        relation: r,
+                        term: fol::GeneralTerm::IntegerTerm(bound),
+                    }] if *r == relation => match term {
+                        fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(name)) => {
+                            Some((name, bound.clone(), fol::Formula::conjoin(conjuncts)))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            }
+        };
+        let (variable, bound, base) = guarded(base, fol::Relation::Equal).ok_or_else(malformed)?;
+        let (step_variable, step_bound, step) =
+            guarded(step, fol::Relation::Greater).ok_or_else(malformed)?;
+        if variable != step_variable || bound != step_bound || !bound.variables().is_empty() {
+            return Err(malformed());
+        }
+
+        let recursion_variable = fol::Variable {
+            name: variable,
+            sort: fol::Sort::Integer,
+        };
+        let recursion_term = fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(
+            recursion_variable.name.clone(),
+        ));
+        let position = match &lhs {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom)) => atom
+                .terms
+                .iter()
+                .position(|term| *term == recursion_term)
+                .ok_or_else(malformed)?,
+            _ => return Err(malformed()),
+        };
+
+        // Every occurrence of the defined predicate must decrease the recursion variable
+        if !decreasing(&base, predicate, &recursion_variable, position)
+            || !decreasing(&step, predicate, &recursion_variable, position)
+        {
+            return Err(ProofOutlineError::RecursionNotDecreasing(self.clone()));
+        }
+
+        let comparison = |relation| {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+                term: recursion_term.clone(),
+                guards: vec![fol::Guard {
+                    relation,
+                    term: fol::GeneralTerm::IntegerTerm(bound.clone()),
+                }],
+            }))
+        };
+        let equivalence = |lhs: fol::Formula, rhs: fol::Formula| fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Equivalence,
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+        };
+        let implication = |lhs: fol::Formula, rhs: fol::Formula| fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Implication,
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+        };
+
+        let base_case = equivalence(lhs.clone(), base).substitute(
+            recursion_variable,
+            fol::GeneralTerm::IntegerTerm(bound.clone()),
+        );
+        let recursive_step = implication(
+            comparison(fol::Relation::Greater),
+            equivalence(lhs.clone(), step),
+        );
+        let range = implication(
+            comparison(fol::Relation::Less),
+            fol::Formula::UnaryFormula {
+                connective: fol::UnaryConnective::Negation,
+                formula: lhs.into(),
+            },
+        );
+
+        Ok(WithWarnings::flawless(vec![
+            base_case.universal_closure(),
+            recursive_step.universal_closure(),
+            range.universal_closure(),
+        ]))
+    }
+
     fn inductive_lemma(
         self,
-    ) -> Result<(fol::Formula, fol::Formula), ProofOutlineWarning, ProofOutlineError> {
+        scheme: fol::InductionScheme,
+    ) -> Result<(Vec<fol::Formula>, fol::Formula), ProofOutlineWarning, ProofOutlineError> {
         let original = self.clone();
-        match self.unbox() {
+        let (variables, lhs, rhs, comparison) = match self.unbox() {
             UnboxedFormula::QuantifiedFormula {
                 quantification:
                     fol::Quantification {
@@ -191,82 +489,397 @@ impl CheckInternal for fol::Formula {
                         rhs,
                     },
             } => match lhs.clone().unbox() {
-                UnboxedFormula::AtomicFormula(fol::AtomicFormula::Comparison(
-                    fol::Comparison { term, guards },
-                )) => {
-                    if guards.len() != 1 {
-                        return Err(ProofOutlineError::MalformedInductiveAntecedent(original));
-                    }
-                    let varset: IndexSet<fol::Variable> = IndexSet::from_iter(variables.clone());
-                    if varset != rhs.free_variables() {
-                        return Err(ProofOutlineError::MalformedInductiveVariables(original));
-                    }
+                UnboxedFormula::AtomicFormula(fol::AtomicFormula::Comparison(comparison)) => {
+                    (variables, *lhs, *rhs, comparison)
+                }
+                _ => return Err(ProofOutlineError::MalformedInductiveLemma(original)),
+            },
+            _ => return Err(ProofOutlineError::MalformedInductiveLemma(original)),
+        };
 
-                    let induction_variable = match term {
-                        fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(ref v)) => {
-                            fol::Variable {
-                                name: v.to_string(),
-                                sort: fol::Sort::Integer,
+        if comparison.guards.is_empty() || comparison.guards.len() > 2 {
+            return Err(ProofOutlineError::MalformedInductiveAntecedent(original));
+        }
+        let varset: IndexSet<fol::Variable> = IndexSet::from_iter(variables);
+        if varset != rhs.free_variables() {
+            return Err(ProofOutlineError::MalformedInductiveVariables(original));
+        }
+
+        // The induction term is the variable compared against the bounds, i.e. `N` in `N >= n`, `N <= n`, or `n <= N <= m`
+        let (induction_term, bounds) = match comparison.guards.as_slice() {
+            [guard] => (&comparison.term, vec![guard.clone()]),
+            [lower, upper] if lower.relation == fol::Relation::LessEqual => (
+                &lower.term,
+                vec![
+                    fol::Guard {
+                        relation: fol::Relation::GreaterEqual,
+                        term: comparison.term.clone(),
+                    },
+                    upper.clone(),
+                ],
+            ),
+            _ => return Err(ProofOutlineError::MalformedInductiveAntecedent(original)),
+        };
+
+        let induction_variable = match induction_term {
+            fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(v)) => fol::Variable {
+                name: v.to_string(),
+                sort: fol::Sort::Integer,
+            },
+            _ => return Err(ProofOutlineError::MalformedInductiveTerm(original)),
+        };
+
+        let mut start = None;
+        let mut end = None;
+        let mut upward = true;
+        for (i, fol::Guard { relation, term }) in bounds.into_iter().enumerate() {
+            let bound = match term {
+                fol::GeneralTerm::IntegerTerm(bound) => bound,
+                _ => return Err(ProofOutlineError::MalformedInductiveLemma(original)),
+            };
+            if bound.variables().contains(&induction_variable) {
+                return Err(ProofOutlineError::MalformedInductiveBound(original));
+            }
+            match (i, relation) {
+                (0, fol::Relation::GreaterEqual) => start = Some(bound),
+                (0, fol::Relation::LessEqual) => {
+                    start = Some(bound);
+                    upward = false;
+                }
+                (1, fol::Relation::LessEqual) => end = Some(bound),
+                (0, _) => return Err(ProofOutlineError::MalformedInductiveLemma(original)),
+                _ => return Err(ProofOutlineError::MalformedInductiveAntecedent(original)),
+            }
+        }
+        let start = start.unwrap();
+
+        // Move `term` by `distance` in the direction of the induction
+        let advance = |term: fol::IntegerTerm, distance: u32| -> fol::IntegerTerm {
+            let distance = if upward {
+                distance as isize
+            } else {
+                -(distance as isize)
+            };
+            match term {
+                _ if distance == 0 => term,
+                fol::IntegerTerm::Numeral(n) => fol::IntegerTerm::Numeral(n + distance),
+                term => fol::IntegerTerm::BinaryOperation {
+                    op: if distance > 0 {
+                        fol::BinaryOperator::Add
+                    } else {
+                        fol::BinaryOperator::Subtract
+                    },
+                    lhs: term.into(),
+                    rhs: fol::IntegerTerm::Numeral(distance.abs()).into(),
+                },
+            }
+        };
+        let instance = |term: fol::IntegerTerm| {
+            rhs.clone().substitute(
+                induction_variable.clone(),
+                fol::GeneralTerm::IntegerTerm(term),
+            )
+        };
+        let comparison = |lhs: fol::IntegerTerm, relation, rhs: fol::IntegerTerm| {
+            fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+                term: fol::GeneralTerm::IntegerTerm(lhs),
+                guards: vec![fol::Guard {
+                    relation,
+                    term: fol::GeneralTerm::IntegerTerm(rhs),
+                }],
+            }))
+        };
+        let implication = |lhs: fol::Formula, rhs: fol::Formula| fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Implication,
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+        };
+        let conjunction = |lhs: fol::Formula, rhs: fol::Formula| fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Conjunction,
+            lhs: lhs.into(),
+            rhs: rhs.into(),
+        };
+        let induction_term = fol::IntegerTerm::Variable(induction_variable.name.clone());
+
+        let (bases, step) = match scheme {
+            fol::InductionScheme::Simple | fol::InductionScheme::Step(_) => {
+                let k = match scheme {
+                    fol::InductionScheme::Step(k) => k,
+                    _ => 1,
+                };
+                if k == 0 {
+                    return Err(ProofOutlineError::MalformedInductiveStep(original));
+                }
+
+                // The bases are the first k instances within the range of the lemma
+                let mut bases = Vec::new();
+                for i in 0..k {
+                    let base = advance(start.clone(), i);
+                    let formula = match (&end, &base) {
+                        (None, _) => instance(base),
+                        (Some(fol::IntegerTerm::Numeral(m)), fol::IntegerTerm::Numeral(n)) => {
+                            if n > m {
+                                continue;
                             }
+                            instance(base)
                         }
-                        _ => return Err(ProofOutlineError::MalformedInductiveTerm(original)),
+                        (Some(end), _) => implication(
+                            comparison(base.clone(), fol::Relation::LessEqual, end.clone()),
+                            instance(base),
+                        ),
                     };
+                    bases.push(formula.universal_closure());
+                }
 
-                    let guard = guards[0].clone();
-
-                    match term {
-                        fol::GeneralTerm::IntegerTerm(induction_term) => match guard {
-                            fol::Guard {
-                                relation: fol::Relation::GreaterEqual,
-                                term: fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Numeral(n)),
-                            } => {
-                                let least_term =
-                                    fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Numeral(n));
-                                let base_case = rhs
-                                    .clone()
-                                    .substitute(induction_variable.clone(), least_term)
-                                    .universal_closure();
-                                let inductive_step_antecedent = fol::Formula::BinaryFormula {
-                                    connective: fol::BinaryConnective::Conjunction,
-                                    lhs: lhs.clone(),
-                                    rhs: rhs.clone(),
-                                };
-
-                                let successor = fol::GeneralTerm::IntegerTerm(
-                                    fol::IntegerTerm::BinaryOperation {
-                                        op: fol::BinaryOperator::Add,
-                                        lhs: induction_term.clone().into(),
-                                        rhs: fol::IntegerTerm::Numeral(1).into(),
-                                    },
-                                );
+                // The step requires both N and N+k to be within the range of the lemma
+                let range = match end {
+                    None => lhs,
+                    // Bounded ranges `n <= N <= m` are always traversed upward
+                    Some(end) => fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(
+                        fol::Comparison {
+                            term: fol::GeneralTerm::IntegerTerm(start),
+                            guards: vec![
+                                fol::Guard {
+                                    relation: fol::Relation::LessEqual,
+                                    term: fol::GeneralTerm::IntegerTerm(induction_term.clone()),
+                                },
+                                fol::Guard {
+                                    relation: fol::Relation::LessEqual,
+                                    term: fol::GeneralTerm::IntegerTerm(match end {
+                                        fol::IntegerTerm::Numeral(m) => {
+                                            fol::IntegerTerm::Numeral(m - k as isize)
+                                        }
+                                        end => fol::IntegerTerm::BinaryOperation {
+                                            op: fol::BinaryOperator::Subtract,
+                                            lhs: end.into(),
+                                            rhs: fol::IntegerTerm::Numeral(k as isize).into(),
+                                        },
+                                    }),
+                                },
+                            ],
+                        },
+                    )),
+                };
+                let step = implication(
+                    conjunction(range, rhs.clone()),
+                    instance(advance(induction_term, k)),
+                )
+                .universal_closure();
 
-                                let inductive_step_consequent =
-                                    rhs.substitute(induction_variable.clone(), successor);
-                                let inductive_step = fol::Formula::BinaryFormula {
-                                    connective: fol::BinaryConnective::Implication,
-                                    lhs: inductive_step_antecedent.into(),
-                                    rhs: inductive_step_consequent.into(),
-                                }
-                                .universal_closure();
+                (bases, step)
+            }
+            fol::InductionScheme::Strong => {
+                // Every instance within the range of the lemma that precedes N may be assumed
+                let name =
+                    choose_fresh_variable_names(&original.variables(), &induction_variable.name, 1)
+                        .pop()
+                        .unwrap();
+                let predecessor = fol::Variable {
+                    name: name.clone(),
+                    sort: fol::Sort::Integer,
+                };
+                let predecessor_term = fol::IntegerTerm::Variable(name);
 
-                                Ok(WithWarnings::flawless((base_case, inductive_step)))
-                            }
-                            _ => Err(ProofOutlineError::MalformedInductiveLemma(original)),
-                        },
-                        _ => Err(ProofOutlineError::MalformedInductiveLemma(original)),
-                    }
+                let hypothesis = fol::Formula::QuantifiedFormula {
+                    quantification: fol::Quantification {
+                        quantifier: fol::Quantifier::Forall,
+                        variables: vec![predecessor.clone()],
+                    },
+                    formula: implication(
+                        conjunction(
+                            lhs.clone().substitute(
+                                induction_variable.clone(),
+                                fol::GeneralTerm::IntegerTerm(predecessor_term.clone()),
+                            ),
+                            comparison(
+                                predecessor_term.clone(),
+                                if upward {
+                                    fol::Relation::Less
+                                } else {
+                                    fol::Relation::Greater
+                                },
+                                induction_term.clone(),
+                            ),
+                        ),
+                        instance(predecessor_term),
+                    )
+                    .into(),
+                };
+
+                let step =
+                    implication(conjunction(lhs, hypothesis), rhs.clone()).universal_closure();
+
+                (vec![], step)
+            }
+        };
+
+        Ok(WithWarnings::flawless((bases, step)))
+    }
+
+    fn case_lemma(
+        self,
+    ) -> Result<
+        (Vec<fol::Formula>, fol::Formula, fol::Formula),
+        ProofOutlineWarning,
+        ProofOutlineError,
+    > {
+        let original = self.clone();
+        let (antecedent, consequent) = match self.unbox() {
+            UnboxedFormula::QuantifiedFormula {
+                quantification:
+                    fol::Quantification {
+                        quantifier: fol::Quantifier::Forall,
+                        ..
+                    },
+                formula:
+                    fol::Formula::BinaryFormula {
+                        connective: fol::BinaryConnective::Implication,
+                        lhs,
+                        rhs,
+                    },
+            } => (*lhs, *rhs),
+            UnboxedFormula::BinaryFormula {
+                connective: fol::BinaryConnective::Implication,
+                lhs,
+                rhs,
+            } => (lhs, rhs),
+            _ => return Err(ProofOutlineError::MalformedCaseLemma(original)),
+        };
+
+        let cases = fol::Formula::disjoin_invert(antecedent.clone());
+        if cases.len() < 2 {
+            return Err(ProofOutlineError::MalformedCaseLemma(original));
+        }
+
+        let cases = cases
+            .into_iter()
+            .map(|case| {
+                fol::Formula::BinaryFormula {
+                    connective: fol::BinaryConnective::Implication,
+                    lhs: case.into(),
+                    rhs: consequent.clone().into(),
                 }
-                _ => Err(ProofOutlineError::MalformedInductiveLemma(original)),
-            },
-            _ => Err(ProofOutlineError::MalformedInductiveLemma(original)),
+                .universal_closure()
+            })
+            .collect();
+
+        Ok(WithWarnings::flawless((
+            cases,
+            antecedent.universal_closure(),
+            consequent.universal_closure(),
+        )))
+    }
+}
+
+// Check that every occurrence of the predicate within the formula has a term `N - k` for a positive numeral `k` at the given position,
+// where `N` is the (unshadowed) recursion variable
+fn decreasing(
+    formula: &fol::Formula,
+    predicate: &fol::Predicate,
+    variable: &fol::Variable,
+    position: usize,
+) -> bool {
+    match formula {
+        fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom)) => {
+            atom.predicate() != *predicate
+                || matches!(
+                    &atom.terms[position],
+                    fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::BinaryOperation {
+                        op: fol::BinaryOperator::Subtract,
+                        lhs,
+                        rhs,
+                    }) if **lhs == fol::IntegerTerm::Variable(variable.name.clone())
+                        && matches!(**rhs, fol::IntegerTerm::Numeral(k) if k > 0)
+                )
+        }
+        fol::Formula::AtomicFormula(_) => true,
+        fol::Formula::UnaryFormula { formula, .. } => {
+            decreasing(formula, predicate, variable, position)
+        }
+        fol::Formula::BinaryFormula { lhs, rhs, .. } => {
+            decreasing(lhs, predicate, variable, position)
+                && decreasing(rhs, predicate, variable, position)
+        }
+        fol::Formula::QuantifiedFormula {
+            quantification,
+            formula,
+        } => {
+            (!quantification.variables.contains(variable)
+                || !formula.predicates().contains(predicate))
+                && decreasing(formula, predicate, variable, position)
+        }
+    }
+}
+
+// The axioms of the formulas named in the `using` annotation of a lemma, and the indices of the named lemmas
+type Dependencies = (Vec<problem::AnnotatedFormula>, Vec<usize>);
+
+// Collect the dependencies of a lemma annotated with `using [...]`, if any
+fn dependencies(
+    lemma: &fol::AnnotatedFormula,
+    available: &IndexMap<String, (Vec<problem::AnnotatedFormula>, Option<usize>)>,
+) -> std::result::Result<Option<Dependencies>, ProofOutlineError> {
+    let Some(names) = &lemma.using else {
+        return Ok(None);
+    };
+
+    let mut axioms = Vec::new();
+    let mut indices = Vec::new();
+    for name in names {
+        match available.get(name) {
+            Some((formulas, index)) => {
+                axioms.extend(formulas.iter().cloned());
+                indices.extend(index);
+            }
+            None => {
+                return Err(ProofOutlineError::UnknownDependency {
+                    name: name.clone(),
+                    lemma: lemma.formula.clone(),
+                })
+            }
+        }
+    }
+
+    Ok(Some((axioms, indices)))
+}
+
+// Collect the indices of the earlier lemmas whose consequences share a predicate with the conjectures of a lemma,
+// where a defined predicate also stands for the predicates occurring in its definition
+fn related(
+    lemma: &GeneralLemma,
+    earlier: &[GeneralLemma],
+    definitions: &IndexMap<fol::Predicate, IndexSet<fol::Predicate>>,
+) -> Vec<usize> {
+    let mut predicates: IndexSet<_> = lemma
+        .conjectures
+        .iter()
+        .flat_map(|c| c.predicates())
+        .collect();
+
+    let mut i = 0;
+    while let Some(predicate) = predicates.get_index(i) {
+        if let Some(used) = definitions.get(predicate) {
+            predicates.extend(used.clone());
         }
+        i += 1;
     }
+
+    earlier
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| {
+            l.consequences
+                .iter()
+                .any(|c| !c.predicates().is_disjoint(&predicates))
+        })
+        .map(|(i, _)| i)
+        .collect()
 }
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ProofOutlineError {
     #[error("the following annotated formula has a role that is forbidden in proof outlines: {0}")]
-    AnnotatedFormulaWithInvalidRole(fol::AnnotatedFormula),
+    AnnotatedFormulaWithInvalidRole(Box<fol::AnnotatedFormula>),
     #[error(
         "the following definiton contains duplicated variables in outermost quantification: {0}"
     )]
@@ -299,10 +912,28 @@ pub enum ProofOutlineError {
         "the inductive term in the following inductive lemma is not an integer variable: `{0}`"
     )]
     MalformedInductiveTerm(fol::Formula),
+    #[error("the bounds of the following inductive lemma contain the induction variable: `{0}`")]
+    MalformedInductiveBound(fol::Formula),
+    #[error("the step size of the following inductive lemma must be positive: `{0}`")]
+    MalformedInductiveStep(fol::Formula),
+    #[error(
+        "the following case lemma is not of the form `C1 or ... or Ck -> F` for k >= 2: `{0}`"
+    )]
+    MalformedCaseLemma(fol::Formula),
+    #[error("the following recursive definition is not of the form `forall X N (p(X, N) <-> N = n and B or N > n and S)`: {0}")]
+    MalformedRecursiveDefinition(fol::Formula),
+    #[error("the following recursive definition contains an occurrence of the defined predicate whose recursion argument is not of the form `N - k` for a positive numeral `k`: {0}")]
+    RecursionNotDecreasing(fol::Formula),
     #[error("the following definition is malformed: {0}")]
     MalformedDefinition(fol::Formula),
+    #[error("`{name}` does not name an earlier lemma or definition of the same direction, but is used by the following lemma: `{lemma}`")]
+    UnknownDependency { name: String, lemma: fol::Formula },
+    #[error(
+        "only lemmas may restrict their axioms with `using`, but the following formula does: `{0}`"
+    )]
+    InvalidUsingAnnotation(Box<fol::AnnotatedFormula>),
     #[error("the following annotated formula cannot be converted to a general lemma: `{0}`")]
-    InvalidRoleForGeneralLemma(fol::AnnotatedFormula),
+    InvalidRoleForGeneralLemma(Box<fol::AnnotatedFormula>),
 }
 
 pub struct ProofOutline {
@@ -340,42 +971,123 @@ impl ProofOutline {
         let mut forward_definitions = Vec::new();
         let mut backward_definitions = Vec::new();
 
+        // The axioms contributed by the named lemmas and definitions, which later lemmas may be restricted to
+        let mut forward_names = IndexMap::new();
+        let mut backward_names = IndexMap::new();
+
+        // The predicates occurring in the definition of each defined predicate
+        let mut defined = IndexMap::new();
+
         for anf in specification.formulas {
             let anf = anf.replace_placeholders(placeholders);
             match anf.role {
-                fol::Role::Lemma | fol::Role::InductiveLemma => {
+                fol::Role::Lemma | fol::Role::InductiveLemma(_) | fol::Role::CaseLemma => {
                     let general_lemma: GeneralLemma = anf
                         .universal_closure_with_quantifier_joining()
                         .replace_placeholders(placeholders)
                         .try_into()?;
-                    match anf.direction {
-                        fol::Direction::Universal => {
-                            forward_lemmas.push(general_lemma.clone());
-                            backward_lemmas.push(general_lemma);
+
+                    for (lemmas, names, applies) in [
+                        (
+                            &mut forward_lemmas,
+                            &mut forward_names,
+                            matches!(
+                                anf.direction,
+                                fol::Direction::Universal | fol::Direction::Forward
+                            ),
+                        ),
+                        (
+                            &mut backward_lemmas,
+                            &mut backward_names,
+                            matches!(
+                                anf.direction,
+                                fol::Direction::Universal | fol::Direction::Backward
+                            ),
+                        ),
+                    ] {
+                        if applies {
+                            // Without a `using` annotation, a lemma depends on the earlier lemmas about related predicates
+                            let (axioms, indices) = match dependencies(&anf, names)? {
+                                Some((axioms, indices)) => (Some(axioms), indices),
+                                None => (None, related(&general_lemma, lemmas, &defined)),
+                            };
+                            if !anf.name.is_empty() {
+                                names.insert(
+                                    anf.name.clone(),
+                                    (general_lemma.consequences.clone(), Some(lemmas.len())),
+                                );
+                            }
+                            lemmas.push(GeneralLemma {
+                                axioms,
+                                dependencies: indices,
+                                ..general_lemma.clone()
+                            });
                         }
-                        fol::Direction::Forward => forward_lemmas.push(general_lemma),
-                        fol::Direction::Backward => backward_lemmas.push(general_lemma),
                     }
                 }
+                fol::Role::Definition if anf.using.is_some() => {
+                    return Err(ProofOutlineError::InvalidUsingAnnotation(anf.into()))
+                }
                 fol::Role::Definition => {
                     let predicate = anf.formula.definition(&taken_predicates)?;
+                    let axioms = anf.formula.defining_axioms(&predicate.data)?.data;
+                    let mut used = anf.formula.predicates();
+                    used.shift_remove(&predicate.data);
+                    defined.insert(predicate.data.clone(), used);
                     taken_predicates.insert(predicate.data);
                     warnings.extend(predicate.warnings);
+
+                    // Recursive definitions are replaced by their base case, recursive step, and range
+                    let definitions: Vec<_> = if axioms.len() == 1 {
+                        vec![anf.clone()]
+                    } else {
+                        axioms
+                            .into_iter()
+                            .zip(["base_case", "recursive_step", "range"])
+                            .map(|(formula, suffix)| fol::AnnotatedFormula {
+                                role: fol::Role::Definition,
+                                direction: anf.direction,
+                                name: format!("{}{suffix}", anf.name),
+                                using: None,
+                                formula,
+                            })
+                            .collect()
+                    };
+                    if !anf.name.is_empty() {
+                        let axioms: Vec<_> = definitions
+                            .iter()
+                            .map(|f| {
+                                f.clone().into_problem_formula(
+                                    problem::Role::Axiom,
+                                    problem::FormulaType::Tff,
+                                )
+                            })
+                            .collect();
+                        if !matches!(anf.direction, fol::Direction::Backward) {
+                            forward_names.insert(anf.name.clone(), (axioms.clone(), None));
+                        }
+                        if !matches!(anf.direction, fol::Direction::Forward) {
+                            backward_names.insert(anf.name.clone(), (axioms, None));
+                        }
+                    }
+
                     match anf.direction {
                         fol::Direction::Forward => {
-                            forward_definitions.push(anf);
+                            forward_definitions.extend(definitions);
                         }
                         fol::Direction::Backward => {
-                            backward_definitions.push(anf);
+                            backward_definitions.extend(definitions);
                         }
                         fol::Direction::Universal => {
-                            forward_definitions.push(anf.clone());
-                            backward_definitions.push(anf);
+                            forward_definitions.extend(definitions.clone());
+                            backward_definitions.extend(definitions);
                         }
                     }
                 }
                 fol::Role::Assumption | fol::Role::Spec => {
-                    return Err(ProofOutlineError::AnnotatedFormulaWithInvalidRole(anf))
+                    return Err(ProofOutlineError::AnnotatedFormulaWithInvalidRole(
+                        anf.into(),
+                    ))
                 }
             }
         }
@@ -393,9 +1105,12 @@ impl ProofOutline {
 #[cfg(test)]
 mod tests {
     use {
-        super::{CheckInternal, ProofOutlineError},
-        crate::syntax_tree::fol,
-        indexmap::IndexSet,
+        super::{CheckInternal, GeneralLemma, ProofOutline, ProofOutlineError},
+        crate::{
+            syntax_tree::fol,
+            verifying::{problem, task::outline_problems},
+        },
+        indexmap::{IndexMap, IndexSet},
     };
 
     #[test]
@@ -415,6 +1130,13 @@ mod tests {
                     arity: 2,
                 },
             ),
+            (
+                "forall X N$ ( path(X, N$) <-> N$ = 0 and t(X) or N$ > 0 and exists Y (t(Y) and path(Y, N$ - 1)) )",
+                fol::Predicate {
+                    symbol: "path".to_string(),
+                    arity: 2,
+                },
+            ),
         ] {
             let taken_predicates: IndexSet<fol::Predicate> =
                 IndexSet::from_iter(vec![fol::Predicate {
@@ -452,15 +1174,43 @@ mod tests {
             ),
             (
                 "forall Z1 Z2 ( ancestor(Z1, Z2) <-> ancestor(Z1, Z2) )",
-                ProofOutlineError::UndefinedRhsPredicate {
-                    definition: "forall Z1 Z2 ( ancestor(Z1, Z2) <-> ancestor(Z1, Z2) )"
+                ProofOutlineError::MalformedRecursiveDefinition(
+                    "forall Z1 Z2 ( ancestor(Z1, Z2) <-> ancestor(Z1, Z2) )"
                         .parse()
                         .unwrap(),
-                    predicate: fol::Predicate {
-                        symbol: "ancestor".to_string(),
-                        arity: 2,
-                    },
-                },
+                ),
+            ),
+            (
+                "forall X N$ ( path(X, N$) <-> N$ = 0 and t(X) or N$ > 0 and path(X, N$) )",
+                ProofOutlineError::RecursionNotDecreasing(
+                    "forall X N$ ( path(X, N$) <-> N$ = 0 and t(X) or N$ > 0 and path(X, N$) )"
+                        .parse()
+                        .unwrap(),
+                ),
+            ),
+            (
+                "forall X N$ ( path(X, N$) <-> N$ = 0 and t(X) or N$ > 0 and exists N$ path(X, N$ - 1) )",
+                ProofOutlineError::RecursionNotDecreasing(
+                    "forall X N$ ( path(X, N$) <-> N$ = 0 and t(X) or N$ > 0 and exists N$ path(X, N$ - 1) )"
+                        .parse()
+                        .unwrap(),
+                ),
+            ),
+            (
+                "forall X N$ ( path(X, N$) <-> N$ = X$ and t(X) or N$ > X$ and path(X, N$ - 1) )",
+                ProofOutlineError::FreeRhsVariables(
+                    "forall X N$ ( path(X, N$) <-> N$ = X$ and t(X) or N$ > X$ and path(X, N$ - 1) )"
+                        .parse()
+                        .unwrap(),
+                ),
+            ),
+            (
+                "forall M$ N$ ( path(M$, N$) <-> N$ = M$ and t(M$) or N$ > M$ and path(M$, N$ - 1) )",
+                ProofOutlineError::MalformedRecursiveDefinition(
+                    "forall M$ N$ ( path(M$, N$) <-> N$ = M$ and t(M$) or N$ > M$ and path(M$, N$ - 1) )"
+                        .parse()
+                        .unwrap(),
+                ),
             ),
         ] {
             let taken_predicates: IndexSet<fol::Predicate> =
@@ -503,7 +1253,12 @@ mod tests {
             ),
             ] {
                 let formula: fol::Formula = src.parse().unwrap();
-                let (base_result, step_result) = formula.inductive_lemma().unwrap().data;
+                let (mut bases, step_result) = formula
+                    .inductive_lemma(fol::InductionScheme::Simple)
+                    .unwrap()
+                    .data;
+                assert_eq!(bases.len(), 1);
+                let base_result = bases.pop().unwrap();
                 let (base_target, step_target): (fol::Formula, fol::Formula) =
                     (base.parse().unwrap(), step.parse().unwrap());
                 assert_eq!(
@@ -537,7 +1292,356 @@ mod tests {
             ),
         ] {
             let formula: fol::Formula = src.parse().unwrap();
-            assert_eq!(formula.inductive_lemma(), Err(target))
+            assert_eq!(
+                formula.inductive_lemma(fol::InductionScheme::Simple),
+                Err(target)
+            )
         }
     }
+
+    #[test]
+    fn test_induction_schemes() {
+        for (src, scheme, bases, step) in [
+            (
+                "forall N$ ( N$ <= 5 -> p(N$) )",
+                fol::InductionScheme::Simple,
+                "p(5).",
+                "forall N$ ( N$ <= 5 and p(N$) -> p(N$ - 1) )",
+            ),
+            (
+                "forall N$ ( 1 <= N$ <= 10 -> p(N$) )",
+                fol::InductionScheme::Simple,
+                "p(1).",
+                "forall N$ ( 1 <= N$ <= 9 and p(N$) -> p(N$ + 1) )",
+            ),
+            (
+                "forall N$ X$ ( X$ <= N$ <= b$i -> p(N$, X$) )",
+                fol::InductionScheme::Simple,
+                "forall X$ ( X$ <= b$i -> p(X$, X$) ).",
+                "forall N$ X$ ( X$ <= N$ <= b$i - 1 and p(N$, X$) -> p(N$ + 1, X$) )",
+            ),
+            (
+                "forall N$ ( N$ >= 0 -> p(N$) )",
+                fol::InductionScheme::Step(2),
+                "p(0). p(1).",
+                "forall N$ ( N$ >= 0 and p(N$) -> p(N$ + 2) )",
+            ),
+            (
+                "forall N$ ( 0 <= N$ <= 1 -> p(N$) )",
+                fol::InductionScheme::Step(3),
+                "p(0). p(1).",
+                "forall N$ ( 0 <= N$ <= -2 and p(N$) -> p(N$ + 3) )",
+            ),
+            (
+                "forall N$ ( N$ <= 0 -> p(N$) )",
+                fol::InductionScheme::Step(2),
+                "p(0). p(-1).",
+                "forall N$ ( N$ <= 0 and p(N$) -> p(N$ - 2) )",
+            ),
+            (
+                "forall N$ ( N$ >= 2 -> p(N$) )",
+                fol::InductionScheme::Strong,
+                "",
+                "forall N$ ( N$ >= 2 and forall N1$ ( N1$ >= 2 and N1$ < N$ -> p(N1$) ) -> p(N$) )",
+            ),
+            (
+                "forall N$ ( 0 <= N$ <= 9 -> p(N$) )",
+                fol::InductionScheme::Strong,
+                "",
+                "forall N$ ( 0 <= N$ <= 9 and forall N1$ ( 0 <= N1$ <= 9 and N1$ < N$ -> p(N1$) ) -> p(N$) )",
+            ),
+            (
+                "forall N$ ( N$ <= 9 -> p(N$) )",
+                fol::InductionScheme::Strong,
+                "",
+                "forall N$ ( N$ <= 9 and forall N1$ ( N1$ <= 9 and N1$ > N$ -> p(N1$) ) -> p(N$) )",
+            ),
+        ] {
+            let formula: fol::Formula = src.parse().unwrap();
+            let (bases_result, step_result) = formula.inductive_lemma(scheme).unwrap().data;
+            let bases_result = fol::Theory {
+                formulas: bases_result,
+            };
+            let (bases_target, step_target): (fol::Theory, fol::Formula) =
+                (bases.parse().unwrap(), step.parse().unwrap());
+            assert_eq!(
+                (bases_result.clone(), step_result.clone()),
+                (bases_target.clone(), step_target.clone()),
+                "\n({bases_result},{step_result})\n != \n({bases_target},{step_target})"
+            )
+        }
+    }
+
+    #[test]
+    fn check_incorrect_induction_schemes() {
+        for (src, scheme, target) in [
+            (
+                "forall N$ ( 0 < N$ <= 9 -> p(N$) )",
+                fol::InductionScheme::Simple,
+                ProofOutlineError::MalformedInductiveAntecedent(
+                    "forall N$ ( 0 < N$ <= 9 -> p(N$) )".parse().unwrap(),
+                ),
+            ),
+            (
+                "forall N$ ( 0 <= N$ >= 9 -> p(N$) )",
+                fol::InductionScheme::Strong,
+                ProofOutlineError::MalformedInductiveAntecedent(
+                    "forall N$ ( 0 <= N$ >= 9 -> p(N$) )".parse().unwrap(),
+                ),
+            ),
+            (
+                "forall N$ ( N$ >= N$ * 2 -> p(N$) )",
+                fol::InductionScheme::Simple,
+                ProofOutlineError::MalformedInductiveBound(
+                    "forall N$ ( N$ >= N$ * 2 -> p(N$) )".parse().unwrap(),
+                ),
+            ),
+            (
+                "forall N$ ( N$ >= 0 -> p(N$) )",
+                fol::InductionScheme::Step(0),
+                ProofOutlineError::MalformedInductiveStep(
+                    "forall N$ ( N$ >= 0 -> p(N$) )".parse().unwrap(),
+                ),
+            ),
+        ] {
+            let formula: fol::Formula = src.parse().unwrap();
+            assert_eq!(formula.inductive_lemma(scheme), Err(target))
+        }
+    }
+
+    #[test]
+    fn test_case_lemma() {
+        for (src, cases, exhaustiveness, consequence) in [
+            (
+                "forall X$ (X$ < 0 or X$ = 0 or X$ > 0 -> p(X$))",
+                "forall X$ (X$ < 0 -> p(X$)). forall X$ (X$ = 0 -> p(X$)). forall X$ (X$ > 0 -> p(X$)).",
+                "forall X$ (X$ < 0 or X$ = 0 or X$ > 0)",
+                "forall X$ p(X$)",
+            ),
+            (
+                "forall X Y (q(X) or not q(X) -> p(X, Y))",
+                "forall X Y (q(X) -> p(X, Y)). forall X Y (not q(X) -> p(X, Y)).",
+                "forall X (q(X) or not q(X))",
+                "forall X Y p(X, Y)",
+            ),
+        ] {
+            let formula: fol::Formula = src.parse().unwrap();
+            let (cases_result, exhaustiveness_result, consequence_result) =
+                formula.case_lemma().unwrap().data;
+            assert_eq!(
+                fol::Theory {
+                    formulas: cases_result
+                },
+                cases.parse().unwrap()
+            );
+            assert_eq!(exhaustiveness_result, exhaustiveness.parse().unwrap());
+            assert_eq!(consequence_result, consequence.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn check_incorrect_case_lemma() {
+        for src in ["forall X (q(X) -> p(X))", "forall X (q(X) or p(X))"] {
+            let formula: fol::Formula = src.parse().unwrap();
+            assert_eq!(
+                formula.clone().case_lemma(),
+                Err(ProofOutlineError::MalformedCaseLemma(formula))
+            )
+        }
+    }
+
+    #[test]
+    fn test_defining_axioms() {
+        for (src, target) in [
+            (
+                "forall X ( p(X) <-> q(X) )",
+                "forall X ( p(X) <-> q(X) ).",
+            ),
+            (
+                "forall N$ S$ ( sum(N$, S$) <-> N$ = 0 and S$ = 0 or N$ > 0 and sum(N$ - 1, S$ - N$) )",
+                "forall S$ ( sum(0, S$) <-> S$ = 0 ).
+                forall N$ S$ ( N$ > 0 -> (sum(N$, S$) <-> sum(N$ - 1, S$ - N$)) ).
+                forall N$ S$ ( N$ < 0 -> not sum(N$, S$) ).",
+            ),
+            (
+                "forall X N$ ( path(X, N$) <-> N$ = n$i and q(X) or N$ > n$i and exists Y (q(Y) and path(Y, N$ - 2)) )",
+                "forall X ( path(X, n$i) <-> q(X) ).
+                forall N$ X ( N$ > n$i -> (path(X, N$) <-> exists Y (q(Y) and path(Y, N$ - 2))) ).
+                forall N$ X ( N$ < n$i -> not path(X, N$) ).",
+            ),
+        ] {
+            let formula: fol::Formula = src.parse().unwrap();
+            let predicate = formula.predicates().into_iter().next().unwrap();
+            let result = fol::Theory {
+                formulas: formula.defining_axioms(&predicate).unwrap().data,
+            };
+            let target: fol::Theory = target.parse().unwrap();
+            assert_eq!(result, target, "\n{result}\n != \n{target}")
+        }
+    }
+
+    #[test]
+    fn test_using() {
+        let specification: fol::Specification = "definition[d]: forall X (d(X) <-> p(X)). \
+            lemma[l1]: forall X (p(X) -> d(X)). \
+            lemma(backward)[l2]: forall X (d(X) -> p(X)). \
+            lemma[l3] using [d, l2]: forall X (d(X) <-> p(X)). \
+            lemma using []: #true."
+            .parse()
+            .unwrap();
+        let taken_predicates = IndexSet::from([fol::Predicate {
+            symbol: "p".to_string(),
+            arity: 1,
+        }]);
+
+        let outline = ProofOutline::from_specification(
+            specification.clone(),
+            taken_predicates.clone(),
+            &IndexMap::new(),
+        );
+        assert_eq!(
+            outline.err(),
+            Some(ProofOutlineError::UnknownDependency {
+                name: "l2".to_string(),
+                lemma: specification.formulas[3].formula.clone(),
+            })
+        );
+
+        let specification = fol::Specification {
+            formulas: specification
+                .formulas
+                .into_iter()
+                .filter(|f| f.direction != fol::Direction::Forward)
+                .map(|f| fol::AnnotatedFormula {
+                    direction: match f.direction {
+                        fol::Direction::Universal => fol::Direction::Backward,
+                        direction => direction,
+                    },
+                    ..f
+                })
+                .collect(),
+        };
+        let outline =
+            ProofOutline::from_specification(specification, taken_predicates, &IndexMap::new())
+                .unwrap()
+                .data;
+
+        let names = |lemma: &GeneralLemma| {
+            lemma
+                .axioms
+                .as_ref()
+                .map(|axioms| axioms.iter().map(|f| f.name.clone()).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            outline
+                .backward_lemmas
+                .iter()
+                .map(names)
+                .collect::<Vec<_>>(),
+            vec![
+                None,
+                None,
+                Some(vec!["d".to_string(), "l2".to_string()]),
+                Some(vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lemma_dependencies() {
+        let specification: fol::Specification = "definition: forall X (d(X) <-> p(X)). \
+            lemma: p(a) -> p(a). \
+            lemma[l]: q(a) -> q(a). \
+            lemma: d(b) -> d(b). \
+            lemma using [l]: p(c) -> p(c)."
+            .parse()
+            .unwrap();
+        let taken_predicates = IndexSet::from([
+            fol::Predicate {
+                symbol: "p".to_string(),
+                arity: 1,
+            },
+            fol::Predicate {
+                symbol: "q".to_string(),
+                arity: 1,
+            },
+        ]);
+
+        let outline =
+            ProofOutline::from_specification(specification, taken_predicates, &IndexMap::new())
+                .unwrap()
+                .data;
+
+        assert_eq!(
+            outline
+                .forward_lemmas
+                .into_iter()
+                .map(|lemma| lemma.dependencies)
+                .collect::<Vec<_>>(),
+            vec![vec![], vec![], vec![0], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_unrelated_lemma_axioms() {
+        // The second lemma only follows from the first one by way of the premise, so it must not rely on it
+        let specification: fol::Specification = "lemma: p(a). lemma: q(a). lemma: p(a) and q(a)."
+            .parse()
+            .unwrap();
+        let premise = problem::AnnotatedFormula {
+            name: "premise".into(),
+            role: problem::Role::Axiom,
+            formula: "forall X (p(X) -> q(X))".parse().unwrap(),
+            formula_type: problem::FormulaType::Tff,
+        };
+
+        let outline =
+            ProofOutline::from_specification(specification, IndexSet::new(), &IndexMap::new())
+                .unwrap()
+                .data;
+        let problems = outline_problems(
+            "",
+            fol::Direction::Forward,
+            &[premise],
+            vec![],
+            &outline.forward_lemmas,
+            |problem| vec![problem],
+        );
+
+        let lemma: fol::Formula = "p(a)".parse().unwrap();
+        let axioms = |i: usize| -> Vec<_> {
+            problems[i]
+                .axioms()
+                .into_iter()
+                .map(|axiom| axiom.formula)
+                .collect()
+        };
+
+        assert!(problems[1].dependencies.is_empty());
+        assert!(!axioms(1).contains(&lemma));
+        assert_eq!(
+            problems[2].dependencies,
+            vec!["outline_0_0".to_string(), "outline_1_0".to_string()]
+        );
+        assert!(axioms(2).contains(&lemma));
+    }
+
+    #[test]
+    fn check_incorrect_using() {
+        let specification: fol::Specification =
+            "definition[d] using []: forall X (d(X) <-> #true)."
+                .parse()
+                .unwrap();
+        assert_eq!(
+            ProofOutline::from_specification(
+                specification.clone(),
+                IndexSet::new(),
+                &IndexMap::new()
+            )
+            .err(),
+            Some(ProofOutlineError::InvalidUsingAnnotation(
+                specification.formulas[0].clone().into()
+            ))
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use {
     crate::{
         command_line::arguments::TaskDecomposition,
-        syntax_tree::fol::{Formula, FunctionConstant, Predicate, Sort, Theory},
+        syntax_tree::fol::{
+            AtomicFormula, Direction, Formula, FunctionConstant, Predicate, Sort, Theory,
+        },
     },
     anyhow::{Context as _, Result},
     indexmap::IndexSet,
@@ -103,6 +105,10 @@ pub struct Problem {
     pub name: String,
     pub interpretation: Interpretation,
     pub formulas: Vec<AnnotatedFormula>,
+    /// The names of the problems that have to be proven before this problem is attempted
+    pub dependencies: Vec<String>,
+    /// The direction of the task the problem belongs to
+    pub direction: Direction,
 }
 
 impl Problem {
@@ -111,9 +117,25 @@ impl Problem {
             name: name.into(),
             interpretation,
             formulas: vec![],
+            dependencies: vec![],
+            direction: Direction::Universal,
         }
     }
 
+    pub fn in_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn add_dependencies(mut self, dependencies: impl IntoIterator<Item = String>) -> Self {
+        for dependency in dependencies {
+            if !self.dependencies.contains(&dependency) {
+                self.dependencies.push(dependency);
+            }
+        }
+        self
+    }
+
     pub fn add_annotated_formulas(
         mut self,
         annotated_formulas: impl IntoIterator<Item = AnnotatedFormula>,
@@ -222,32 +244,91 @@ impl Problem {
                     name: format!("{}_{i}", self.name),
                     interpretation: self.interpretation.clone(),
                     formulas,
+                    dependencies: self.dependencies.clone(),
+                    direction: self.direction,
                 }
             })
             .collect_vec()
     }
 
+    /// Every conjecture becomes an axiom of the subsequent problems, which therefore depend on all earlier problems
     pub fn decompose_sequential(&self) -> Vec<Self> {
         let mut formulas = self.axioms();
-        self.conjectures()
-            .into_iter()
+        let conjectures = self.conjectures();
+        conjectures
+            .iter()
             .enumerate()
             .map(|(i, c)| {
                 if let Some(last) = formulas.last_mut() {
                     last.role = Role::Axiom;
                 }
 
-                formulas.push(c);
+                formulas.push(c.clone());
+
+                let dependencies = (0..i).map(|j| format!("{}_{j}", self.name));
 
                 Problem {
                     name: format!("{}_{i}", self.name),
                     interpretation: self.interpretation.clone(),
                     formulas: formulas.clone(),
+                    dependencies: self.dependencies.clone(),
+                    direction: self.direction,
                 }
+                .add_dependencies(dependencies)
             })
             .collect_vec()
     }
 
+    /// The problem of deriving a contradiction from the axioms, which is a theorem if and only if the axioms are contradictory
+    pub fn consistency_problem(&self) -> Self {
+        let formula_type = self
+            .formulas
+            .first()
+            .map_or(FormulaType::Tff, |f| f.formula_type.clone());
+
+        let mut formulas = self.axioms();
+        formulas.push(AnnotatedFormula {
+            name: "contradiction".to_string(),
+            role: Role::Conjecture,
+            formula: Formula::AtomicFormula(AtomicFormula::Falsity),
+            formula_type,
+        });
+
+        Problem {
+            name: format!("{}_consistency", self.name),
+            interpretation: self.interpretation.clone(),
+            formulas,
+            dependencies: vec![],
+            direction: self.direction,
+        }
+    }
+
+    /// The interpretation and the formulas of the problem as text, which ignores the names of the problem and its formulas
+    pub fn canonical_text(&self) -> String {
+        let mut text = format!("{:?}\n", self.interpretation);
+        for formula in &self.formulas {
+            text.push_str(&format!(
+                "{} {} {}\n",
+                formula.formula_type, formula.role, formula.formula
+            ));
+        }
+        text
+    }
+
+    /// A fingerprint of the canonical text of the problem
+    ///
+    /// Distinct problems may share a fingerprint, so it identifies a problem only together with its canonical text.
+    pub fn fingerprint(&self) -> String {
+        // A 64-bit FNV-1a hash, which (unlike the hashers of the standard library) is stable across releases
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in self.canonical_text().bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        format!("{hash:016x}")
+    }
+
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let path = path.as_ref();
         let mut file = File::create(path)
@@ -314,7 +395,7 @@ impl fmt::Display for Problem {
 mod tests {
     use {
         super::{AnnotatedFormula, Interpretation, Problem, Role},
-        crate::verifying::problem::FormulaType,
+        crate::{syntax_tree::fol::Direction, verifying::problem::FormulaType},
         std::vec,
     };
 
@@ -349,6 +430,8 @@ mod tests {
                     formula_type: FormulaType::Tff,
                 },
             ],
+            dependencies: vec![],
+            direction: Direction::Universal,
         };
 
         assert_eq!(
@@ -377,6 +460,8 @@ mod tests {
                             formula_type: FormulaType::Tff,
                         },
                     ],
+                    dependencies: vec![],
+                    direction: Direction::Universal,
                 },
                 Problem {
                     name: "problem_1".into(),
@@ -401,6 +486,8 @@ mod tests {
                             formula_type: FormulaType::Tff,
                         },
                     ],
+                    dependencies: vec![],
+                    direction: Direction::Universal,
                 }
             ]
         );
@@ -431,6 +518,8 @@ mod tests {
                             formula_type: FormulaType::Tff,
                         },
                     ],
+                    dependencies: vec![],
+                    direction: Direction::Universal,
                 },
                 Problem {
                     name: "problem_1".into(),
@@ -461,8 +550,125 @@ mod tests {
                             formula_type: FormulaType::Tff,
                         },
                     ],
+                    dependencies: vec!["problem_0".into()],
+                    direction: Direction::Universal,
                 }
             ]
         );
     }
+
+    #[test]
+    fn test_sequential_dependencies() {
+        let conjecture = |name: &str, formula: &str| AnnotatedFormula {
+            name: name.into(),
+            role: Role::Conjecture,
+            formula: formula.parse().unwrap(),
+            formula_type: FormulaType::Tff,
+        };
+
+        let problem = Problem::with_name("problem", Interpretation::Standard)
+            .add_annotated_formulas([
+                conjecture("conjecture_0", "p(a)"),
+                conjecture("conjecture_1", "q(a)"),
+                conjecture("conjecture_2", "p(b) or q(b)"),
+            ])
+            .add_dependencies(["lemma".to_string()]);
+
+        assert_eq!(
+            problem
+                .decompose_sequential()
+                .into_iter()
+                .map(|p| (p.name, p.dependencies))
+                .collect::<Vec<_>>(),
+            vec![
+                ("problem_0".to_string(), vec!["lemma".to_string()]),
+                (
+                    "problem_1".to_string(),
+                    vec!["lemma".to_string(), "problem_0".to_string()]
+                ),
+                (
+                    "problem_2".to_string(),
+                    vec![
+                        "lemma".to_string(),
+                        "problem_0".to_string(),
+                        "problem_1".to_string()
+                    ]
+                ),
+            ]
+        );
+        assert!(problem
+            .decompose_independent()
+            .into_iter()
+            .all(|p| p.dependencies == vec!["lemma".to_string()]));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let formula = |name: &str, role: Role, formula: &str| AnnotatedFormula {
+            name: name.into(),
+            role,
+            formula: formula.parse().unwrap(),
+            formula_type: FormulaType::Tff,
+        };
+
+        let problem = Problem::with_name("problem", Interpretation::Standard)
+            .add_annotated_formulas([
+                formula("axiom", Role::Axiom, "p(a)"),
+                formula("conjecture", Role::Conjecture, "q(a)"),
+            ]);
+        let renamed = Problem::with_name("renamed", Interpretation::Standard)
+            .add_annotated_formulas([
+                formula("premise", Role::Axiom, "p(a)"),
+                formula("goal", Role::Conjecture, "q(a)"),
+            ]);
+        let changed = Problem::with_name("problem", Interpretation::Standard)
+            .add_annotated_formulas([
+                formula("axiom", Role::Axiom, "p(b)"),
+                formula("conjecture", Role::Conjecture, "q(a)"),
+            ]);
+        let swapped = Problem::with_name("problem", Interpretation::Standard)
+            .add_annotated_formulas([
+                formula("axiom", Role::Conjecture, "p(a)"),
+                formula("conjecture", Role::Axiom, "q(a)"),
+            ]);
+
+        assert_eq!(problem.fingerprint(), renamed.fingerprint());
+        assert_eq!(problem.canonical_text(), renamed.canonical_text());
+        assert_ne!(problem.canonical_text(), changed.canonical_text());
+        assert_ne!(problem.fingerprint(), changed.fingerprint());
+        assert_ne!(problem.fingerprint(), swapped.fingerprint());
+    }
+
+    #[test]
+    fn test_consistency_problem() {
+        let problem = Problem::with_name("problem", Interpretation::Standard)
+            .add_annotated_formulas([
+                AnnotatedFormula {
+                    name: "axiom_0".into(),
+                    role: Role::Axiom,
+                    formula: "p(a)".parse().unwrap(),
+                    formula_type: FormulaType::Tff,
+                },
+                AnnotatedFormula {
+                    name: "conjecture_0".into(),
+                    role: Role::Conjecture,
+                    formula: "q(a)".parse().unwrap(),
+                    formula_type: FormulaType::Tff,
+                },
+            ])
+            .consistency_problem();
+
+        assert_eq!(problem.name, "problem_consistency");
+        assert_eq!(
+            problem
+                .formulas
+                .iter()
+                .map(|f| (f.role.clone(), f.formula.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Role::Axiom, "p(a)".to_string()),
+                (Role::Conjecture, "#false".to_string())
+            ]
+        );
+    }
 }
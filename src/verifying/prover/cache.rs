@@ -0,0 +1,151 @@
+use {
+    crate::verifying::{
+        problem::Problem,
+        prover::{Status, Success},
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::BTreeMap,
+        fs,
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("unable to read the cache file `{}`", .0.display())]
+    UnableToRead(PathBuf, #[source] std::io::Error),
+    #[error("the cache file `{}` is malformed", .0.display())]
+    Malformed(PathBuf, #[source] serde_json::Error),
+    #[error("unable to write the cache file `{}`", .0.display())]
+    UnableToWrite(PathBuf, #[source] std::io::Error),
+}
+
+/// The outcome of a previous proof search for a problem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub status: String,
+    // Entries written without the canonical text of their problem never match
+    #[serde(default)]
+    pub problem: String,
+}
+
+/// The outcomes of previous proof searches, identified by the fingerprints of the problems
+///
+/// The cache is stored as a JSON object mapping fingerprints to entries.
+/// A problem is considered proven if an earlier proof search for a problem with the same fingerprint ended with a theorem,
+/// and the entry records the same canonical text as the problem (fingerprints may collide).
+#[derive(Debug, Clone)]
+pub struct Cache {
+    pub path: PathBuf,
+    pub entries: BTreeMap<String, Entry>,
+}
+
+impl Cache {
+    /// Read the cache stored in the given file, a missing file yields an empty cache
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CacheError> {
+        let path = path.as_ref().to_path_buf();
+
+        let entries = if path.is_file() {
+            let content = fs::read_to_string(&path)
+                .map_err(|error| CacheError::UnableToRead(path.clone(), error))?;
+            serde_json::from_str(&content)
+                .map_err(|error| CacheError::Malformed(path.clone(), error))?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Cache { path, entries })
+    }
+
+    pub fn proven(&self, problem: &Problem) -> bool {
+        self.entries
+            .get(&problem.fingerprint())
+            .is_some_and(|entry| {
+                entry.status == Status::Success(Success::Theorem).to_string()
+                    && entry.problem == problem.canonical_text()
+            })
+    }
+
+    pub fn insert(&mut self, problem: &Problem, status: Status) {
+        self.entries.insert(
+            problem.fingerprint(),
+            Entry {
+                name: problem.name.clone(),
+                status: status.to_string(),
+                problem: problem.canonical_text(),
+            },
+        );
+    }
+
+    pub fn save(&self) -> Result<(), CacheError> {
+        let content = serde_json::to_string_pretty(&self.entries)
+            .map_err(|error| CacheError::Malformed(self.path.clone(), error))?;
+
+        if let Some(directory) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(directory)
+                .map_err(|error| CacheError::UnableToWrite(self.path.clone(), error))?;
+        }
+
+        fs::write(&self.path, content + "\n")
+            .map_err(|error| CacheError::UnableToWrite(self.path.clone(), error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::Cache,
+        crate::verifying::{
+            problem::{AnnotatedFormula, FormulaType, Interpretation, Problem, Role},
+            prover::{Failure, Status, Success},
+        },
+        std::fs,
+    };
+
+    #[test]
+    fn store_and_load_outcomes() {
+        let path = std::env::temp_dir()
+            .join(format!(
+                "anthem-cache-{}-store_and_load_outcomes",
+                std::process::id()
+            ))
+            .join("cache.json");
+
+        let problem = |name: &str, conjecture: &str| {
+            Problem::with_name(name, Interpretation::Standard).add_annotated_formulas([
+                AnnotatedFormula {
+                    name: "conjecture".into(),
+                    role: Role::Conjecture,
+                    formula: conjecture.parse().unwrap(),
+                    formula_type: FormulaType::Tff,
+                },
+            ])
+        };
+
+        let mut cache = Cache::load(&path).unwrap();
+        assert!(cache.entries.is_empty());
+
+        cache.insert(&problem("proven", "p"), Status::Success(Success::Theorem));
+        cache.insert(&problem("failed", "q"), Status::Failure(Failure::TimeOut));
+        cache.save().unwrap();
+
+        let cache = Cache::load(&path).unwrap();
+        assert!(cache.proven(&problem("proven", "p")));
+        assert!(cache.proven(&problem("renamed", "p")));
+        assert!(!cache.proven(&problem("failed", "q")));
+        assert!(!cache.proven(&problem("unknown", "r")));
+
+        // An entry under the fingerprint of a problem, but recording another problem, does not count
+        let mut colliding = cache.clone();
+        let fingerprint = problem("unknown", "r").fingerprint();
+        let mut entry = colliding.entries[&problem("proven", "p").fingerprint()].clone();
+        entry.name = "collision".into();
+        colliding.entries.insert(fingerprint, entry);
+        assert!(!colliding.proven(&problem("unknown", "r")));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
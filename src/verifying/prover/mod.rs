@@ -6,11 +6,14 @@ use {
         fmt::{Debug, Display},
         str::FromStr,
         sync::mpsc::channel,
+        time::Instant,
     },
     thiserror::Error,
     threadpool::ThreadPool,
 };
 
+pub mod cache;
+pub mod replay;
 pub mod vampire;
 
 lazy_static! {
@@ -89,13 +92,23 @@ impl FromStr for Status {
 }
 
 pub trait Report: Display + Debug + Clone {
+    fn problem(&self) -> &Problem;
+
     fn status(&self) -> Result<Status, StatusExtractionError>;
+
+    fn stdout(&self) -> &str;
+
+    fn stderr(&self) -> &str;
+
+    fn start_time(&self) -> Option<Instant>;
 }
 
 pub trait Prover: Debug + Clone + Send + 'static {
     type Report: Report + Send;
     type Error: Send;
 
+    fn name(&self) -> &'static str;
+
     fn instances(&self) -> usize;
 
     fn cores(&self) -> usize;
@@ -0,0 +1,264 @@
+use {
+    crate::verifying::{
+        problem::Problem,
+        prover::{Prover, Report, Status, StatusExtractionError},
+    },
+    std::{
+        fmt::{self, Display},
+        fs,
+        path::{Path, PathBuf},
+        time::Instant,
+    },
+    thiserror::Error,
+};
+
+/// The path of the file recording the outcome of the problem with the given name
+fn record_path(directory: &Path, name: &str) -> PathBuf {
+    directory.join(format!("{name}.szs"))
+}
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("no outcome was recorded for problem `{0}`")]
+    MissingRecord(String),
+    #[error("unable to read the recorded outcome for problem `{0}`")]
+    UnableToRead(String, #[source] std::io::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    pub problem: Problem,
+    pub record: String,
+    pub start_time: Option<Instant>,
+}
+
+impl Report for ReplayReport {
+    fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
+    fn status(&self) -> Result<Status, StatusExtractionError> {
+        self.record.parse()
+    }
+
+    fn stdout(&self) -> &str {
+        &self.record
+    }
+
+    fn stderr(&self) -> &str {
+        ""
+    }
+
+    fn start_time(&self) -> Option<Instant> {
+        self.start_time
+    }
+}
+
+impl Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- {} (replayed) ---", self.problem.name)?;
+        match self.status() {
+            Ok(status) => writeln!(f, "status: {status}"),
+            Err(error) => writeln!(f, "error: {error}"),
+        }
+    }
+}
+
+/// A prover that answers every problem with a previously recorded outcome
+///
+/// The outcome of a problem named `name` is read from the file `name.szs` in the given directory.
+/// Such a file must contain a line of the form `% SZS status <status> for <name>`.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub directory: PathBuf,
+    pub time_execution: bool,
+}
+
+impl Prover for Replay {
+    type Error = ReplayError;
+    type Report = ReplayReport;
+
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn instances(&self) -> usize {
+        1
+    }
+
+    fn cores(&self) -> usize {
+        1
+    }
+
+    fn prove(&self, problem: Problem) -> Result<Self::Report, Self::Error> {
+        let start_time = if self.time_execution {
+            Some(Instant::now())
+        } else {
+            None
+        };
+
+        let path = record_path(&self.directory, &problem.name);
+        if !path.is_file() {
+            return Err(ReplayError::MissingRecord(problem.name));
+        }
+
+        let record = fs::read_to_string(path)
+            .map_err(|error| ReplayError::UnableToRead(problem.name.clone(), error))?;
+
+        Ok(ReplayReport {
+            problem,
+            record,
+            start_time,
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RecordError<E: std::error::Error + 'static> {
+    #[error(transparent)]
+    Prover(E),
+    #[error("unable to record the outcome for problem `{0}`")]
+    UnableToWrite(String, #[source] std::io::Error),
+}
+
+/// A prover that forwards every problem to another prover and records the outcome for later replay
+#[derive(Debug, Clone)]
+pub struct Record<P> {
+    pub prover: P,
+    pub directory: PathBuf,
+}
+
+impl<P> Prover for Record<P>
+where
+    P: Prover,
+    P::Error: std::error::Error + 'static,
+{
+    type Error = RecordError<P::Error>;
+    type Report = P::Report;
+
+    fn name(&self) -> &'static str {
+        self.prover.name()
+    }
+
+    fn instances(&self) -> usize {
+        self.prover.instances()
+    }
+
+    fn cores(&self) -> usize {
+        self.prover.cores()
+    }
+
+    fn prove(&self, problem: Problem) -> Result<Self::Report, Self::Error> {
+        let name = problem.name.clone();
+        let report = self.prover.prove(problem).map_err(RecordError::Prover)?;
+
+        // Record a single status line whenever possible to keep the records small and stable
+        let record = match report.status() {
+            Ok(status) => format!("% SZS status {status} for {name}\n"),
+            Err(_) => report.stdout().to_string(),
+        };
+
+        fs::create_dir_all(&self.directory)
+            .and_then(|_| fs::write(record_path(&self.directory, &name), record))
+            .map_err(|error| RecordError::UnableToWrite(name, error))?;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Record, Replay, ReplayError},
+        crate::verifying::{
+            problem::{Interpretation, Problem},
+            prover::{Failure, Prover, Report as _, Status, Success},
+        },
+        std::fs,
+    };
+
+    #[test]
+    fn replay_recorded_outcomes() {
+        let directory = std::env::temp_dir().join(format!(
+            "anthem-replay-{}-replay_recorded_outcomes",
+            std::process::id()
+        ));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("forward.szs"),
+            "% SZS status Theorem for forward\n",
+        )
+        .unwrap();
+        fs::write(
+            directory.join("backward.szs"),
+            "% SZS status Timeout for backward\n",
+        )
+        .unwrap();
+
+        let prover = Replay {
+            directory: directory.clone(),
+            time_execution: false,
+        };
+
+        let report = prover
+            .prove(Problem::with_name("forward", Interpretation::Standard))
+            .unwrap();
+        assert_eq!(report.status().unwrap(), Status::Success(Success::Theorem));
+
+        let report = prover
+            .prove(Problem::with_name("backward", Interpretation::Standard))
+            .unwrap();
+        assert_eq!(report.status().unwrap(), Status::Failure(Failure::TimeOut));
+
+        assert!(matches!(
+            prover.prove(Problem::with_name("missing", Interpretation::Standard)),
+            Err(ReplayError::MissingRecord(name)) if name == "missing"
+        ));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let source = std::env::temp_dir().join(format!(
+            "anthem-replay-{}-record_and_replay-source",
+            std::process::id()
+        ));
+        let target = std::env::temp_dir().join(format!(
+            "anthem-replay-{}-record_and_replay-target",
+            std::process::id()
+        ));
+        fs::create_dir_all(&source).unwrap();
+        fs::write(
+            source.join("problem.szs"),
+            "% SZS status CounterSatisfiable for problem\n",
+        )
+        .unwrap();
+
+        let prover = Record {
+            prover: Replay {
+                directory: source.clone(),
+                time_execution: false,
+            },
+            directory: target.clone(),
+        };
+        prover
+            .prove(Problem::with_name("problem", Interpretation::Standard))
+            .unwrap();
+
+        let replay = Replay {
+            directory: target.clone(),
+            time_execution: false,
+        };
+        let report = replay
+            .prove(Problem::with_name("problem", Interpretation::Standard))
+            .unwrap();
+        assert_eq!(
+            report.status().unwrap(),
+            Status::Success(Success::CounterSatisfiable)
+        );
+
+        fs::remove_dir_all(source).unwrap();
+        fs::remove_dir_all(target).unwrap();
+    }
+}
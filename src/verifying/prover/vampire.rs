@@ -50,9 +50,25 @@ pub struct VampireReport {
 }
 
 impl Report for VampireReport {
+    fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
     fn status(&self) -> Result<Status, StatusExtractionError> {
         self.output.stdout.parse()
     }
+
+    fn stdout(&self) -> &str {
+        &self.output.stdout
+    }
+
+    fn stderr(&self) -> &str {
+        &self.output.stderr
+    }
+
+    fn start_time(&self) -> Option<Instant> {
+        self.start_time
+    }
 }
 
 impl Display for VampireReport {
@@ -97,6 +113,10 @@ impl Prover for Vampire {
     type Error = VampireError;
     type Report = VampireReport;
 
+    fn name(&self) -> &'static str {
+        "vampire"
+    }
+
     fn instances(&self) -> usize {
         if self.instances == 0 {
             std::cmp::max(num_cpus::get() / self.cores(), 1)
@@ -0,0 +1,559 @@
+use {
+    crate::{
+        syntax_tree::fol,
+        verifying::{
+            outline::{GeneralLemma, ProofOutline, ProofOutlineError},
+            problem::{self, Interpretation, Problem},
+        },
+    },
+    indexmap::{IndexMap, IndexSet},
+    std::{path::PathBuf, str::FromStr},
+    thiserror::Error,
+};
+
+/// The commands available within a proof session
+pub const HELP: &str = "\
+Commands:
+  add <lemma or definition>  prove a step and append it to the proof outline, e.g. `add lemma[l]: forall X p(X).`
+  try <formula>              prove a conjecture from the current axioms without adding it
+  remove <name>              remove the named step from the proof outline
+  steps                      list the steps of the proof outline
+  axioms                     list the current axioms
+  time-limit <seconds>       set the time limit for each problem passed to the prover
+  qed                        prove the unchecked steps and then the conclusions of the task
+  save <file>                write the steps of the proof outline to the given file
+  help                       show this message
+  quit                       end the session";
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("unknown command `{0}`, enter `help` for a list of commands")]
+    UnknownInstruction(String),
+    #[error("the command `{0}` expects {1}")]
+    MissingArgument(String, &'static str),
+    #[error("unable to parse `{0}`:\n{1}")]
+    Unparsable(String, String),
+    #[error("`{0}` is not a valid number of seconds")]
+    InvalidTimeLimit(String),
+    #[error("the proof outline contains no step named `{0}`")]
+    UnknownStep(String),
+    #[error("the following step does not apply to the {0} direction of the session: {1}")]
+    InapplicableDirection(fol::Direction, Box<fol::AnnotatedFormula>),
+    #[error("the session has no conclusions to prove")]
+    NoConclusions,
+    #[error(transparent)]
+    ProofOutline(#[from] ProofOutlineError),
+}
+
+/// A command entered into a proof session
+#[derive(Clone, Debug, PartialEq)]
+pub enum Instruction {
+    Add(fol::AnnotatedFormula),
+    Try(fol::Formula),
+    Remove(String),
+    Steps,
+    Axioms,
+    TimeLimit(usize),
+    Qed,
+    Save(PathBuf),
+    Help,
+    Quit,
+}
+
+impl FromStr for Instruction {
+    type Err = SessionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (keyword, argument) = match s.split_once(char::is_whitespace) {
+            Some((keyword, argument)) => (keyword, argument.trim()),
+            None => (s, ""),
+        };
+
+        let expect = |description| {
+            if argument.is_empty() {
+                Err(SessionError::MissingArgument(
+                    keyword.to_string(),
+                    description,
+                ))
+            } else {
+                Ok(argument)
+            }
+        };
+
+        // Formulas may be terminated by a period, as in proof outlines
+        let formula = |description| {
+            expect(description).map(|argument| argument.strip_suffix('.').unwrap_or(argument))
+        };
+
+        match keyword {
+            "add" => {
+                let argument = formula("a lemma or definition")?;
+                argument.parse().map(Instruction::Add).map_err(|error| {
+                    SessionError::Unparsable(argument.to_string(), error.to_string())
+                })
+            }
+            "try" => {
+                let argument = formula("a formula")?;
+                argument.parse().map(Instruction::Try).map_err(|error| {
+                    SessionError::Unparsable(argument.to_string(), error.to_string())
+                })
+            }
+            "remove" => Ok(Instruction::Remove(
+                expect("the name of a step")?.to_string(),
+            )),
+            "steps" => Ok(Instruction::Steps),
+            "axioms" => Ok(Instruction::Axioms),
+            "time-limit" => {
+                let argument = expect("a number of seconds")?;
+                argument
+                    .parse()
+                    .map(Instruction::TimeLimit)
+                    .map_err(|_| SessionError::InvalidTimeLimit(argument.to_string()))
+            }
+            "qed" => Ok(Instruction::Qed),
+            "save" => Ok(Instruction::Save(expect("a file")?.into())),
+            "help" => Ok(Instruction::Help),
+            "quit" | "exit" => Ok(Instruction::Quit),
+            _ => Err(SessionError::UnknownInstruction(keyword.to_string())),
+        }
+    }
+}
+
+/// A step of the proof outline developed within a proof session
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub formula: fol::AnnotatedFormula,
+    // Steps loaded from an existing proof outline, or following a removed step, remain unchecked until `qed`
+    pub proven: bool,
+}
+
+/// A proof session develops a proof outline step by step for one direction of a task
+///
+/// Every step added to the session has to be proven from the premises of the task and the preceding steps.
+/// The conclusions of the task can be proven from the resulting axioms at any time.
+pub struct Session {
+    pub premises: Vec<problem::AnnotatedFormula>,
+    pub conclusions: Vec<problem::AnnotatedFormula>,
+    pub direction: fol::Direction,
+    pub taken_predicates: IndexSet<fol::Predicate>,
+    pub placeholders: IndexMap<String, fol::FunctionConstant>,
+    pub steps: Vec<Step>,
+    pub attempts: usize,
+}
+
+impl Session {
+    pub fn new(
+        premises: Vec<problem::AnnotatedFormula>,
+        conclusions: Vec<problem::AnnotatedFormula>,
+        direction: fol::Direction,
+        taken_predicates: IndexSet<fol::Predicate>,
+        placeholders: IndexMap<String, fol::FunctionConstant>,
+    ) -> Self {
+        Session {
+            premises,
+            conclusions,
+            direction,
+            taken_predicates,
+            placeholders,
+            steps: vec![],
+            attempts: 0,
+        }
+    }
+
+    /// Accept the steps of an existing proof outline without proving them
+    pub fn load(&mut self, specification: fol::Specification) -> Result<(), SessionError> {
+        let count = self.steps.len();
+        self.steps
+            .extend(specification.formulas.into_iter().map(|formula| Step {
+                formula,
+                proven: false,
+            }));
+
+        if let Err(error) = self.outline(self.steps.len(), None) {
+            self.steps.truncate(count);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    fn applies(&self, direction: fol::Direction) -> bool {
+        match self.direction {
+            fol::Direction::Universal | fol::Direction::Forward => {
+                direction != fol::Direction::Backward
+            }
+            fol::Direction::Backward => direction != fol::Direction::Forward,
+        }
+    }
+
+    // The lemmas and definitions of the first `count` steps (and the given additional step) in the direction of the session
+    fn outline(
+        &self,
+        count: usize,
+        step: Option<&fol::AnnotatedFormula>,
+    ) -> Result<(Vec<GeneralLemma>, Vec<fol::AnnotatedFormula>), SessionError> {
+        let specification = self.steps[..count]
+            .iter()
+            .map(|step| step.formula.clone())
+            .chain(step.cloned())
+            .collect();
+
+        let outline = ProofOutline::from_specification(
+            specification,
+            self.taken_predicates.clone(),
+            &self.placeholders,
+        )?
+        .data;
+
+        Ok(match self.direction {
+            fol::Direction::Universal | fol::Direction::Forward => {
+                (outline.forward_lemmas, outline.forward_definitions)
+            }
+            fol::Direction::Backward => (outline.backward_lemmas, outline.backward_definitions),
+        })
+    }
+
+    fn axioms_from(
+        &self,
+        lemmas: &[GeneralLemma],
+        definitions: Vec<fol::AnnotatedFormula>,
+    ) -> Vec<problem::AnnotatedFormula> {
+        let mut axioms = self.premises.clone();
+        axioms.extend(
+            definitions
+                .into_iter()
+                .map(|f| f.into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff)),
+        );
+        axioms.extend(
+            lemmas
+                .iter()
+                .flat_map(|lemma| lemma.consequences.iter().cloned()),
+        );
+        axioms
+    }
+
+    /// The premises of the task together with the definitions and lemmas of the proof outline
+    pub fn axioms(&self) -> Result<Vec<problem::AnnotatedFormula>, SessionError> {
+        let (lemmas, definitions) = self.outline(self.steps.len(), None)?;
+        Ok(self.axioms_from(&lemmas, definitions))
+    }
+
+    /// The problems to prove before the given step is appended to the proof outline, definitions need no proof
+    pub fn problems(&self, step: &fol::AnnotatedFormula) -> Result<Vec<Problem>, SessionError> {
+        if !self.applies(step.direction) {
+            return Err(SessionError::InapplicableDirection(
+                self.direction,
+                step.clone().into(),
+            ));
+        }
+
+        self.problems_at(self.steps.len(), step)
+    }
+
+    // The problems of proving the given step from the premises and the first `index` steps
+    fn problems_at(
+        &self,
+        index: usize,
+        step: &fol::AnnotatedFormula,
+    ) -> Result<Vec<Problem>, SessionError> {
+        let (mut lemmas, definitions) = self.outline(index, Some(step))?;
+        if step.role == fol::Role::Definition {
+            return Ok(vec![]);
+        }
+
+        let lemma = lemmas
+            .pop()
+            .expect("a lemma in the direction of the session yields a general lemma");
+        let definitions: Vec<_> = definitions
+            .into_iter()
+            .map(|f| f.into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff))
+            .collect();
+        let axioms = lemma.proof_axioms(&self.premises, &definitions, &lemmas);
+
+        Ok(lemma
+            .conjectures
+            .into_iter()
+            .enumerate()
+            .map(|(j, conjecture)| {
+                Problem::with_name(format!("step_{index}_{j}"), Interpretation::Standard)
+                    .add_annotated_formulas(axioms.clone())
+                    .add_annotated_formulas(std::iter::once(conjecture))
+                    .rename_conflicting_symbols()
+            })
+            .collect())
+    }
+
+    /// Append a step to the proof outline
+    pub fn accept(&mut self, formula: fol::AnnotatedFormula) {
+        self.steps.push(Step {
+            formula,
+            proven: true,
+        })
+    }
+
+    /// The indices of the steps that have not been proven yet
+    pub fn unchecked(&self) -> Vec<usize> {
+        self.steps
+            .iter()
+            .enumerate()
+            .filter_map(|(i, step)| (!step.proven).then_some(i))
+            .collect()
+    }
+
+    /// The problems to prove before the step at the given index counts as proven
+    ///
+    /// Steps that do not apply to the direction of the session contribute no axioms and need no proof.
+    pub fn check(&self, index: usize) -> Result<Vec<Problem>, SessionError> {
+        let step = &self.steps[index].formula;
+        if !self.applies(step.direction) {
+            return Ok(vec![]);
+        }
+        self.problems_at(index, step)
+    }
+
+    /// Mark the step at the given index as proven
+    pub fn confirm(&mut self, index: usize) {
+        self.steps[index].proven = true;
+    }
+
+    /// Remove the named step from the proof outline, unless later steps refer to it
+    ///
+    /// Later steps may have been proven with the help of the removed step, so they become unchecked.
+    pub fn remove(&mut self, name: &str) -> Result<Step, SessionError> {
+        let index = self
+            .steps
+            .iter()
+            .position(|step| step.formula.name == name)
+            .ok_or_else(|| SessionError::UnknownStep(name.to_string()))?;
+
+        let step = self.steps.remove(index);
+        if let Err(error) = self.outline(self.steps.len(), None) {
+            self.steps.insert(index, step);
+            return Err(error);
+        }
+
+        for step in &mut self.steps[index..] {
+            step.proven = false;
+        }
+
+        Ok(step)
+    }
+
+    /// The problem of proving the given conjecture from the current axioms
+    pub fn conjecture(&mut self, formula: fol::Formula) -> Result<Problem, SessionError> {
+        let name = format!("conjecture_{}", self.attempts);
+        self.attempts += 1;
+
+        let conjecture = fol::AnnotatedFormula {
+            role: fol::Role::Lemma,
+            direction: self.direction,
+            name: name.clone(),
+            using: None,
+            formula,
+        }
+        .replace_placeholders(&self.placeholders)
+        .universal_closure_with_quantifier_joining()
+        .into_problem_formula(problem::Role::Conjecture, problem::FormulaType::Tff);
+
+        Ok(Problem::with_name(name, Interpretation::Standard)
+            .add_annotated_formulas(self.axioms()?)
+            .add_annotated_formulas(std::iter::once(conjecture))
+            .rename_conflicting_symbols())
+    }
+
+    /// The problems of proving each conclusion of the task from the current axioms
+    pub fn conclusions(&self) -> Result<Vec<Problem>, SessionError> {
+        if self.conclusions.is_empty() {
+            return Err(SessionError::NoConclusions);
+        }
+
+        let name = match self.direction {
+            fol::Direction::Backward => "backward_problem",
+            fol::Direction::Universal | fol::Direction::Forward => "forward_problem",
+        };
+
+        Ok(Problem::with_name(name, Interpretation::Standard)
+            .add_annotated_formulas(self.axioms()?)
+            .add_annotated_formulas(self.conclusions.clone())
+            .rename_conflicting_symbols()
+            .decompose_independent())
+    }
+
+    /// The proof outline consisting of the steps of the session
+    pub fn specification(&self) -> fol::Specification {
+        self.steps.iter().map(|step| step.formula.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Instruction, Session, SessionError},
+        crate::{syntax_tree::fol, verifying::problem},
+        indexmap::{IndexMap, IndexSet},
+    };
+
+    #[test]
+    fn parse_instructions() {
+        for (src, target) in [
+            (
+                "add lemma[l]: forall X p(X).",
+                Instruction::Add("lemma[l]: forall X p(X)".parse().unwrap()),
+            ),
+            ("try p(a)", Instruction::Try("p(a)".parse().unwrap())),
+            ("  remove l ", Instruction::Remove("l".to_string())),
+            ("time-limit 10", Instruction::TimeLimit(10)),
+            ("save outline.po", Instruction::Save("outline.po".into())),
+            ("steps", Instruction::Steps),
+            ("exit", Instruction::Quit),
+        ] {
+            assert_eq!(src.parse::<Instruction>().unwrap(), target)
+        }
+
+        for src in ["add", "time-limit ten", "try p(", "prove p(a)"] {
+            assert!(src.parse::<Instruction>().is_err(), "{src}")
+        }
+    }
+
+    fn session() -> Session {
+        let premise = |formula: &str| problem::AnnotatedFormula {
+            name: "premise".to_string(),
+            role: problem::Role::Axiom,
+            formula: formula.parse().unwrap(),
+            formula_type: problem::FormulaType::Tff,
+        };
+
+        Session::new(
+            vec![premise("forall X (q(X) -> p(X))")],
+            vec![problem::AnnotatedFormula {
+                name: "conclusion".to_string(),
+                role: problem::Role::Conjecture,
+                formula: "forall X (q(X) -> r(X))".parse().unwrap(),
+                formula_type: problem::FormulaType::Tff,
+            }],
+            fol::Direction::Forward,
+            IndexSet::from_iter(["p", "q", "r"].map(|symbol| fol::Predicate {
+                symbol: symbol.to_string(),
+                arity: 1,
+            })),
+            IndexMap::new(),
+        )
+    }
+
+    #[test]
+    fn develop_outline() {
+        let mut session = session();
+
+        let definition: fol::AnnotatedFormula = "definition[d]: forall X (d(X) <-> p(X) and q(X))"
+            .parse()
+            .unwrap();
+        assert!(session.problems(&definition).unwrap().is_empty());
+        session.accept(definition);
+
+        let lemma: fol::AnnotatedFormula = "lemma[l] using [d]: forall X (q(X) -> d(X))"
+            .parse()
+            .unwrap();
+        let problems = session.problems(&lemma).unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["step_1_0"]
+        );
+        assert_eq!(problems[0].axioms().len(), 2);
+        session.accept(lemma);
+
+        assert_eq!(
+            session
+                .axioms()
+                .unwrap()
+                .into_iter()
+                .map(|axiom| axiom.name)
+                .collect::<Vec<_>>(),
+            vec!["premise", "d", "l"]
+        );
+
+        let conjecture = session.conjecture("p(a)".parse().unwrap()).unwrap();
+        assert_eq!(conjecture.name, "conjecture_0");
+        assert_eq!(conjecture.axioms().len(), 3);
+
+        assert_eq!(session.conclusions().unwrap().len(), 1);
+
+        let backward: fol::AnnotatedFormula = "lemma(backward): p(a)".parse().unwrap();
+        assert!(matches!(
+            session.problems(&backward),
+            Err(SessionError::InapplicableDirection(..))
+        ));
+
+        assert!(matches!(
+            session.remove("d"),
+            Err(SessionError::ProofOutline(_))
+        ));
+        assert_eq!(
+            session.remove("l").unwrap().formula,
+            "lemma[l] using [d]: forall X (q(X) -> d(X))"
+                .parse()
+                .unwrap()
+        );
+        assert!(matches!(
+            session.remove("l"),
+            Err(SessionError::UnknownStep(_))
+        ));
+        assert_eq!(
+            session.specification().to_string(),
+            "definition[d]: forall X (d(X) <-> p(X) and q(X)).\n"
+        );
+    }
+
+    #[test]
+    fn load_outline() {
+        let mut session = session();
+        assert!(matches!(
+            session.load(
+                "definition[d]: forall X (d(X) <-> p(X)). definition: forall X (p(X) <-> q(X))."
+                    .parse()
+                    .unwrap()
+            ),
+            Err(SessionError::ProofOutline(_))
+        ));
+        assert!(session.steps.is_empty());
+
+        session
+            .load(
+                "definition[d]: forall X (d(X) <-> p(X)). lemma: forall X (d(X) -> p(X))."
+                    .parse()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(session.steps.len(), 2);
+        assert_eq!(session.unchecked(), vec![0, 1]);
+
+        assert!(session.check(0).unwrap().is_empty());
+        session.confirm(0);
+
+        let problems = session.check(1).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].name, "step_1_0");
+        assert_eq!(problems[0].axioms().len(), 2);
+        session.confirm(1);
+        assert!(session.unchecked().is_empty());
+    }
+
+    #[test]
+    fn remove_invalidates_later_steps() {
+        let mut session = session();
+        for step in [
+            "lemma[a]: forall X (q(X) -> p(X))",
+            "lemma[b]: forall X (q(X) -> r(X))",
+            "lemma[c]: forall X (p(X) -> p(X))",
+        ] {
+            session.accept(step.parse().unwrap());
+        }
+
+        session.remove("b").unwrap();
+        assert_eq!(session.unchecked(), vec![1]);
+        assert_eq!(session.steps[1].formula.name, "c");
+    }
+}
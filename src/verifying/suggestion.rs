@@ -0,0 +1,374 @@
+use {
+    crate::{
+        convenience::choose_fresh_variable_names,
+        syntax_tree::fol,
+        verifying::{
+            outline::{self, GeneralLemma},
+            problem::Problem,
+        },
+    },
+    indexmap::{IndexMap, IndexSet},
+};
+
+/// A lemma proposed to help with a problem that could not be proven
+///
+/// A candidate is proven from the axioms of the problem alone,
+/// its consequences are then added to the axioms of another attempt at the problem.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub formula: fol::AnnotatedFormula,
+    pub lemma: GeneralLemma,
+}
+
+impl Candidate {
+    /// A candidate for the given formula, unless it is not a valid lemma
+    fn new(formula: fol::AnnotatedFormula) -> Option<Self> {
+        let lemma = GeneralLemma::try_from(formula.clone()).ok()?;
+        Some(Candidate { formula, lemma })
+    }
+
+    /// The problems of proving the conjectures of the candidate from the axioms of the given problem
+    pub fn problems(&self, problem: &Problem) -> Vec<Problem> {
+        self.lemma
+            .conjectures
+            .iter()
+            .enumerate()
+            .map(|(j, conjecture)| {
+                Problem::with_name(
+                    format!("{}_{}_{j}", problem.name, self.formula.name),
+                    problem.interpretation.clone(),
+                )
+                .in_direction(problem.direction)
+                .add_annotated_formulas(problem.axioms())
+                .add_annotated_formulas(std::iter::once(conjecture.clone()))
+            })
+            .collect()
+    }
+}
+
+fn variable(name: impl Into<String>, sort: fol::Sort) -> fol::Variable {
+    fol::Variable {
+        name: name.into(),
+        sort,
+    }
+}
+
+fn term(variable: &fol::Variable) -> fol::GeneralTerm {
+    match variable.sort {
+        fol::Sort::Integer => {
+            fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Variable(variable.name.clone()))
+        }
+        fol::Sort::Symbol => {
+            fol::GeneralTerm::SymbolicTerm(fol::SymbolicTerm::Variable(variable.name.clone()))
+        }
+        fol::Sort::General => fol::GeneralTerm::Variable(variable.name.clone()),
+    }
+}
+
+fn atom(predicate: &fol::Predicate, variables: &[fol::Variable]) -> fol::Formula {
+    fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(fol::Atom {
+        predicate_symbol: predicate.symbol.clone(),
+        terms: variables.iter().map(term).collect(),
+    }))
+}
+
+fn comparison(
+    lhs: fol::GeneralTerm,
+    relation: fol::Relation,
+    rhs: fol::GeneralTerm,
+) -> fol::Formula {
+    fol::Formula::AtomicFormula(fol::AtomicFormula::Comparison(fol::Comparison {
+        term: lhs,
+        guards: vec![fol::Guard {
+            relation,
+            term: rhs,
+        }],
+    }))
+}
+
+fn implication(lhs: fol::Formula, rhs: fol::Formula) -> fol::Formula {
+    fol::Formula::BinaryFormula {
+        connective: fol::BinaryConnective::Implication,
+        lhs: lhs.into(),
+        rhs: rhs.into(),
+    }
+}
+
+/// Typing lemmas stating that an argument of a predicate occurring in the conjectures of the problem is an integer,
+/// e.g. `forall V1 V2 (p(V1, V2) -> exists N$i (V1 = N$i))`
+pub fn typing_lemmas(problem: &Problem) -> IndexMap<(fol::Predicate, usize), Candidate> {
+    let predicates: IndexSet<_> = problem
+        .conjectures()
+        .iter()
+        .flat_map(|conjecture| conjecture.predicates())
+        .filter(|predicate| predicate.arity > 0)
+        .collect();
+
+    let mut result = IndexMap::new();
+    for predicate in predicates {
+        for i in 0..predicate.arity {
+            let formula = outline::typing_lemma(&predicate, i, problem.direction);
+            if let Some(candidate) = Candidate::new(formula) {
+                result.insert((predicate.clone(), i), candidate);
+            }
+        }
+    }
+    result
+}
+
+/// The predicate defined by a completed definition `forall V1 ... Vn (p(V1, ..., Vn) <-> F)` and its body `F`
+fn completed_definition(
+    formula: &fol::Formula,
+) -> Option<(fol::Predicate, &Vec<fol::Variable>, &fol::Formula)> {
+    match formula {
+        fol::Formula::QuantifiedFormula {
+            quantification:
+                fol::Quantification {
+                    quantifier: fol::Quantifier::Forall,
+                    variables,
+                },
+            formula,
+        } => match formula.as_ref() {
+            fol::Formula::BinaryFormula {
+                connective: fol::BinaryConnective::Equivalence,
+                lhs,
+                rhs,
+            } => match lhs.as_ref() {
+                fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(atom))
+                    if variables.iter().all(|v| v.sort == fol::Sort::General)
+                        && atom.terms == variables.iter().map(term).collect::<Vec<_>>() =>
+                {
+                    Some((atom.predicate(), variables, rhs))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The completed definitions of private predicates among the axioms of the problem,
+/// restricted to integer values for the arguments known to be integers,
+/// e.g. `forall N$i (p(N$i) <-> F(N$i))` for the definition `forall V1 (p(V1) <-> F(V1))`
+pub fn restricted_definitions(
+    problem: &Problem,
+    public_predicates: &IndexSet<fol::Predicate>,
+    integer_arguments: &IndexSet<(fol::Predicate, usize)>,
+) -> Vec<Candidate> {
+    let mut result = vec![];
+    for axiom in problem.axioms() {
+        let Some((predicate, variables, body)) = completed_definition(&axiom.formula) else {
+            continue;
+        };
+
+        let positions: Vec<_> = (0..variables.len())
+            .filter(|i| integer_arguments.contains(&(predicate.clone(), *i)))
+            .collect();
+        if public_predicates.contains(&predicate) || positions.is_empty() {
+            continue;
+        }
+
+        let fresh = choose_fresh_variable_names(&axiom.formula.variables(), "N", variables.len());
+
+        let mut restricted = variables.clone();
+        let mut formula = fol::Formula::BinaryFormula {
+            connective: fol::BinaryConnective::Equivalence,
+            lhs: atom(&predicate, variables).into(),
+            rhs: body.clone().into(),
+        };
+        for i in positions {
+            let integer = variable(fresh[i].clone(), fol::Sort::Integer);
+            formula = formula.substitute(variables[i].clone(), term(&integer));
+            restricted[i] = integer;
+        }
+
+        let formula = fol::AnnotatedFormula {
+            role: fol::Role::Lemma,
+            direction: problem.direction,
+            name: format!(
+                "restricted_definition_of_{}_{}",
+                predicate.symbol, predicate.arity
+            ),
+            using: None,
+            formula: formula.quantify(fol::Quantifier::Forall, restricted),
+        };
+
+        result.extend(Candidate::new(formula));
+    }
+    result
+}
+
+/// Inductive lemmas generalizing a conjecture `forall X (F)` of the problem to `forall X (N >= 0 -> F)`,
+/// for every integer variable `N` among `X`
+pub fn inductive_generalizations(problem: &Problem) -> Vec<Candidate> {
+    let mut result = vec![];
+    for conjecture in problem.conjectures() {
+        let fol::Formula::QuantifiedFormula {
+            quantification:
+                fol::Quantification {
+                    quantifier: fol::Quantifier::Forall,
+                    variables,
+                },
+            formula,
+        } = conjecture.formula
+        else {
+            continue;
+        };
+
+        for induction_variable in variables.iter().filter(|v| v.sort == fol::Sort::Integer) {
+            let bound = comparison(
+                term(induction_variable),
+                fol::Relation::GreaterEqual,
+                fol::GeneralTerm::IntegerTerm(fol::IntegerTerm::Numeral(0)),
+            );
+
+            let formula = fol::AnnotatedFormula {
+                role: fol::Role::InductiveLemma(fol::InductionScheme::Simple),
+                direction: problem.direction,
+                name: format!("generalization_{}", result.len()),
+                using: None,
+                formula: implication(bound, *formula.clone())
+                    .quantify(fol::Quantifier::Forall, variables.clone()),
+            };
+
+            result.extend(Candidate::new(formula));
+        }
+    }
+    result
+}
+
+/// Another attempt at the problem, with the consequences of the given candidates as additional axioms
+pub fn strengthen<'a>(
+    problem: &Problem,
+    candidates: impl IntoIterator<Item = &'a Candidate>,
+) -> Problem {
+    Problem::with_name(
+        format!("{}_with_suggestions", problem.name),
+        problem.interpretation.clone(),
+    )
+    .in_direction(problem.direction)
+    .add_annotated_formulas(problem.axioms())
+    .add_annotated_formulas(
+        candidates
+            .into_iter()
+            .flat_map(|candidate| candidate.lemma.consequences.clone()),
+    )
+    .add_annotated_formulas(problem.conjectures())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{inductive_generalizations, restricted_definitions, strengthen, typing_lemmas},
+        crate::{
+            syntax_tree::fol,
+            verifying::problem::{AnnotatedFormula, FormulaType, Interpretation, Problem, Role},
+        },
+        indexmap::IndexSet,
+    };
+
+    fn problem(
+        name: &str,
+        direction: fol::Direction,
+        axioms: &[&str],
+        conjecture: &str,
+    ) -> Problem {
+        Problem::with_name(name, Interpretation::Standard)
+            .in_direction(direction)
+            .add_annotated_formulas(
+                axioms
+                    .iter()
+                    .enumerate()
+                    .map(|(i, axiom)| AnnotatedFormula {
+                        name: format!("axiom_{i}"),
+                        role: Role::Axiom,
+                        formula: axiom.parse().unwrap(),
+                        formula_type: FormulaType::Tff,
+                    })
+                    .chain(std::iter::once(AnnotatedFormula {
+                        name: "conjecture".into(),
+                        role: Role::Conjecture,
+                        formula: conjecture.parse().unwrap(),
+                        formula_type: FormulaType::Tff,
+                    })),
+            )
+    }
+
+    #[test]
+    fn propose_candidates() {
+        let problem = problem(
+            "forward_problem_0",
+            fol::Direction::Forward,
+            &[
+                "forall V1 (composite(V1) <-> exists I$i J$i (V1 = I$i * J$i and I$i > 1 and J$i > 1))",
+                "forall V1 (prime(V1) <-> exists N$i (V1 = N$i and not composite(N$i)))",
+            ],
+            "forall V1 (prime(V1) -> not composite(V1))",
+        );
+        let public = IndexSet::from(["prime/1".parse().unwrap()]);
+
+        let typing = typing_lemmas(&problem);
+        assert_eq!(
+            typing
+                .values()
+                .map(|candidate| candidate.formula.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "lemma(forward)[typing_prime_1_0]: forall V1 (prime(V1) -> exists N$i (V1 = N$i))",
+                "lemma(forward)[typing_composite_1_0]: forall V1 (composite(V1) -> exists N$i (V1 = N$i))",
+            ]
+        );
+
+        let integer_arguments = typing.keys().cloned().collect();
+        let restricted = restricted_definitions(&problem, &public, &integer_arguments);
+        assert_eq!(
+            restricted
+                .iter()
+                .map(|candidate| candidate.formula.to_string())
+                .collect::<Vec<_>>(),
+            ["lemma(forward)[restricted_definition_of_composite_1]: forall N$i (composite(N$i) <-> exists I$i J$i (N$i = I$i * J$i and I$i > 1 and J$i > 1))"]
+        );
+
+        let strengthened = strengthen(&problem, &restricted);
+        assert_eq!(strengthened.name, "forward_problem_0_with_suggestions");
+        assert_eq!(strengthened.axioms().len(), 3);
+        assert_eq!(strengthened.conjectures(), problem.conjectures());
+    }
+
+    #[test]
+    fn generalize_conjectures() {
+        let problem = problem(
+            "backward_problem_0",
+            fol::Direction::Backward,
+            &[],
+            "forall X N$i (p(X, N$i) -> q(N$i))",
+        );
+
+        let candidates = inductive_generalizations(&problem);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(
+            candidates[0].formula.to_string(),
+            "inductive-lemma(backward)[generalization_0]: forall X N$i (N$i >= 0 -> (p(X, N$i) -> q(N$i)))"
+        );
+        assert_eq!(candidates[0].lemma.conjectures.len(), 2);
+
+        let problems = candidates[0].problems(&problem);
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| problem.name.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "backward_problem_0_generalization_0_0",
+                "backward_problem_0_generalization_0_1"
+            ]
+        );
+
+        assert!(inductive_generalizations(&Problem::with_name(
+            "problem",
+            Interpretation::Standard
+        ))
+        .is_empty());
+    }
+}
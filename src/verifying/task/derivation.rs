@@ -5,8 +5,8 @@ use {
         syntax_tree::fol,
         verifying::{
             outline::{ProofOutline, ProofOutlineError, ProofOutlineWarning},
-            problem::{self, FormulaType, Interpretation, Problem},
-            task::Task,
+            problem::{self, FormulaType, Problem},
+            task::{outline_problems, Task},
         },
     },
     thiserror::Error,
@@ -22,19 +22,21 @@ pub enum DerivationTaskError {
 pub enum DerivationTaskWarning {
     #[error("warning")]
     DefinitionWithWarning(#[from] ProofOutlineWarning),
+    #[error("the following formula is ignored in the {0} direction of the derivation due to its annotated direction: {1}")]
+    InconsistentDirectionAnnotation(fol::Direction, fol::AnnotatedFormula),
 }
 
 pub struct DerivationTask {
     pub proof_outline: fol::Specification,
     pub user_guide: fol::UserGuide,
     pub task_decomposition: TaskDecomposition,
+    pub direction: fol::Direction,
     pub simplify: bool,
     pub break_equivalences: bool,
 }
 
-// The derive task assumes proof outlines have been written correctly for this purpose, e.g.
-// 1. all the assumptions are defined in the user guide,
-// 2. none of the definitions, lemmas, etc. have a direction other than universal
+// The derive task assumes that all the assumptions are defined in the user guide
+// Both directions share these assumptions as premises, but may differ in the lemmas and definitions of the proof outline
 impl Task for DerivationTask {
     type Error = DerivationTaskError;
 
@@ -58,6 +60,32 @@ impl Task for DerivationTask {
 
         let mut warnings: Vec<DerivationTaskWarning> = Vec::new();
 
+        // The directions of the derivation, which is only split if the proof outline distinguishes them
+        let directed = self
+            .proof_outline
+            .formulas
+            .iter()
+            .any(|f| f.direction != fol::Direction::Universal);
+        let (forward, backward) = match self.direction {
+            fol::Direction::Universal => (true, directed),
+            fol::Direction::Forward => (true, false),
+            fol::Direction::Backward => (false, true),
+        };
+
+        for formula in &self.proof_outline.formulas {
+            let ignored = match formula.direction {
+                fol::Direction::Universal => None,
+                fol::Direction::Forward => (!forward).then_some(fol::Direction::Backward),
+                fol::Direction::Backward => (!backward).then_some(fol::Direction::Forward),
+            };
+            if let Some(direction) = ignored {
+                warnings.push(DerivationTaskWarning::InconsistentDirectionAnnotation(
+                    direction,
+                    formula.clone(),
+                ));
+            }
+        }
+
         let proof_outline_construction =
             ProofOutline::from_specification(self.proof_outline, taken_predicates, &placeholders)?;
         warnings.extend(
@@ -66,65 +94,52 @@ impl Task for DerivationTask {
                 .into_iter()
                 .map(DerivationTaskWarning::from),
         );
+        let proof_outline = proof_outline_construction.data;
 
-        let mut problems: Vec<Problem> = Vec::new();
-
-        let mut axioms = Vec::new();
+        let mut premises = Vec::new();
         for formula in self.user_guide.formulas() {
             match formula.role {
                 fol::Role::Assumption => {
                     let anf = formula.replace_placeholders(&placeholders);
-                    axioms.push(anf.into_problem_formula(problem::Role::Axiom, FormulaType::Tff));
+                    premises.push(anf.into_problem_formula(problem::Role::Axiom, FormulaType::Tff));
                 }
 
                 _ => todo!(), // user guides should only contain assumptions
             }
         }
 
-        // All definitions are treated as starting axioms
-        axioms.extend(
-            proof_outline_construction
-                .data
-                .forward_definitions
-                .into_iter()
-                .map(|f| f.into_problem_formula(problem::Role::Axiom, FormulaType::Tff)),
-        );
+        let mut problems: Vec<Problem> = Vec::new();
 
-        axioms.extend(
-            proof_outline_construction
-                .data
-                .backward_definitions
-                .into_iter()
-                .map(|f| f.into_problem_formula(problem::Role::Axiom, FormulaType::Tff)),
-        );
+        if forward {
+            let (prefix, direction) =
+                if forward && backward || self.direction == fol::Direction::Forward {
+                    ("forward_", fol::Direction::Forward)
+                } else {
+                    ("", fol::Direction::Universal)
+                };
+            problems.extend(outline_problems(
+                prefix,
+                direction,
+                &premises,
+                proof_outline.forward_definitions,
+                &proof_outline.forward_lemmas,
+                |problem| problem.decompose(self.task_decomposition),
+            ));
+        }
 
-        // All lemmas are derived sequentially, axiom set grows accordingly
-        // forward_lemmas = backward_lemmas if proof outline was written correctly
-        for (i, lemma) in proof_outline_construction
-            .data
-            .forward_lemmas
-            .iter()
-            .enumerate()
-        {
-            for (j, conjecture) in lemma.conjectures.iter().enumerate() {
-                problems.push(
-                    Problem::with_name(format!("outline_{i}_{j}"), Interpretation::Standard)
-                        .add_annotated_formulas(axioms.clone())
-                        .add_annotated_formulas(std::iter::once(conjecture.clone()))
-                        .rename_conflicting_symbols(),
-                );
-            }
-            axioms.append(&mut lemma.consequences.clone());
+        if backward {
+            problems.extend(outline_problems(
+                "backward_",
+                fol::Direction::Backward,
+                &premises,
+                proof_outline.backward_definitions,
+                &proof_outline.backward_lemmas,
+                |problem| problem.decompose(self.task_decomposition),
+            ));
         }
 
         Ok(WithWarnings {
-            data: problems
-                .into_iter()
-                .flat_map(|p: Problem| match self.task_decomposition {
-                    TaskDecomposition::Independent => p.decompose_independent(),
-                    TaskDecomposition::Sequential => p.decompose_sequential(),
-                })
-                .collect(),
+            data: problems,
             warnings,
         })
     }
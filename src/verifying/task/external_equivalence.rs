@@ -1,6 +1,6 @@
 use {
     crate::{
-        analyzing::{private_recursion::PrivateRecursion, tightness::Tightness},
+        analyzing::{private_recursion::PrivateRecursion, sorts::Sorts, tightness::Tightness},
         breaking::fol::ht::break_equivalences_annotated_formula,
         command_line::arguments::{FormulaRepresentation, TaskDecomposition},
         convenience::{
@@ -14,12 +14,15 @@ use {
                 tau_star,
                 Version::{AbstractGringoCompliant, Original},
             },
-            completion::completion,
+            completion::{completion, ordered_completion},
+            shorthand,
         },
         verifying::{
-            outline::{GeneralLemma, ProofOutline, ProofOutlineError, ProofOutlineWarning},
+            outline::{
+                typing_lemma, GeneralLemma, ProofOutline, ProofOutlineError, ProofOutlineWarning,
+            },
             problem::{self, Interpretation, Problem},
-            task::Task,
+            task::{outline_problems, Task},
         },
     },
     either::Either,
@@ -28,10 +31,19 @@ use {
     thiserror::Error,
 };
 
-trait RenamePredicates {
+/// Rename predicates by appending a suffix, e.g. `p/1` becomes `p_p/1` given the mapping `p/1 -> "p"`
+pub trait RenamePredicates {
     fn rename_predicates(self, mapping: &IndexMap<fol::Predicate, String>) -> Self;
 }
 
+impl RenamePredicates for fol::Theory {
+    fn rename_predicates(self, mapping: &IndexMap<fol::Predicate, String>) -> Self {
+        self.into_iter()
+            .map(|f| f.rename_predicates(mapping))
+            .collect()
+    }
+}
+
 impl RenamePredicates for fol::Specification {
     fn rename_predicates(self, mapping: &IndexMap<fol::Predicate, String>) -> Self {
         fol::Specification {
@@ -120,27 +132,48 @@ impl Display for ExternalEquivalenceTaskWarning {
 
 #[derive(Error, Debug)]
 pub enum ExternalEquivalenceTaskError {
-    NonTightProgram(asp::Program),
-    ProgramContainsPrivateRecursion(asp::Program),
+    LoopThroughConditionalLiteral(asp::Program),
+    OutsideShorthandFragment(asp::Rule),
+    PrivatePredicatesNotProjectable(asp::Program, Vec<fol::Predicate>),
     InputOutputPredicatesOverlap(Vec<fol::Predicate>),
     InputPredicateInRuleHead(Vec<fol::Predicate>),
     OutputPredicateInUserGuideAssumption(Vec<fol::Predicate>),
     OutputPredicateInSpecificationAssumption(Vec<fol::Predicate>),
     PlaceholdersWithIdenticalNamesDifferentSorts(String),
-    AssumptionContainsNonInputSymbols(fol::AnnotatedFormula),
-    AssumptionContainsFreeVariables(fol::AnnotatedFormula),
+    AssumptionContainsNonInputSymbols(Box<fol::AnnotatedFormula>),
+    AssumptionContainsFreeVariables(Box<fol::AnnotatedFormula>),
     ProofOutlineError(#[from] ProofOutlineError),
 }
 
 impl Display for ExternalEquivalenceTaskError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ExternalEquivalenceTaskError::NonTightProgram(program) => {
-                writeln!(f, "the following program is not tight: ")?;
+            ExternalEquivalenceTaskError::LoopThroughConditionalLiteral(program) => {
+                writeln!(f, "the ordered completion does not support positive loops through conditional literals, as in the following program: ")?;
                 writeln!(f, "{program}")
             }
-            ExternalEquivalenceTaskError::ProgramContainsPrivateRecursion(program) => {
-                writeln!(f, "the following program contains private recursion: ")?;
+            ExternalEquivalenceTaskError::OutsideShorthandFragment(rule) => {
+                writeln!(
+                    f,
+                    "the following rule falls outside the fragment supported by the shorthand formula representation: {rule}"
+                )
+            }
+            ExternalEquivalenceTaskError::PrivatePredicatesNotProjectable(program, predicates) => {
+                write!(
+                    f,
+                    "the following private predicates cannot be projected away since they are not determined uniquely by the public predicates (e.g. due to choice rules, recursion through negation, or positive recursion when bypassing tightness): "
+                )?;
+
+                let mut iter = predicates.iter().peekable();
+                for predicate in predicates {
+                    write!(f, "{predicate}")?;
+                    if iter.peek().is_some() {
+                        write!(f, ", ")?;
+                    }
+                }
+
+                writeln!(f)?;
+                writeln!(f, "in the program: ")?;
                 writeln!(f, "{program}")
             }
             ExternalEquivalenceTaskError::InputOutputPredicatesOverlap(predicates) => {
@@ -233,29 +266,43 @@ pub struct ExternalEquivalenceTask {
     pub task_decomposition: TaskDecomposition,
     pub direction: fol::Direction,
     pub bypass_tightness: bool,
+    /// Prove the typing lemmas inferred from the programs before the proof outline and use them as axioms
+    pub typing_lemmas: bool,
     pub simplify: bool,
     pub break_equivalences: bool,
 }
 
+/// The typing lemmas for the integer arguments of the predicates defined by the program
+fn typing_lemmas(program: &asp::Program, direction: fol::Direction) -> fol::Specification {
+    fol::Specification {
+        formulas: program
+            .integer_arguments()
+            .into_iter()
+            .map(|(predicate, position)| {
+                typing_lemma(&fol::Predicate::from(predicate), position, direction)
+            })
+            .collect(),
+    }
+}
+
 impl ExternalEquivalenceTask {
-    fn ensure_program_tightness(
+    fn warn_about_bypassed_tightness(
         &self,
         program: &asp::Program,
     ) -> Result<(), ExternalEquivalenceTaskWarning, ExternalEquivalenceTaskError> {
-        if program.is_tight() {
-            Ok(WithWarnings::flawless(()))
-        } else if self.bypass_tightness {
+        if self.bypass_tightness && !program.is_tight() {
             Ok(WithWarnings::flawless(()).add_warning(
                 ExternalEquivalenceTaskWarning::NonTightProgram(program.clone()),
             ))
         } else {
-            Err(ExternalEquivalenceTaskError::NonTightProgram(
-                program.clone(),
-            ))
+            Ok(WithWarnings::flawless(()))
         }
     }
 
-    fn ensure_absence_of_private_recursion(
+    /// Private predicates are treated as assumptions by their completed definitions, which amounts to
+    /// projecting them away as long as they are determined uniquely by the public predicates.
+    /// Positive private recursion is resolved by the level mappings of the ordered completion.
+    fn ensure_projectability_of_private_predicates(
         &self,
         program: &asp::Program,
         private_predicates: &IndexSet<fol::Predicate>,
@@ -266,10 +313,35 @@ impl ExternalEquivalenceTask {
             .map(asp::Predicate::from)
             .collect();
 
-        if program.has_private_recursion(&private_predicates) {
-            Err(ExternalEquivalenceTaskError::ProgramContainsPrivateRecursion(program.clone()))
-        } else {
+        if !program.has_private_recursion(&private_predicates) {
+            return Ok(WithWarnings::flawless(()));
+        }
+
+        let mut unprojectable = program.unprojectable_private_predicates(&private_predicates);
+
+        // The plain completion does not determine positively recursive private predicates uniquely
+        if self.bypass_tightness {
+            unprojectable.extend(
+                program
+                    .positive_loops()
+                    .into_iter()
+                    .flatten()
+                    .filter(|predicate| private_predicates.contains(predicate)),
+            );
+        }
+
+        if unprojectable.is_empty() {
             Ok(WithWarnings::flawless(()))
+        } else {
+            Err(
+                ExternalEquivalenceTaskError::PrivatePredicatesNotProjectable(
+                    program.clone(),
+                    unprojectable
+                        .into_iter()
+                        .map(fol::Predicate::from)
+                        .collect(),
+                ),
+            )
         }
     }
 
@@ -376,7 +448,7 @@ impl ExternalEquivalenceTask {
                 if predicates.difference(&inputs).next().is_some() {
                     return Err(
                         ExternalEquivalenceTaskError::AssumptionContainsNonInputSymbols(
-                            formula.clone(),
+                            formula.clone().into(),
                         ),
                     );
                 }
@@ -395,7 +467,9 @@ impl ExternalEquivalenceTask {
                 && !formula.formula.free_variables().is_empty()
             {
                 return Err(
-                    ExternalEquivalenceTaskError::AssumptionContainsFreeVariables(formula.clone()),
+                    ExternalEquivalenceTaskError::AssumptionContainsFreeVariables(
+                        formula.clone().into(),
+                    ),
                 );
             }
         }
@@ -409,6 +483,23 @@ impl Task for ExternalEquivalenceTask {
     type Warning = ExternalEquivalenceTaskWarning;
 
     fn decompose(self) -> Result<Vec<Problem>, Self::Warning, Self::Error> {
+        let assembled = self.assemble()?;
+        Ok(assembled
+            .data
+            .decompose()?
+            .preface_warnings(assembled.warnings))
+    }
+}
+
+impl ExternalEquivalenceTask {
+    /// Validate the task and sort the resulting formulas into premises and conclusions of both directions
+    pub fn assemble(
+        self,
+    ) -> Result<
+        AssembledExternalEquivalenceTask,
+        ExternalEquivalenceTaskWarning,
+        ExternalEquivalenceTaskError,
+    > {
         let placeholders = self
             .user_guide
             .placeholders()
@@ -443,8 +534,11 @@ impl Task for ExternalEquivalenceTask {
         let mut warnings = Vec::new();
 
         self.ensure_input_and_output_predicates_are_disjoint()?;
-        warnings.extend(self.ensure_program_tightness(&self.program)?.warnings);
-        self.ensure_absence_of_private_recursion(&self.program, &program_private_predicates)?;
+        warnings.extend(self.warn_about_bypassed_tightness(&self.program)?.warnings);
+        self.ensure_projectability_of_private_predicates(
+            &self.program,
+            &program_private_predicates,
+        )?;
         self.ensure_rule_heads_do_not_contain_input_predicates(&self.program)?;
         self.ensure_placeholder_name_uniqueness()?;
         self.ensure_assumptions_only_contain_input_symbols(&self.user_guide.formulas())?;
@@ -452,8 +546,8 @@ impl Task for ExternalEquivalenceTask {
 
         match self.specification {
             Either::Left(ref program) => {
-                warnings.extend(self.ensure_program_tightness(program)?.warnings);
-                self.ensure_absence_of_private_recursion(
+                warnings.extend(self.warn_about_bypassed_tightness(program)?.warnings);
+                self.ensure_projectability_of_private_predicates(
                     program,
                     &specification_private_predicates,
                 )?;
@@ -501,18 +595,21 @@ impl Task for ExternalEquivalenceTask {
                         role: fol::Role::Spec,
                         direction: fol::Direction::Universal,
                         name: format!("completed_definition_of_{}_{}", p.symbol, p.arity),
+                        using: None,
                         formula,
                     },
                     Some(p) => fol::AnnotatedFormula {
                         role: fol::Role::Assumption,
                         direction: fol::Direction::Universal,
                         name: format!("completed_definition_of_{}_{}", p.symbol, p.arity),
+                        using: None,
                         formula,
                     },
                     None => fol::AnnotatedFormula {
                         role: fol::Role::Spec,
                         direction: fol::Direction::Universal,
                         name: format!("constraint_{}", constraint_counter.next().unwrap()),
+                        using: None,
                         formula,
                     },
                 })
@@ -521,17 +618,67 @@ impl Task for ExternalEquivalenceTask {
         };
 
         let version = match self.formula_representation {
-            FormulaRepresentation::TauStarV1 => Original,
+            FormulaRepresentation::TauStarV1 | FormulaRepresentation::Shorthand => Original,
             FormulaRepresentation::TauStarV2 => AbstractGringoCompliant,
-            FormulaRepresentation::Shorthand => Original,
+        };
+        let use_shorthand = matches!(
+            self.formula_representation,
+            FormulaRepresentation::Shorthand
+        );
+
+        // Non-tight programs are completed with level mappings unless the user claims they are locally tight
+        let bypass_tightness = self.bypass_tightness;
+        let complete = |program: asp::Program| {
+            let loops: Vec<IndexSet<fol::Predicate>> = if bypass_tightness {
+                Vec::new()
+            } else {
+                program
+                    .positive_loops()
+                    .into_iter()
+                    .map(|predicates| predicates.into_iter().map(fol::Predicate::from).collect())
+                    .collect()
+            };
+
+            let theory = if use_shorthand {
+                if let Some(rule) = shorthand::unsupported_rule(&program) {
+                    return Err(ExternalEquivalenceTaskError::OutsideShorthandFragment(
+                        rule.clone(),
+                    ));
+                }
+                shorthand::completable_shorthand(program.clone())
+            } else {
+                tau_star::tau_star(program.clone(), version)
+            }
+            .replace_placeholders(&placeholders);
+
+            if loops.is_empty() {
+                Ok(
+                    completion(theory)
+                        .expect("the translation did not create a completable theory"),
+                )
+            } else {
+                ordered_completion(theory, &loops).ok_or(
+                    ExternalEquivalenceTaskError::LoopThroughConditionalLiteral(program),
+                )
+            }
+        };
+
+        // A typing lemma follows from the completion of its program, which is a premise of one direction only
+        let (left_typing_lemmas, right_typing_lemmas) = if self.typing_lemmas {
+            (
+                match self.specification {
+                    Either::Left(ref program) => typing_lemmas(program, fol::Direction::Forward),
+                    Either::Right(_) => fol::Specification::empty(),
+                },
+                typing_lemmas(&self.program, fol::Direction::Backward),
+            )
+        } else {
+            (fol::Specification::empty(), fol::Specification::empty())
         };
 
         let left = match self.specification {
             Either::Left(program) => {
-                let formula_representation = completion(
-                    tau_star::tau_star(program, version).replace_placeholders(&placeholders),
-                )
-                .expect("tau_star did not create a completable theory");
+                let formula_representation = complete(program)?;
                 if self.simplify {
                     control_translate(simplify(formula_representation))
                 } else {
@@ -542,10 +689,7 @@ impl Task for ExternalEquivalenceTask {
         };
 
         let right = {
-            let formula_representation = completion(
-                tau_star::tau_star(self.program, version).replace_placeholders(&placeholders),
-            )
-            .expect("tau_star did not create a completable theory");
+            let formula_representation = complete(self.program)?;
             if self.simplify {
                 control_translate(simplify(formula_representation))
             } else {
@@ -553,14 +697,25 @@ impl Task for ExternalEquivalenceTask {
             }
         };
 
+        // The translations may introduce private predicates (e.g. level mappings), so conflicts are determined afterwards
         // TODO: Warn when a conflict between private predicates is encountered
         // TODO: Check if renaming creates new conflicts
-        let right = right.rename_predicates(
-            &specification_private_predicates
-                .intersection(&program_private_predicates)
-                .map(|p| (p.clone(), "p".to_string()))
-                .collect(),
-        );
+        let left_private_predicates: IndexSet<_> = left
+            .predicates()
+            .into_iter()
+            .filter(|p| !public_predicates.contains(p))
+            .collect();
+        let right_private_predicates: IndexSet<_> = right
+            .predicates()
+            .into_iter()
+            .filter(|p| !public_predicates.contains(p))
+            .collect();
+        let renaming = left_private_predicates
+            .intersection(&right_private_predicates)
+            .map(|p| (p.clone(), "p".to_string()))
+            .collect();
+        let right = right.rename_predicates(&renaming);
+        let right_typing_lemmas = right_typing_lemmas.rename_predicates(&renaming);
 
         let mut user_guide_assumptions = Vec::new();
         for formula in self.user_guide.formulas() {
@@ -595,8 +750,17 @@ impl Task for ExternalEquivalenceTask {
             taken_predicates.extend(anf.formula.predicates());
         }
 
+        let proof_outline = fol::Specification {
+            formulas: left_typing_lemmas
+                .formulas
+                .into_iter()
+                .chain(right_typing_lemmas.formulas)
+                .chain(self.proof_outline.formulas)
+                .collect(),
+        };
+
         let proof_outline_construction =
-            ProofOutline::from_specification(self.proof_outline, taken_predicates, &placeholders)?;
+            ProofOutline::from_specification(proof_outline, taken_predicates, &placeholders)?;
         warnings.extend(
             proof_outline_construction
                 .warnings
@@ -613,7 +777,7 @@ impl Task for ExternalEquivalenceTask {
             direction: self.direction,
             break_equivalences: self.break_equivalences,
         }
-        .decompose()?
+        .assemble()
         .preface_warnings(warnings))
     }
 }
@@ -628,11 +792,10 @@ struct ValidatedExternalEquivalenceTask {
     pub break_equivalences: bool,
 }
 
-impl Task for ValidatedExternalEquivalenceTask {
-    type Error = ExternalEquivalenceTaskError;
-    type Warning = ExternalEquivalenceTaskWarning;
-
-    fn decompose(self) -> Result<Vec<Problem>, Self::Warning, Self::Error> {
+impl ValidatedExternalEquivalenceTask {
+    fn assemble(
+        self,
+    ) -> WithWarnings<AssembledExternalEquivalenceTask, ExternalEquivalenceTaskWarning> {
         use crate::{
             syntax_tree::fol::{Direction::*, Role::*},
             verifying::problem::Role::*,
@@ -687,7 +850,7 @@ impl Task for ValidatedExternalEquivalenceTask {
                         }
                     }
                 }
-                Lemma | Definition | InductiveLemma => unreachable!(),
+                Lemma | Definition | InductiveLemma(_) | CaseLemma => unreachable!(),
             }
         }
 
@@ -727,26 +890,27 @@ impl Task for ValidatedExternalEquivalenceTask {
                         }
                     }
                 }
-                Lemma | Definition | InductiveLemma => unreachable!(),
+                Lemma | Definition | InductiveLemma(_) | CaseLemma => unreachable!(),
             }
         }
 
-        Ok(AssembledExternalEquivalenceTask {
-            stable_premises,
-            forward_premises,
-            forward_conclusions,
-            backward_premises,
-            backward_conclusions,
-            proof_outline: self.proof_outline,
-            decomposition: self.decomposition,
-            direction: self.direction,
+        WithWarnings {
+            data: AssembledExternalEquivalenceTask {
+                stable_premises,
+                forward_premises,
+                forward_conclusions,
+                backward_premises,
+                backward_conclusions,
+                proof_outline: self.proof_outline,
+                decomposition: self.decomposition,
+                direction: self.direction,
+            },
+            warnings,
         }
-        .decompose()?
-        .preface_warnings(warnings))
     }
 }
 
-struct AssembledExternalEquivalenceTask {
+pub struct AssembledExternalEquivalenceTask {
     pub stable_premises: Vec<problem::AnnotatedFormula>,
     pub forward_premises: Vec<problem::AnnotatedFormula>,
     pub forward_conclusions: Vec<problem::AnnotatedFormula>,
@@ -768,31 +932,22 @@ impl Task for AssembledExternalEquivalenceTask {
             self.direction,
             fol::Direction::Universal | fol::Direction::Forward
         ) {
-            let mut axioms = self.stable_premises.clone();
-            axioms.extend(self.forward_premises.clone());
-            axioms.extend(
-                self.proof_outline.forward_definitions.into_iter().map(|f| {
-                    f.into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff)
-                }),
+            let mut premises = self.stable_premises.clone();
+            premises.extend(self.forward_premises.clone());
+            let outline = outline_problems(
+                "forward_",
+                fol::Direction::Forward,
+                &premises,
+                self.proof_outline.forward_definitions,
+                &self.proof_outline.forward_lemmas,
+                |problem| vec![problem],
             );
-
-            for (i, lemma) in self.proof_outline.forward_lemmas.iter().enumerate() {
-                for (j, conjecture) in lemma.conjectures.iter().enumerate() {
-                    problems.push(
-                        Problem::with_name(
-                            format!("forward_outline_{i}_{j}"),
-                            Interpretation::Standard,
-                        )
-                        .add_annotated_formulas(axioms.clone())
-                        .add_annotated_formulas(std::iter::once(conjecture.clone()))
-                        .rename_conflicting_symbols(),
-                    );
-                }
-                axioms.append(&mut lemma.consequences.clone());
-            }
+            let dependencies: Vec<_> = outline.iter().map(|p| p.name.clone()).collect();
+            problems.extend(outline);
 
             problems.append(
                 &mut Problem::with_name("forward_problem", Interpretation::Standard)
+                    .in_direction(fol::Direction::Forward)
                     .add_annotated_formulas(self.stable_premises.clone())
                     .add_annotated_formulas(self.forward_premises)
                     .add_annotated_formulas(
@@ -803,6 +958,7 @@ impl Task for AssembledExternalEquivalenceTask {
                     )
                     .add_annotated_formulas(self.forward_conclusions)
                     .rename_conflicting_symbols()
+                    .add_dependencies(dependencies)
                     .decompose(self.decomposition),
             );
         }
@@ -811,34 +967,22 @@ impl Task for AssembledExternalEquivalenceTask {
             self.direction,
             fol::Direction::Universal | fol::Direction::Backward
         ) {
-            let mut axioms = self.stable_premises.clone();
-            axioms.extend(self.backward_premises.clone());
-            axioms.extend(
-                self.proof_outline
-                    .backward_definitions
-                    .into_iter()
-                    .map(|f| {
-                        f.into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff)
-                    }),
+            let mut premises = self.stable_premises.clone();
+            premises.extend(self.backward_premises.clone());
+            let outline = outline_problems(
+                "backward_",
+                fol::Direction::Backward,
+                &premises,
+                self.proof_outline.backward_definitions,
+                &self.proof_outline.backward_lemmas,
+                |problem| vec![problem],
             );
-
-            for (i, lemma) in self.proof_outline.backward_lemmas.iter().enumerate() {
-                for (j, conjecture) in lemma.conjectures.iter().enumerate() {
-                    problems.push(
-                        Problem::with_name(
-                            format!("backward_outline_{i}_{j}"),
-                            Interpretation::Standard,
-                        )
-                        .add_annotated_formulas(axioms.clone())
-                        .add_annotated_formulas(std::iter::once(conjecture.clone()))
-                        .rename_conflicting_symbols(),
-                    );
-                }
-                axioms.append(&mut lemma.consequences.clone());
-            }
+            let dependencies: Vec<_> = outline.iter().map(|p| p.name.clone()).collect();
+            problems.extend(outline);
 
             problems.append(
                 &mut Problem::with_name("backward_problem", Interpretation::Standard)
+                    .in_direction(fol::Direction::Backward)
                     .add_annotated_formulas(self.stable_premises)
                     .add_annotated_formulas(self.backward_premises)
                     .add_annotated_formulas(
@@ -849,6 +993,7 @@ impl Task for AssembledExternalEquivalenceTask {
                     )
                     .add_annotated_formulas(self.backward_conclusions)
                     .rename_conflicting_symbols()
+                    .add_dependencies(dependencies)
                     .decompose(self.decomposition),
             );
         }
@@ -2,11 +2,71 @@ pub mod derivation;
 pub mod external_equivalence;
 pub mod intuit_equivalence;
 pub mod strong_equivalence;
+pub mod uniform_equivalence;
 
-use crate::{convenience::with_warnings::Result, verifying::problem::Problem};
+use crate::{
+    convenience::with_warnings::Result,
+    syntax_tree::fol,
+    verifying::{
+        outline::GeneralLemma,
+        problem::{self, Interpretation, Problem},
+    },
+};
 
 pub trait Task {
     type Error;
     type Warning;
     fn decompose(self) -> Result<Vec<Problem>, Self::Warning, Self::Error>;
 }
+
+/// The problems of proving the lemmas of one direction of a proof outline in sequence from the premises and definitions,
+/// named `{prefix}outline_{i}_{j}` for the `j`-th conjecture of the `i`-th lemma and belonging to the given direction
+///
+/// The problems of a lemma depend on the problems of the earlier lemmas it relies on.
+/// Every problem is passed through `finish` (e.g., to decompose it) before the problems depending on it are created.
+pub(crate) fn outline_problems(
+    prefix: &str,
+    direction: fol::Direction,
+    premises: &[problem::AnnotatedFormula],
+    definitions: Vec<fol::AnnotatedFormula>,
+    lemmas: &[GeneralLemma],
+    finish: impl Fn(Problem) -> Vec<Problem>,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    // All definitions of the direction are treated as starting axioms
+    let definitions: Vec<_> = definitions
+        .into_iter()
+        .map(|f| f.into_problem_formula(problem::Role::Axiom, problem::FormulaType::Tff))
+        .collect();
+
+    // Every lemma is proven from the consequences of the earlier lemmas it depends on
+    let mut names: Vec<Vec<String>> = Vec::new();
+    for (i, lemma) in lemmas.iter().enumerate() {
+        let dependencies: Vec<_> = lemma
+            .dependencies
+            .iter()
+            .flat_map(|k| names[*k].clone())
+            .collect();
+        let mut lemma_names = Vec::new();
+        for (j, conjecture) in lemma.conjectures.iter().enumerate() {
+            let finished = finish(
+                Problem::with_name(format!("{prefix}outline_{i}_{j}"), Interpretation::Standard)
+                    .in_direction(direction)
+                    .add_annotated_formulas(lemma.proof_axioms(
+                        premises,
+                        &definitions,
+                        &lemmas[..i],
+                    ))
+                    .add_annotated_formulas(std::iter::once(conjecture.clone()))
+                    .rename_conflicting_symbols()
+                    .add_dependencies(dependencies.clone()),
+            );
+            lemma_names.extend(finished.iter().map(|p| p.name.clone()));
+            problems.extend(finished);
+        }
+        names.push(lemma_names);
+    }
+
+    problems
+}
@@ -1,5 +1,6 @@
 use {
     crate::{
+        analyzing::private_recursion::PrivateRecursion,
         command_line::arguments::{FormulaRepresentation, TaskDecomposition},
         convenience::with_warnings::{Result, WithWarnings},
         syntax_tree::{asp, fol},
@@ -8,23 +9,48 @@ use {
                 tau_star,
                 Version::{AbstractGringoCompliant, Original},
             },
+            completion::completion,
             gamma::{self, gamma},
         },
         verifying::{
             problem::{AnnotatedFormula, FormulaType, Interpretation, Problem, Role},
-            task::Task,
+            task::{external_equivalence::RenamePredicates as _, Task},
         },
     },
-    std::convert::Infallible,
+    indexmap::{IndexMap, IndexSet},
+    std::{convert::Infallible, fmt::Display},
     thiserror::Error,
 };
 
 #[derive(Error, Debug)]
-pub enum StrongEquivalenceTaskError {}
+pub enum StrongEquivalenceTaskError {
+    AuxiliaryPredicateRecursion(asp::Program),
+}
+
+impl Display for StrongEquivalenceTaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StrongEquivalenceTaskError::AuxiliaryPredicateRecursion(program) => {
+                writeln!(
+                    f,
+                    "the following program contains recursion through predicates outside of the context: "
+                )?;
+                writeln!(f, "{program}")
+            }
+        }
+    }
+}
 
 pub struct StrongEquivalenceTask {
     pub left: asp::Program,
     pub right: asp::Program,
+    /// The predicates that may occur in the rules added to both programs,
+    /// all other (auxiliary) predicates are hidden from the comparison
+    ///
+    /// Every auxiliary predicate must be defined without recursion and choice rules.
+    /// Then the completed definitions of the auxiliary predicates of both programs are added as axioms,
+    /// which preserves the answer sets of both programs when combined with rules over the context.
+    pub context: Option<IndexSet<fol::Predicate>>,
     pub formula_representation: FormulaRepresentation,
     pub task_decomposition: TaskDecomposition,
     pub direction: fol::Direction,
@@ -33,10 +59,40 @@ pub struct StrongEquivalenceTask {
 }
 
 impl StrongEquivalenceTask {
-    fn transition_axioms(&self) -> fol::Theory {
-        fn transition(p: asp::Predicate) -> fol::Formula {
-            let p: fol::Predicate = p.into();
+    fn ensure_absence_of_auxiliary_recursion(
+        &self,
+        program: &asp::Program,
+        auxiliary_predicates: &IndexSet<fol::Predicate>,
+    ) -> std::result::Result<(), StrongEquivalenceTaskError> {
+        let auxiliary_predicates = auxiliary_predicates
+            .iter()
+            .cloned()
+            .map(asp::Predicate::from)
+            .collect();
+
+        if program.has_private_recursion(&auxiliary_predicates) {
+            Err(StrongEquivalenceTaskError::AuxiliaryPredicateRecursion(
+                program.clone(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
 
+    fn auxiliary_predicates(&self, program: &asp::Program) -> IndexSet<fol::Predicate> {
+        match self.context {
+            Some(ref context) => program
+                .predicates()
+                .into_iter()
+                .map(fol::Predicate::from)
+                .filter(|p| !context.contains(p))
+                .collect(),
+            None => IndexSet::new(),
+        }
+    }
+
+    fn transition_axioms(predicates: IndexSet<fol::Predicate>) -> fol::Theory {
+        fn transition(p: fol::Predicate) -> fol::Formula {
             let hp = gamma::here(p.clone().to_formula());
             let tp = gamma::there(p.to_formula());
 
@@ -50,21 +106,49 @@ impl StrongEquivalenceTask {
             .quantify(fol::Quantifier::Forall, variables.into_iter().collect())
         }
 
-        let mut predicates = self.left.predicates();
-        predicates.extend(self.right.predicates());
-
         fol::Theory {
             formulas: predicates.into_iter().map(transition).collect(),
         }
     }
 }
 
+/// The completed definitions of the given predicates within a theory
+fn completed_definitions(
+    theory: fol::Theory,
+    predicates: &IndexSet<fol::Predicate>,
+) -> fol::Theory {
+    fn head_predicate(formula: &fol::Formula) -> Option<fol::Predicate> {
+        match formula {
+            fol::Formula::BinaryFormula {
+                connective: fol::BinaryConnective::Equivalence,
+                lhs,
+                ..
+            } => match **lhs {
+                fol::Formula::AtomicFormula(fol::AtomicFormula::Atom(ref a)) => Some(a.predicate()),
+                _ => None,
+            },
+            fol::Formula::QuantifiedFormula { formula, .. } => head_predicate(formula),
+            _ => None,
+        }
+    }
+
+    completion(theory)
+        .expect("tau_star did not create a completable theory")
+        .into_iter()
+        .filter(|formula| head_predicate(formula).is_some_and(|p| predicates.contains(&p)))
+        .collect()
+}
+
 impl Task for StrongEquivalenceTask {
     type Error = StrongEquivalenceTaskError;
     type Warning = Infallible;
 
     fn decompose(self) -> Result<Vec<Problem>, Self::Warning, Self::Error> {
-        let transition_axioms = self.transition_axioms(); // These are the "forall X (hp(X) -> tp(X))" axioms.
+        let left_auxiliary_predicates = self.auxiliary_predicates(&self.left);
+        let right_auxiliary_predicates = self.auxiliary_predicates(&self.right);
+
+        self.ensure_absence_of_auxiliary_recursion(&self.left, &left_auxiliary_predicates)?;
+        self.ensure_absence_of_auxiliary_recursion(&self.right, &right_auxiliary_predicates)?;
 
         let version = match self.formula_representation {
             FormulaRepresentation::TauStarV1 => Original,
@@ -75,22 +159,59 @@ impl Task for StrongEquivalenceTask {
         let mut left = tau_star::tau_star(self.left, version);
         let mut right = tau_star::tau_star(self.right, version);
 
+        // Auxiliary predicates of both programs are different predicates, even if their names coincide
+        let conflicts: IndexMap<_, _> = left_auxiliary_predicates
+            .intersection(&right_auxiliary_predicates)
+            .map(|p| (p.clone(), "r".to_string()))
+            .collect();
+        right = right.rename_predicates(&conflicts);
+        let right_auxiliary_predicates: IndexSet<_> = right_auxiliary_predicates
+            .into_iter()
+            .map(|p| match conflicts.get(&p) {
+                Some(suffix) => fol::Predicate {
+                    symbol: format!("{}_{suffix}", p.symbol),
+                    arity: p.arity,
+                },
+                None => p,
+            })
+            .collect();
+
+        let mut definitions: fol::Theory =
+            completed_definitions(left.clone(), &left_auxiliary_predicates)
+                .into_iter()
+                .chain(completed_definitions(
+                    right.clone(),
+                    &right_auxiliary_predicates,
+                ))
+                .collect();
+
+        // These are the "forall X (hp(X) -> tp(X))" axioms.
+        let mut predicates = IndexSet::new();
+        for formula in left.formulas.iter().chain(right.formulas.iter()) {
+            predicates.extend(formula.predicates());
+        }
+        let transition_axioms = Self::transition_axioms(predicates);
+
         if self.simplify {
             left = crate::simplifying::fol::ht::simplify(left);
             right = crate::simplifying::fol::ht::simplify(right);
+            definitions = crate::simplifying::fol::ht::simplify(definitions);
         }
 
         left = gamma(left);
         right = gamma(right);
+        definitions = gamma(definitions);
 
         if self.simplify {
             left = crate::simplifying::fol::classic::simplify(left);
             right = crate::simplifying::fol::classic::simplify(right);
+            definitions = crate::simplifying::fol::classic::simplify(definitions);
         }
 
         if self.break_equivalences {
             left = crate::breaking::fol::ht::break_equivalences_theory(left);
             right = crate::breaking::fol::ht::break_equivalences_theory(right);
+            definitions = crate::breaking::fol::ht::break_equivalences_theory(definitions);
         }
 
         let mut problems = Vec::new();
@@ -106,6 +227,12 @@ impl Task for StrongEquivalenceTask {
                         formula,
                         formula_type: FormulaType::Tff,
                     })
+                    .add_theory(definitions.clone(), |i, formula| AnnotatedFormula {
+                        name: format!("auxiliary_definition_{i}"),
+                        role: Role::Axiom,
+                        formula,
+                        formula_type: FormulaType::Tff,
+                    })
                     .add_theory(left.clone(), |i, formula| AnnotatedFormula {
                         name: format!("left_{i}"),
                         role: Role::Axiom,
@@ -133,6 +260,12 @@ impl Task for StrongEquivalenceTask {
                         formula,
                         formula_type: FormulaType::Tff,
                     })
+                    .add_theory(definitions, |i, formula| AnnotatedFormula {
+                        name: format!("auxiliary_definition_{i}"),
+                        role: Role::Axiom,
+                        formula,
+                        formula_type: FormulaType::Tff,
+                    })
                     .add_theory(right, |i, formula| AnnotatedFormula {
                         name: format!("right_{i}"),
                         role: Role::Axiom,
@@ -0,0 +1,280 @@
+use {
+    crate::{
+        analyzing::tightness::Tightness,
+        command_line::arguments::{FormulaRepresentation, TaskDecomposition},
+        convenience::with_warnings::{Result, WithWarnings},
+        syntax_tree::{asp, fol},
+        translating::{
+            asp_to_ht::{
+                tau_star,
+                Version::{AbstractGringoCompliant, Original},
+            },
+            completion::completion,
+            shorthand,
+        },
+        verifying::{
+            problem::{AnnotatedFormula, FormulaType, Interpretation, Problem, Role},
+            task::Task,
+        },
+    },
+    indexmap::IndexSet,
+    std::{convert::Infallible, fmt::Display},
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum UniformEquivalenceTaskError {
+    NonTightProgram(asp::Program),
+    OutsideShorthandFragment(asp::Rule),
+}
+
+impl Display for UniformEquivalenceTaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UniformEquivalenceTaskError::NonTightProgram(program) => {
+                writeln!(f, "the following program is not tight: ")?;
+                writeln!(f, "{program}")
+            }
+            UniformEquivalenceTaskError::OutsideShorthandFragment(rule) => {
+                writeln!(
+                    f,
+                    "the following rule falls outside the fragment supported by the shorthand formula representation: {rule}"
+                )
+            }
+        }
+    }
+}
+
+/// Verify that two tight programs have the same answer sets when extended by any set of facts
+///
+/// The facts are represented by a fresh predicate `p_fact` for every predicate `p`,
+/// which is added to both programs by the rule `p(X1, ..., Xn) :- p_fact(X1, ..., Xn)`.
+/// Since the extended programs remain tight, it suffices to prove the equivalence of their completions.
+pub struct UniformEquivalenceTask {
+    pub left: asp::Program,
+    pub right: asp::Program,
+    pub formula_representation: FormulaRepresentation,
+    pub task_decomposition: TaskDecomposition,
+    pub direction: fol::Direction,
+    pub simplify: bool,
+    pub break_equivalences: bool,
+}
+
+impl UniformEquivalenceTask {
+    fn ensure_program_tightness(
+        &self,
+        program: &asp::Program,
+    ) -> std::result::Result<(), UniformEquivalenceTaskError> {
+        if program.is_tight() {
+            Ok(())
+        } else {
+            Err(UniformEquivalenceTaskError::NonTightProgram(
+                program.clone(),
+            ))
+        }
+    }
+
+    /// The rules `p(X1, ..., Xn) :- p_fact(X1, ..., Xn)` for every predicate `p` of both programs
+    fn fact_rules(&self) -> Vec<asp::Rule> {
+        let mut predicates = self.left.predicates();
+        predicates.extend(self.right.predicates());
+
+        let mut taken_symbols: IndexSet<_> = predicates.iter().map(|p| p.symbol.clone()).collect();
+
+        let mut rules = Vec::new();
+        for predicate in predicates {
+            let fact_symbol = (0..)
+                .map(|i| match i {
+                    0 => format!("{}_fact", predicate.symbol),
+                    i => format!("{}_fact{i}", predicate.symbol),
+                })
+                .find(|symbol| !taken_symbols.contains(symbol))
+                .unwrap();
+            taken_symbols.insert(fact_symbol.clone());
+
+            let terms: Vec<_> = (1..=predicate.arity)
+                .map(|i| asp::Term::Variable(asp::Variable(format!("X{i}"))))
+                .collect();
+
+            rules.push(asp::Rule {
+                head: asp::Head::Basic(asp::Atom {
+                    predicate_symbol: predicate.symbol,
+                    terms: terms.clone(),
+                }),
+                body: asp::Body {
+                    formulas: vec![asp::ConditionalLiteral {
+                        head: asp::ConditionalHead::AtomicFormula(asp::AtomicFormula::Literal(
+                            asp::Literal {
+                                sign: asp::Sign::NoSign,
+                                atom: asp::Atom {
+                                    predicate_symbol: fact_symbol,
+                                    terms,
+                                },
+                            },
+                        )),
+                        conditions: asp::ConditionalBody { formulas: vec![] },
+                    }],
+                },
+            });
+        }
+
+        rules
+    }
+}
+
+impl Task for UniformEquivalenceTask {
+    type Error = UniformEquivalenceTaskError;
+    type Warning = Infallible;
+
+    fn decompose(self) -> Result<Vec<Problem>, Self::Warning, Self::Error> {
+        self.ensure_program_tightness(&self.left)?;
+        self.ensure_program_tightness(&self.right)?;
+
+        let version = match self.formula_representation {
+            FormulaRepresentation::TauStarV1 | FormulaRepresentation::Shorthand => Original,
+            FormulaRepresentation::TauStarV2 => AbstractGringoCompliant,
+        };
+        let use_shorthand = matches!(
+            self.formula_representation,
+            FormulaRepresentation::Shorthand
+        );
+
+        let fact_rules = self.fact_rules();
+
+        let complete = |mut program: asp::Program| {
+            program.rules.extend(fact_rules.iter().cloned());
+            let theory = if use_shorthand {
+                if let Some(rule) = shorthand::unsupported_rule(&program) {
+                    return Err(UniformEquivalenceTaskError::OutsideShorthandFragment(
+                        rule.clone(),
+                    ));
+                }
+                shorthand::completable_shorthand(program)
+            } else {
+                tau_star::tau_star(program, version)
+            };
+            Ok(completion(theory).expect("the translation did not create a completable theory"))
+        };
+
+        let mut left = complete(self.left)?;
+        let mut right = complete(self.right)?;
+
+        if self.simplify {
+            left = crate::simplifying::fol::ht::simplify(left);
+            right = crate::simplifying::fol::ht::simplify(right);
+        }
+
+        if self.break_equivalences {
+            left = crate::breaking::fol::ht::break_equivalences_theory(left);
+            right = crate::breaking::fol::ht::break_equivalences_theory(right);
+        }
+
+        let mut problems = Vec::new();
+        if matches!(
+            self.direction,
+            fol::Direction::Universal | fol::Direction::Forward
+        ) {
+            problems.push(
+                Problem::with_name("forward", Interpretation::Standard)
+                    .add_theory(left.clone(), |i, formula| AnnotatedFormula {
+                        name: format!("left_{i}"),
+                        role: Role::Axiom,
+                        formula,
+                        formula_type: FormulaType::Tff,
+                    })
+                    .add_theory(right.clone(), |i, formula| AnnotatedFormula {
+                        name: format!("right_{i}"),
+                        role: Role::Conjecture,
+                        formula,
+                        formula_type: FormulaType::Tff,
+                    })
+                    .rename_conflicting_symbols(),
+            );
+        }
+        if matches!(
+            self.direction,
+            fol::Direction::Universal | fol::Direction::Backward
+        ) {
+            problems.push(
+                Problem::with_name("backward", Interpretation::Standard)
+                    .add_theory(right, |i, formula| AnnotatedFormula {
+                        name: format!("right_{i}"),
+                        role: Role::Axiom,
+                        formula,
+                        formula_type: FormulaType::Tff,
+                    })
+                    .add_theory(left, |i, formula| AnnotatedFormula {
+                        name: format!("left_{i}"),
+                        role: Role::Conjecture,
+                        formula,
+                        formula_type: FormulaType::Tff,
+                    })
+                    .rename_conflicting_symbols(),
+            );
+        }
+
+        Ok(WithWarnings::flawless(
+            problems
+                .into_iter()
+                .flat_map(|p: Problem| match self.task_decomposition {
+                    TaskDecomposition::Independent => p.decompose_independent(),
+                    TaskDecomposition::Sequential => p.decompose_sequential(),
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{UniformEquivalenceTask, UniformEquivalenceTaskError},
+        crate::{
+            command_line::arguments::{FormulaRepresentation, TaskDecomposition},
+            syntax_tree::{asp, fol},
+            verifying::task::Task as _,
+        },
+    };
+
+    #[test]
+    fn fact_rules() {
+        let task = UniformEquivalenceTask {
+            left: "p(X) :- q(X). p_fact.".parse().unwrap(),
+            right: "p(X) :- q(X), not r.".parse().unwrap(),
+            formula_representation: FormulaRepresentation::default(),
+            task_decomposition: TaskDecomposition::default(),
+            direction: fol::Direction::default(),
+            simplify: true,
+            break_equivalences: true,
+        };
+
+        let rules = asp::Program {
+            rules: task.fact_rules(),
+        };
+        let target: asp::Program =
+            "p(X1) :- p_fact1(X1). q(X1) :- q_fact(X1). p_fact :- p_fact_fact. r :- r_fact."
+                .parse()
+                .unwrap();
+
+        assert_eq!(rules, target);
+    }
+
+    #[test]
+    fn shorthand() {
+        let task = |left: &str| UniformEquivalenceTask {
+            left: left.parse().unwrap(),
+            right: "p(X) :- q(X), not r.".parse().unwrap(),
+            formula_representation: FormulaRepresentation::Shorthand,
+            task_decomposition: TaskDecomposition::default(),
+            direction: fol::Direction::default(),
+            simplify: true,
+            break_equivalences: true,
+        };
+
+        assert!(task("p(X) :- q(X).").decompose().is_ok());
+        assert!(matches!(
+            task("p(X) :- q(Y) : r(X, Y).").decompose(),
+            Err(UniformEquivalenceTaskError::OutsideShorthandFragment(_))
+        ));
+    }
+}
@@ -0,0 +1,249 @@
+use {assert_cmd::Command, std::path::Path};
+
+#[test]
+fn derive_order() {
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join("order");
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("derive")
+        .arg("res/examples/derivation/order/order.po")
+        .arg("res/examples/derivation/order/order.ug")
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(records)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("> Proving outline_1_0_0 ended with a SZS status"));
+    assert!(stdout.contains("> Success! Anthem proved every lemma."));
+}
+
+/// Run `derive` on the order example with the outcomes recorded in the given directory
+fn derive_order_with(records: &str, arguments: &[&str]) -> (i32, String, String) {
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join(records);
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("derive")
+        .arg("res/examples/derivation/order/order.po")
+        .arg("res/examples/derivation/order/order.ug")
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(records)
+        .args(arguments)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn derive_consistency_check() {
+    let (code, stdout, _) = derive_order_with("consistent", &["--check-consistency"]);
+    assert_eq!(code, 0);
+    assert!(stdout.contains("> Checking outline_1_0_0_consistency..."));
+    assert!(stdout.contains("> Success! Anthem proved every lemma."));
+
+    let (code, stdout, stderr) = derive_order_with("contradictory", &["--check-consistency"]);
+    assert_eq!(code, 6);
+    assert!(!stdout.contains("> Proving outline_0_0_0 "), "{stdout}");
+    assert!(
+        stderr.contains("the axioms of the following problems are contradictory: outline_1_0_0"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn derive_contradictory_axioms() {
+    let (code, stdout, _) = derive_order_with("contradictory_axioms", &[]);
+    assert_eq!(code, 6);
+    assert!(stdout.contains("Error: the axioms are contradictory"));
+    assert!(stdout.contains("> Failure! Anthem was unable to prove every lemma."));
+}
+
+#[test]
+fn derive_blocked_lemma() {
+    let (code, stdout, _) = derive_order_with("blocked", &[]);
+    assert_eq!(code, 3);
+    assert!(stdout.contains("Status: GaveUp"), "{stdout}");
+    assert!(
+        stdout.contains(
+            "> Skipping outline_1_0_0, which depends on unproven problems: outline_0_0_0"
+        ),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("> Proving outline_1_0_0..."), "{stdout}");
+
+    let (code, stdout, _) = derive_order_with("blocked", &["--output", "json"]);
+    assert_eq!(code, 3);
+    assert!(
+        stdout.contains(
+            r#"{"type":"blocked","name":"outline_1_0_0","dependencies":["outline_0_0_0"]}"#
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains(r#""blocked":1"#), "{stdout}");
+}
+
+#[test]
+fn derive_cache() {
+    let cache = std::env::temp_dir()
+        .join(format!("anthem-ui-{}-derive_cache", std::process::id()))
+        .join("cache.json");
+    let cache = cache.to_str().unwrap();
+
+    let (code, stdout, _) = derive_order_with("order", &["--cache", cache]);
+    assert_eq!(code, 0);
+    assert!(!stdout.contains("(cached)"), "{stdout}");
+
+    // Problems found proven in the cache are not passed to the prover, so no records are needed
+    let (code, stdout, _) = derive_order_with("missing", &["--cache", cache]);
+    assert_eq!(code, 0, "{stdout}");
+    assert!(stdout
+        .contains("> Proving outline_0_0_0 ended with a SZS status\nStatus: Theorem (cached)"));
+    assert!(stdout
+        .contains("> Proving outline_1_0_0 ended with a SZS status\nStatus: Theorem (cached)"));
+    assert!(stdout.contains("> Success! Anthem proved every lemma."));
+
+    // A problem that has to be proven again invalidates the cached outcomes of its dependents
+    let mut entries: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(cache).unwrap()).unwrap();
+    for entry in entries.as_object_mut().unwrap().values_mut() {
+        if entry["name"] == "outline_0_0_0" {
+            entry["status"] = "GaveUp".into();
+        }
+    }
+    std::fs::write(cache, entries.to_string()).unwrap();
+    let (code, stdout, _) = derive_order_with("order", &["--cache", cache]);
+    assert_eq!(code, 0, "{stdout}");
+    assert!(!stdout.contains("(cached)"), "{stdout}");
+
+    std::fs::remove_dir_all(Path::new(cache).parent().unwrap()).unwrap();
+}
+
+#[test]
+fn derive_directions() {
+    let outline = std::env::temp_dir().join(format!(
+        "anthem-ui-{}-derive_directions.po",
+        std::process::id()
+    ));
+    std::fs::write(
+        &outline,
+        "lemma(forward)[first]: forall X$ V$ (tp(X$) and tp(V$) -> not tp(V$)) -> forall X$ (not tp(X$)).\n\
+         lemma(backward)[second]: forall X$ Y$ V$ ((tp(X$) and tp(Y$)) and tp(V$) -> not tp(V$)) -> forall X$ Y$ (not (tp(X$) and tp(Y$))).\n\
+         lemma[third]: forall X$ (tp(X$) -> tp(X$)).\n",
+    )
+    .unwrap();
+
+    let derive = |direction: &str| {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("derive")
+            .arg(&outline)
+            .arg("res/examples/derivation/order/order.ug")
+            .arg("--no-timing")
+            .arg("--replay")
+            .arg(
+                Path::new(file!())
+                    .parent()
+                    .unwrap()
+                    .join("records")
+                    .join("directions"),
+            )
+            .arg("--direction")
+            .arg(direction)
+            .env("RUST_BACKTRACE", "0")
+            .output()
+            .unwrap();
+        (
+            output.status.code().unwrap(),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    };
+
+    let (code, stdout) = derive("universal");
+    assert_eq!(code, 0, "{stdout}");
+    assert!(!stdout.contains("WARNING"), "{stdout}");
+    assert!(!stdout.contains("is ignored"), "{stdout}");
+    for name in [
+        "forward_outline_0_0_0",
+        "forward_outline_1_0_0",
+        "backward_outline_0_0_0",
+        "backward_outline_1_0_0",
+    ] {
+        assert!(
+            stdout.contains(&format!("> Proving {name} ended with a SZS status")),
+            "{stdout}"
+        );
+    }
+
+    let (code, stdout) = derive("forward");
+    assert_eq!(code, 0, "{stdout}");
+    assert!(
+        stdout.contains("> Proving forward_outline_1_0_0..."),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("backward_outline"), "{stdout}");
+    assert!(
+        stdout.contains(
+            "the following formula is ignored in the forward direction of the derivation due to its annotated direction: lemma(backward)[second]"
+        ),
+        "{stdout}"
+    );
+
+    std::fs::remove_file(outline).unwrap();
+}
+
+#[test]
+fn derive_positional_files() {
+    let directory = std::env::temp_dir().join(format!(
+        "anthem-ui-{}-derive_positional_files",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&directory).unwrap();
+    let outline = directory.join("outline.txt");
+    let user_guide = directory.join("guide.txt");
+    std::fs::copy("res/examples/derivation/order/order.po", &outline).unwrap();
+    std::fs::copy("res/examples/derivation/order/order.ug", &user_guide).unwrap();
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("derive")
+        .arg(&outline)
+        .arg(&user_guide)
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(
+            Path::new(file!())
+                .parent()
+                .unwrap()
+                .join("records")
+                .join("order"),
+        )
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(!stdout.contains("WARNING"), "{stdout}");
+    assert!(stdout.contains("> Success! Anthem proved every lemma."));
+}
@@ -0,0 +1,34 @@
+use assert_cmd::Command;
+
+/// Run `evaluate` on the primes specification, return the exit code and stdout
+fn evaluate(structure: &str) -> (i32, String) {
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("evaluate")
+        .args(["-c", "a=2", "-c", "b=5"])
+        .arg("tests/ui/evaluate/inputs/primes.spec")
+        .arg(format!("tests/ui/evaluate/inputs/{structure}"))
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn evaluate_primes() {
+    let (code, stdout) = evaluate("primes.lp");
+    assert_eq!(code, 0);
+    assert!(stdout.contains("satisfies the specification"), "{stdout}");
+
+    let (code, stdout) = evaluate("wrong.lp");
+    assert_eq!(code, 7);
+    assert!(
+        stdout.contains("violates the following formulas"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("spec: forall X (prime(X) <->"), "{stdout}");
+}
@@ -1 +1,6 @@
+mod derive;
+mod evaluate;
+mod prove;
+mod solve;
 mod translate;
+mod verify;
@@ -0,0 +1,93 @@
+use {assert_cmd::Command, std::path::Path};
+
+/// Run a proof session on the order example with the given commands and arguments, return the exit code and stdout
+fn prove(commands: &str, arguments: &[&str]) -> (i32, String) {
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join("order");
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("prove")
+        .arg("res/examples/derivation/order/order.ug")
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(records)
+        .args(arguments)
+        .env("RUST_BACKTRACE", "0")
+        .write_stdin(commands)
+        .output()
+        .unwrap();
+
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn prove_session() {
+    let outline = std::env::temp_dir()
+        .join(format!("anthem-ui-{}-prove_session.po", std::process::id()))
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let (code, stdout) = prove(
+        &format!(
+            "add lemma[first]: forall X$ V$ (tp(X$) and tp(V$) -> not tp(V$)) -> forall X$ (not tp(X$)).\n\
+             add lemma[second]: forall X$ (tp(X$) -> tp(X$)).\n\
+             try forall X (hp(X) -> tp(X))\n\
+             steps\n\
+             save {outline}\n\
+             quit\n\
+             steps\n"
+        ),
+        &[],
+    );
+
+    // A rejected step makes the session fail, but does not end it
+    assert_eq!(code, 3, "{stdout}");
+    assert!(stdout.contains("> Proving step_0_0..."), "{stdout}");
+    assert!(stdout.contains("> Accepted lemma[first]: "), "{stdout}");
+    assert!(stdout.contains("> Rejected lemma[second]: "), "{stdout}");
+    assert!(
+        stdout.contains("> Anthem proved the conjecture."),
+        "{stdout}"
+    );
+    assert_eq!(stdout.matches("0: lemma[first]: ").count(), 1, "{stdout}");
+    assert!(!stdout.contains("1: lemma[second]"), "{stdout}");
+
+    let saved = std::fs::read_to_string(&outline).unwrap();
+    assert!(saved.starts_with("lemma[first]: "), "{saved}");
+    assert_eq!(saved.lines().count(), 1, "{saved}");
+
+    // The saved proof outline is accepted at the start of the next session
+    let (code, stdout) = prove("steps\n", &["--proof-outline", &outline]);
+    assert_eq!(code, 0, "{stdout}");
+    assert!(stdout.contains("0: lemma[first]: "), "{stdout}");
+    assert!(stdout.contains(" (unchecked)"), "{stdout}");
+
+    std::fs::remove_file(outline).unwrap();
+}
+
+#[test]
+fn prove_interactive_session() {
+    let (code, stdout) = prove("frobnicate\nqed\nhelp\n", &["--interactive"]);
+    assert_eq!(code, 0, "{stdout}");
+    assert!(stdout.contains("anthem> "), "{stdout}");
+    assert!(
+        stdout.contains("Error: unknown command `frobnicate`"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Error: the session has no conclusions to prove"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("time-limit <seconds>"), "{stdout}");
+
+    let (code, _) = prove("frobnicate\nhelp\n", &[]);
+    assert_eq!(code, 6);
+}
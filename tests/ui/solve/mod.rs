@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+
+/// Run `solve` on the coloring of a triangle, return the exit code and stdout
+fn solve(arguments: &[&str]) -> (i32, String) {
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("solve")
+        .args(arguments)
+        .arg("tests/ui/solve/inputs/coloring.lp")
+        .arg("tests/ui/solve/inputs/triangle.lp")
+        .output()
+        .unwrap();
+
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn solve_coloring() {
+    let (code, stdout) = solve(&["-c", "k=3", "--models", "0"]);
+    assert_eq!(code, 0);
+    assert_eq!(stdout.matches("Answer:").count(), 6);
+    assert!(stdout.contains("SATISFIABLE\n\nModels       : 6\n"));
+    assert!(stdout.contains(
+        "color(1,1) color(2,2) color(3,3) colored(1) colored(2) colored(3) edge(1,2) edge(1,3) edge(2,3) vertex(1) vertex(2) vertex(3)"
+    ));
+
+    let (code, stdout) = solve(&["-c", "k=3"]);
+    assert_eq!(code, 0);
+    assert!(stdout.contains("Models       : 1+"));
+
+    let (code, stdout) = solve(&["-c", "k=2"]);
+    assert_eq!(code, 0);
+    assert!(stdout.starts_with("UNSATISFIABLE\n"));
+}
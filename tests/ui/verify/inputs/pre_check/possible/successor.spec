@@ -0,0 +1,2 @@
+spec: forall X (q(X) <-> p(X)).
+spec: forall N$i exists M$i (M$i > N$i).
@@ -0,0 +1,2 @@
+spec: q <-> t or r.
+spec: p <-> #true.
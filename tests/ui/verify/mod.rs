@@ -0,0 +1,687 @@
+use {assert_cmd::Command, std::path::Path};
+
+/// Run `verify` with the prover outcomes recorded in `records`, return the exit code and stdout
+fn verify(equivalence: &str, files: &[&str], records: &str) -> (i32, String) {
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join(records);
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg(equivalence)
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(records)
+        .args(files)
+        .output()
+        .unwrap();
+
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn verify_external_equivalence_propositional() {
+    let (code, stdout) = verify(
+        "external",
+        &["res/examples/external_equivalence/trivial/propositional"],
+        "propositional",
+    );
+
+    assert_eq!(code, 0);
+    assert!(stdout.contains("> Proving forward_problem_0 ended with a SZS status"));
+    assert!(stdout.contains("> Success! Anthem found a proof of equivalence."));
+}
+
+#[test]
+fn verify_external_equivalence_primes() {
+    let (code, stdout) = verify(
+        "external",
+        &["res/examples/external_equivalence/primes/simple"],
+        "primes",
+    );
+
+    assert_eq!(code, 0);
+    assert!(stdout.contains("> Proving backward_outline_0_0 ended with a SZS status"));
+    assert!(stdout.contains("> Success! Anthem found a proof of equivalence."));
+}
+
+#[test]
+fn verify_strong_equivalence_successor() {
+    let (code, stdout) = verify(
+        "strong",
+        &[
+            "res/examples/strong_equivalence/successor/successor.1.lp",
+            "res/examples/strong_equivalence/successor/successor.2.lp",
+        ],
+        "successor",
+    );
+
+    assert_eq!(code, 0);
+    assert!(stdout.contains("> Success! Anthem found a proof of equivalence."));
+}
+
+#[test]
+fn verify_external_equivalence_timeout() {
+    let (code, stdout) = verify(
+        "external",
+        &["res/examples/external_equivalence/trivial/propositional"],
+        "propositional_timeout",
+    );
+
+    assert_eq!(code, 4);
+    assert!(stdout.contains("Status: Timeout"));
+    assert!(stdout.contains("> Failure! Anthem was unable to find a proof of equivalence."));
+}
+
+#[test]
+fn verify_external_equivalence_gave_up() {
+    let (code, stdout) = verify(
+        "external",
+        &["res/examples/external_equivalence/trivial/propositional"],
+        "propositional_gave_up",
+    );
+
+    assert_eq!(code, 3);
+    assert!(stdout.contains("Status: GaveUp"));
+}
+
+#[test]
+fn verify_missing_record() {
+    let (code, stdout) = verify(
+        "external",
+        &["res/examples/external_equivalence/trivial/propositional"],
+        "successor",
+    );
+
+    assert_eq!(code, 5);
+    assert!(stdout.contains("Error: no outcome was recorded for problem `forward_problem_0`"));
+}
+
+#[test]
+fn verify_invalid_input() {
+    let (code, _) = verify(
+        "external",
+        &["tests/ui/verify/inputs/overlapping_io"],
+        "propositional",
+    );
+
+    assert_eq!(code, 6);
+}
+
+#[test]
+fn verify_non_tight_external_equivalence() {
+    let problems = std::env::temp_dir().join(format!(
+        "anthem-ui-{}-verify_non_tight_external_equivalence",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&problems).unwrap();
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--no-proof-search")
+        .arg("--save-problems")
+        .arg(&problems)
+        .arg("tests/ui/verify/inputs/transitive_closure")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let problem = std::fs::read_to_string(problems.join("forward_problem_0.p")).unwrap();
+    assert!(problem.contains("tc_level"));
+
+    std::fs::remove_dir_all(problems).unwrap();
+}
+
+#[test]
+fn verify_loop_through_conditional_literal() {
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--no-proof-search")
+        .arg("tests/ui/verify/inputs/conditional_loop")
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(6));
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("does not support positive loops through conditional literals"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("not tight"), "{stderr}");
+}
+
+#[test]
+fn verify_uniform_equivalence() {
+    let problems = std::env::temp_dir().join(format!(
+        "anthem-ui-{}-verify_uniform_equivalence",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&problems).unwrap();
+
+    for (files, code) in [
+        ("tests/ui/verify/inputs/uniform", 0),
+        ("tests/ui/verify/inputs/transitive_closure", 6),
+    ] {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("verify")
+            .arg("--equivalence")
+            .arg("uniform")
+            .arg("--no-proof-search")
+            .arg("--save-problems")
+            .arg(&problems)
+            .arg(files)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(code), "{files}");
+    }
+
+    let problem = std::fs::read_to_string(problems.join("forward_0.p")).unwrap();
+    assert!(problem.contains("b_fact"));
+
+    std::fs::remove_dir_all(problems).unwrap();
+}
+
+#[test]
+fn verify_relativized_strong_equivalence_skips_witness() {
+    let (code, stdout) = verify(
+        "strong",
+        &["tests/ui/verify/inputs/relativized"],
+        "relativized_gave_up",
+    );
+
+    assert_eq!(code, 3);
+    assert!(
+        stdout.contains("> No witness of non-equivalence was searched for, since the claim is relative to the public predicates of the user guide."),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn verify_relativized_strong_equivalence_success() {
+    let (code, stdout) = verify(
+        "strong",
+        &["tests/ui/verify/inputs/relativized"],
+        "relativized",
+    );
+
+    assert_eq!(code, 0);
+    assert!(
+        stdout.contains("> Success! Anthem found a proof of equivalence relative to the public predicates of the user guide {r/1, s/1, p/1}."),
+        "{stdout}"
+    );
+
+    let (code, stdout) = verify(
+        "strong",
+        &["--output", "json", "tests/ui/verify/inputs/relativized"],
+        "relativized",
+    );
+    let summary: serde_json::Value = serde_json::from_str(stdout.lines().last().unwrap()).unwrap();
+
+    assert_eq!(code, 0);
+    assert_eq!(summary["success"], true);
+    assert_eq!(summary["context"], serde_json::json!(["r/1", "s/1", "p/1"]));
+}
+
+#[test]
+fn verify_relativized_strong_equivalence() {
+    let problems = std::env::temp_dir().join(format!(
+        "anthem-ui-{}-verify_relativized_strong_equivalence",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&problems).unwrap();
+
+    for (arguments, code) in [
+        (vec!["tests/ui/verify/inputs/relativized"], 0),
+        (
+            vec![
+                "--context",
+                "edge/2",
+                "tests/ui/verify/inputs/transitive_closure",
+            ],
+            6,
+        ),
+    ] {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("verify")
+            .arg("--equivalence")
+            .arg("strong")
+            .arg("--no-proof-search")
+            .arg("--save-problems")
+            .arg(&problems)
+            .args(&arguments)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(code), "{arguments:?}");
+    }
+
+    let problem = std::fs::read_to_string(problems.join("forward_0.p")).unwrap();
+    assert!(problem.contains("auxiliary_definition_1"));
+    assert!(problem.contains("q_r"));
+
+    std::fs::remove_dir_all(problems).unwrap();
+}
+
+#[test]
+fn verify_projection_of_private_predicates() {
+    for (arguments, code) in [
+        (vec!["tests/ui/verify/inputs/private_recursion/positive"], 0),
+        (
+            vec![
+                "--bypass-tightness",
+                "tests/ui/verify/inputs/private_recursion/positive",
+            ],
+            6,
+        ),
+        (vec!["tests/ui/verify/inputs/private_recursion/negative"], 6),
+    ] {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("verify")
+            .arg("--equivalence")
+            .arg("external")
+            .arg("--no-proof-search")
+            .args(&arguments)
+            .env("RUST_BACKTRACE", "0")
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(code), "{arguments:?}");
+
+        if code != 0 {
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            assert!(stderr.contains("cannot be projected away"), "{stderr}");
+        }
+    }
+}
+
+#[test]
+fn verify_bounded_pre_check() {
+    for (input, code) in [("correct", 0), ("wrong", 7)] {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("verify")
+            .arg("--equivalence")
+            .arg("external")
+            .arg("--pre-check")
+            .arg("bounded")
+            .arg("--no-proof-search")
+            .arg(format!("tests/ui/verify/inputs/pre_check/{input}"))
+            .env("RUST_BACKTRACE", "0")
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(code), "{input}");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        if code == 0 {
+            assert!(stdout.contains("no counterexample"), "{stdout}");
+        } else {
+            assert!(stdout.contains("placeholders: n = 1"), "{stdout}");
+            assert!(stdout.contains("program output: {prime(1)}"), "{stdout}");
+        }
+    }
+}
+
+#[test]
+fn verify_bounded_pre_check_possible_counterexample() {
+    // The specification is violated on small universes, but holds for all integers
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--pre-check")
+        .arg("bounded")
+        .arg("--no-proof-search")
+        .arg("tests/ui/verify/inputs/pre_check/possible")
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("> Possible counterexample!"), "{stdout}");
+    assert!(
+        stdout.contains("violated formula: spec: forall N$i exists M$i (M$i > N$i)"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn verify_strong_equivalence_witness() {
+    for (arguments, code) in [
+        (
+            vec![
+                "tests/ui/verify/inputs/strong_witness/strong_witness.1.lp",
+                "tests/ui/verify/inputs/strong_witness/strong_witness.2.lp",
+            ],
+            7,
+        ),
+        (
+            vec![
+                "tests/ui/verify/inputs/strong_witness/strong_witness.1.lp",
+                "tests/ui/verify/inputs/strong_witness/strong_witness.1.lp",
+            ],
+            0,
+        ),
+    ] {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("verify")
+            .arg("--equivalence")
+            .arg("strong")
+            .arg("--pre-check")
+            .arg("bounded")
+            .arg("--no-proof-search")
+            .args(&arguments)
+            .env("RUST_BACKTRACE", "0")
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(code), "{arguments:?}");
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        if code == 7 {
+            assert!(
+                stdout.contains("HT-interpretation: <{p(1) r(1)}, {p(1) r(1)}>"),
+                "{stdout}"
+            );
+            assert!(stdout.contains("added rules: p(1). r(1)."), "{stdout}");
+        } else {
+            assert!(stdout.contains("no counterexample"), "{stdout}");
+        }
+    }
+}
+
+#[test]
+fn verify_json_output() {
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join("propositional_timeout");
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--output")
+        .arg("json")
+        .arg("--replay")
+        .arg(records)
+        .arg("res/examples/external_equivalence/trivial/propositional")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(4));
+
+    let records: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(records.len(), 10);
+
+    let warning = records.first().unwrap();
+    assert_eq!(warning["type"], "warning");
+    assert!(warning["message"].as_str().unwrap().contains("README.md"));
+
+    let timeout = records
+        .iter()
+        .find(|record| record["name"] == "backward_problem_2")
+        .unwrap();
+    assert_eq!(timeout["type"], "problem");
+    assert_eq!(timeout["prover"], "replay");
+    assert_eq!(timeout["status"], "Timeout");
+    assert_eq!(timeout["conjectures"][0], "p -> #true");
+
+    let summary = records.last().unwrap();
+    assert_eq!(summary["type"], "summary");
+    assert_eq!(summary["success"], false);
+    assert_eq!(summary["problems"], 8);
+    assert_eq!(summary["proven"], 7);
+    assert_eq!(summary["failed"], 0);
+    assert_eq!(summary["timeouts"], 1);
+    assert_eq!(summary["tool_errors"], 0);
+}
+
+#[test]
+fn verify_explicit_file_roles() {
+    let directory = "res/examples/external_equivalence/primes/simple";
+    let (code, stdout) = verify(
+        "external",
+        &[
+            "--spec",
+            &format!("{directory}/primes.1.lp"),
+            "--program",
+            &format!("{directory}/primes.2.lp"),
+            "--user-guide",
+            &format!("{directory}/primes.ug"),
+            "--proof-outline",
+            &format!("{directory}/primes.po"),
+        ],
+        "primes",
+    );
+
+    assert_eq!(code, 0);
+    assert!(!stdout.contains("is not used"));
+    assert!(stdout.contains("> Proving backward_outline_0_0 ended with a SZS status"));
+    assert!(stdout.contains("> Success! Anthem found a proof of equivalence."));
+}
+
+#[test]
+fn verify_project_configuration() {
+    let project = Path::new(file!()).parent().unwrap().join("inputs/project");
+
+    for (profile, code, message) in [
+        (None, 0, "> Success! Anthem found a proof of equivalence."),
+        (
+            Some("timeout"),
+            4,
+            "> Failure! Anthem was unable to find a proof of equivalence.",
+        ),
+    ] {
+        let mut command = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        command.arg("verify").arg(&project);
+        if let Some(profile) = profile {
+            command.arg("--profile").arg(profile);
+        }
+
+        let output = command.output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        assert_eq!(output.status.code(), Some(code));
+        assert!(stdout.contains(message));
+    }
+}
+
+#[test]
+fn verify_project_configuration_override() {
+    let project = Path::new(file!()).parent().unwrap().join("inputs/project");
+
+    // The configuration sets `no-timing = true`
+    for (arguments, timing) in [(&[][..], false), (&["--no-timing=false"], true)] {
+        let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+            .unwrap()
+            .arg("verify")
+            .arg(&project)
+            .args(arguments)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(stdout.contains(" ms)"), timing, "{stdout}");
+    }
+}
+
+#[test]
+fn verify_shorthand_formula_representation() {
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join("propositional");
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--formula-representation")
+        .arg("shorthand")
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(records)
+        .arg("res/examples/external_equivalence/trivial/propositional")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("> Success! Anthem found a proof of equivalence."));
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--formula-representation")
+        .arg("shorthand")
+        .arg("--no-proof-search")
+        .arg("tests/ui/verify/inputs/pre_check/correct")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(6));
+    assert!(String::from_utf8(output.stderr).unwrap().contains(
+        "the following rule falls outside the fragment supported by the shorthand formula representation: composite(I * J) :- I = 2..n, J = 2..n."
+    ));
+}
+
+#[test]
+fn verify_suggest_lemmas() {
+    let directory = "res/examples/external_equivalence/primes/simple";
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join("suggestions");
+    let outline = std::env::temp_dir()
+        .join(format!(
+            "anthem-ui-{}-verify_suggest_lemmas",
+            std::process::id()
+        ))
+        .join("suggestions.po");
+    std::fs::create_dir_all(outline.parent().unwrap()).unwrap();
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(records)
+        .arg("--suggest-lemmas")
+        .arg(&outline)
+        .arg("--spec")
+        .arg(format!("{directory}/primes.1.lp"))
+        .arg("--program")
+        .arg(format!("{directory}/primes.2.lp"))
+        .arg("--user-guide")
+        .arg(format!("{directory}/primes.ug"))
+        .output()
+        .unwrap();
+
+    // The suggestions do not change the outcome of the proof search
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("> Searching for lemmas helping with backward_problem_1..."));
+    assert!(!stdout.contains("typing_prime_1_0"), "{stdout}");
+    assert!(stdout.contains("> The proven lemmas help to prove backward_problem_1."));
+    assert!(stdout.contains("> Saved 2 suggested lemmas to"));
+
+    let suggestions = std::fs::read_to_string(&outline).unwrap();
+    assert_eq!(
+        suggestions,
+        "lemma(backward)[typing_composite_1_0]: forall V1 (composite(V1) -> exists N$i (V1 = N$i)).\n\
+         lemma(backward)[restricted_definition_of_composite_1]: forall N$i (composite(N$i) <-> exists I1$i J1$i (N$i = I1$i * J1$i and I1$i > 1 and J1$i > 1)).\n"
+    );
+
+    // The suggested lemmas are a valid proof outline
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--no-proof-search")
+        .arg("--proof-outline")
+        .arg(&outline)
+        .arg(directory)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+
+    std::fs::remove_dir_all(outline.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn verify_typing_lemmas() {
+    let records = Path::new(file!())
+        .parent()
+        .unwrap()
+        .join("records")
+        .join("typing");
+
+    let output = Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .arg("verify")
+        .arg("--equivalence")
+        .arg("external")
+        .arg("--typing-lemmas")
+        .arg("--no-timing")
+        .arg("--replay")
+        .arg(records)
+        .arg("res/examples/external_equivalence/primes/simple")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // The typing lemmas are proven before the lemmas of the proof outline and become axioms of the later problems
+    assert!(
+        stdout.contains("> Proving forward_outline_0_0...\n"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("Conjectures:\n    forall V1 (composite(V1) -> exists N$i (V1 = N$i))"));
+    assert!(stdout.contains("    forall V1 (prime(V1) -> exists N$i (V1 = N$i))"));
+    assert!(stdout.contains("    forall V1 (composite_p(V1) -> exists N$i (V1 = N$i))"));
+    assert!(stdout.contains("> Proving backward_outline_2_0 ended with a SZS status"));
+    assert!(stdout.contains("> Success! Anthem found a proof of equivalence."));
+}